@@ -8,7 +8,7 @@
 //! Since request executions are driven through futures, the agent also acts as
 //! a specialized task executor for tasks related to requests.
 
-use crate::{error::Error, handler::RequestHandler, task::WakerExt};
+use crate::{error::{Error, ErrorKind}, handler::RequestHandler, task::WakerExt};
 use async_channel::{Receiver, Sender};
 use crossbeam_utils::{atomic::AtomicCell, sync::WaitGroup};
 use curl::multi::{Events, Multi, Socket, SocketEvents};
@@ -38,6 +38,7 @@ pub(crate) struct AgentBuilder {
     max_connections: usize,
     max_connections_per_host: usize,
     connection_cache_size: usize,
+    thread_name: Option<String>,
 }
 
 impl AgentBuilder {
@@ -56,6 +57,11 @@ impl AgentBuilder {
         self
     }
 
+    pub(crate) fn thread_name(mut self, name: String) -> Self {
+        self.thread_name = Some(name);
+        self
+    }
+
     /// Spawn a new agent using the configuration in this builder and return a
     /// handle for communicating with the agent.
     pub(crate) fn spawn(&self) -> io::Result<Handle> {
@@ -136,9 +142,14 @@ impl AgentBuilder {
         let handle = Handle {
             message_tx,
             waker,
+            closed: AtomicCell::new(false),
             join_handle: Mutex::new(Some(
                 thread::Builder::new()
-                    .name(format!("isahc-agent-{}", id))
+                    .name(
+                        self.thread_name
+                            .clone()
+                            .unwrap_or_else(|| format!("isahc-agent-{}", id)),
+                    )
                     .spawn(thread_main)?,
             )),
         };
@@ -162,6 +173,9 @@ pub(crate) struct Handle {
     /// A waker that can wake up the agent thread while it is polling.
     waker: Waker,
 
+    /// Set once the agent has been asked to shut down gracefully.
+    closed: AtomicCell<bool>,
+
     /// A join handle for the agent thread.
     join_handle: Mutex<Option<thread::JoinHandle<Result<(), Error>>>>,
 }
@@ -187,6 +201,10 @@ struct AgentContext {
     /// Indicates if the thread has been requested to stop.
     close_requested: bool,
 
+    /// If set, the agent is draining and will stop once all active requests
+    /// complete or this deadline passes, whichever happens first.
+    drain_deadline: Option<Instant>,
+
     /// A waker that can wake up the agent thread while it is polling.
     waker: Waker,
 
@@ -206,6 +224,10 @@ enum Message {
     /// Requests the agent to close.
     Close,
 
+    /// Requests the agent to stop accepting new requests and close once all
+    /// active requests complete, or the given deadline passes.
+    Drain(Instant),
+
     /// Begin executing a new request.
     Execute(EasyHandle),
 
@@ -216,6 +238,10 @@ enum Message {
     /// Request to resume writing the response body for the request with the
     /// given ID.
     UnpauseWrite(usize),
+
+    /// Request to abort the request with the given ID, since it has been
+    /// cancelled.
+    Cancel(usize),
 }
 
 #[derive(Debug)]
@@ -228,10 +254,31 @@ enum JoinResult {
 
 impl Handle {
     /// Begin executing a request with this agent.
-    pub(crate) fn submit_request(&self, request: EasyHandle) -> Result<(), Error> {
+    pub(crate) fn submit_request(&self, mut request: EasyHandle) -> Result<(), Error> {
+        if self.closed.load() {
+            return Err(ErrorKind::ClientClosed.into());
+        }
+
+        request.get_mut().submitted_at = Some(Instant::now());
+
         self.send_message(Message::Execute(request))
     }
 
+    /// Begin shutting down the agent gracefully. Use [`Handle::join`] to wait
+    /// for the agent to exit.
+    ///
+    /// New requests are rejected immediately, while active requests are given
+    /// until the deadline passes to complete before they are aborted.
+    pub(crate) fn close(&self, deadline: Instant) {
+        if self.closed.swap(true) {
+            return;
+        }
+
+        if self.send_message(Message::Drain(deadline)).is_err() {
+            tracing::error!("agent thread terminated prematurely");
+        }
+    }
+
     /// Send a message to the agent thread.
     ///
     /// If the agent is not connected, an error is returned.
@@ -242,6 +289,8 @@ impl Handle {
                 self.waker.wake_by_ref();
                 Ok(())
             }
+            // The agent was shut down while this message was being sent.
+            Err(_) if self.closed.load() => Err(ErrorKind::ClientClosed.into()),
             Err(_) => match self.try_join() {
                 JoinResult::Err(e) => panic!("agent thread terminated with error: {:?}", e),
                 JoinResult::Panic => panic!("agent thread panicked"),
@@ -263,22 +312,32 @@ impl Handle {
             JoinResult::AlreadyJoined
         }
     }
+
+    /// Wait for the agent thread to shut down.
+    pub(crate) fn join(&self) {
+        match self.try_join() {
+            JoinResult::Ok => tracing::trace!("agent thread joined cleanly"),
+            JoinResult::Err(e) => tracing::error!("agent thread terminated with error: {}", e),
+            JoinResult::Panic => tracing::error!("agent thread panicked"),
+            _ => {}
+        }
+    }
 }
 
 impl Drop for Handle {
     fn drop(&mut self) {
+        // The agent has already been shut down gracefully.
+        if self.closed.load() {
+            return;
+        }
+
         // Request the agent thread to shut down.
         if self.send_message(Message::Close).is_err() {
             tracing::error!("agent thread terminated prematurely");
         }
 
         // Wait for the agent thread to shut down before continuing.
-        match self.try_join() {
-            JoinResult::Ok => tracing::trace!("agent thread joined cleanly"),
-            JoinResult::Err(e) => tracing::error!("agent thread terminated with error: {}", e),
-            JoinResult::Panic => tracing::error!("agent thread panicked"),
-            _ => {}
-        }
+        self.join();
     }
 }
 
@@ -321,6 +380,7 @@ impl AgentContext {
             message_rx,
             requests: Slab::new(),
             close_requested: false,
+            drain_deadline: None,
             waker: selector.waker(),
             selector,
             timer,
@@ -361,6 +421,17 @@ impl AgentContext {
                         }
                     })
             },
+            {
+                let tx = self.message_tx.clone();
+
+                self.waker
+                    .chain(move |inner| match tx.try_send(Message::Cancel(id)) {
+                        Ok(()) => inner.wake_by_ref(),
+                        Err(_) => {
+                            tracing::debug!(id, "agent went away while cancelling request")
+                        }
+                    })
+            },
         );
 
         // Register the request with curl.
@@ -387,14 +458,49 @@ impl AgentContext {
         Ok(())
     }
 
+    /// Abort any requests that have run past their deadline, including those
+    /// still waiting for a connection.
+    fn abort_expired_requests(&mut self) -> Result<(), Error> {
+        let now = Instant::now();
+
+        let expired = self
+            .requests
+            .iter()
+            .filter(|(_, request)| {
+                matches!(request.get_ref().deadline, Some(deadline) if now >= deadline)
+            })
+            .map(|(token, _)| token)
+            .collect::<Vec<_>>();
+
+        for token in expired {
+            tracing::debug!(id = token, "aborting request that exceeded its total timeout");
+
+            let handle = self.requests.remove(token);
+            let mut handle = self.multi.remove2(handle).map_err(Error::from_any)?;
+            let error = handle.get_ref().deadline_error();
+
+            handle.get_mut().set_result(Err(error));
+        }
+
+        Ok(())
+    }
+
+    /// Get the earliest deadline of all active requests, if any.
+    fn next_request_deadline(&self) -> Option<Instant> {
+        self.requests
+            .iter()
+            .filter_map(|(_, request)| request.get_ref().deadline)
+            .min()
+    }
+
     /// Polls the message channel for new messages from any agent handles.
     ///
-    /// If there are no active requests right now, this function will block
-    /// until a message is received.
+    /// If there are no active requests right now and the agent is not
+    /// draining, this function will block until a message is received.
     #[tracing::instrument(level = "trace", skip(self))]
     fn poll_messages(&mut self) -> Result<(), Error> {
         while !self.close_requested {
-            if self.requests.is_empty() {
+            if self.requests.is_empty() && self.drain_deadline.is_none() {
                 match block_on(self.message_rx.recv()) {
                     Ok(message) => self.handle_message(message)?,
                     _ => {
@@ -425,6 +531,16 @@ impl AgentContext {
 
         match message {
             Message::Close => self.close_requested = true,
+            Message::Drain(deadline) => {
+                tracing::debug!(active = self.requests.len(), "draining active requests");
+                self.drain_deadline = Some(deadline);
+            }
+            Message::Execute(mut request) if self.drain_deadline.is_some() => {
+                // A request submitted just before the agent started draining.
+                request
+                    .get_mut()
+                    .set_result(Err(ErrorKind::ClientClosed.into()));
+            }
             Message::Execute(request) => self.begin_request(request)?,
             Message::UnpauseRead(token) => {
                 if let Some(request) = self.requests.get(token) {
@@ -464,6 +580,23 @@ impl AgentContext {
                     );
                 }
             }
+            Message::Cancel(token) => {
+                // The request may have completed already and its ID been
+                // reused by another request, so make sure the request with
+                // this ID is actually the one that was cancelled.
+                let cancelled = match self.requests.get(token) {
+                    Some(request) => request.get_ref().is_cancelled(),
+                    None => false,
+                };
+
+                if cancelled {
+                    tracing::debug!(id = token, "aborting cancelled request");
+                    self.complete_request(
+                        token,
+                        Err(curl::Error::new(curl_sys::CURLE_ABORTED_BY_CALLBACK)),
+                    )?;
+                }
+            }
         }
 
         Ok(())
@@ -481,6 +614,32 @@ impl AgentContext {
                 break;
             }
 
+            if let Some(deadline) = self.drain_deadline {
+                if self.requests.is_empty() {
+                    tracing::debug!("all active requests drained");
+                    break;
+                }
+
+                if Instant::now() >= deadline {
+                    tracing::warn!(
+                        active = self.requests.len(),
+                        "timed out waiting for active requests to drain"
+                    );
+
+                    // Fail the remaining requests with a meaningful error
+                    // instead of leaving them to find the agent gone.
+                    for handle in self.requests.drain() {
+                        if let Ok(mut handle) = self.multi.remove2(handle) {
+                            handle
+                                .get_mut()
+                                .set_result(Err(ErrorKind::ClientClosed.into()));
+                        }
+                    }
+
+                    break;
+                }
+            }
+
             // Block until activity is detected or the timeout passes.
             self.poll()?;
 
@@ -497,6 +656,8 @@ impl AgentContext {
             for (token, result) in multi_messages.drain(..) {
                 self.complete_request(token, result)?;
             }
+
+            self.abort_expired_requests()?;
         }
 
         tracing::debug!("agent shutting down");
@@ -513,7 +674,17 @@ impl AgentContext {
 
         // Get the latest timeout value from curl that we should use, limited to
         // a maximum we chose.
-        let poll_timeout = timeout.map(|t| t.min(WAIT_TIMEOUT)).unwrap_or(WAIT_TIMEOUT);
+        let mut poll_timeout = timeout.map(|t| t.min(WAIT_TIMEOUT)).unwrap_or(WAIT_TIMEOUT);
+
+        // Don't oversleep past the deadline if we are draining.
+        if let Some(deadline) = self.drain_deadline {
+            poll_timeout = poll_timeout.min(deadline.saturating_duration_since(now));
+        }
+
+        // Nor past the deadline of any request.
+        if let Some(deadline) = self.next_request_deadline() {
+            poll_timeout = poll_timeout.min(deadline.saturating_duration_since(now));
+        }
 
         // Block until either an I/O event occurs on a socket, the timeout is
         // reached, or the agent handle interrupts us.
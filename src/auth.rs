@@ -1,7 +1,9 @@
 //! Types for working with HTTP authentication methods.
 
 use crate::config::{proxy::Proxy, request::SetOpt};
+use http::header::{HeaderValue, InvalidHeaderValue};
 use std::{
+    convert::TryFrom,
     fmt,
     ops::{BitOr, BitOrAssign},
 };
@@ -49,6 +51,31 @@ impl fmt::Debug for Credentials {
     }
 }
 
+/// A bearer token sent in the `Authorization` header of outgoing requests.
+#[derive(Clone)]
+pub(crate) struct BearerToken(String);
+
+impl BearerToken {
+    pub(crate) fn new(token: impl Into<String>) -> Self {
+        BearerToken(token.into())
+    }
+
+    /// Get the value of the `Authorization` header for this token. The value
+    /// is marked as sensitive so that it is not logged.
+    pub(crate) fn header_value(&self) -> Result<HeaderValue, InvalidHeaderValue> {
+        let mut value = HeaderValue::try_from(format!("Bearer {}", self.0))?;
+        value.set_sensitive(true);
+        Ok(value)
+    }
+}
+
+// Never print the token itself.
+impl fmt::Debug for BearerToken {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("BearerToken(*****)")
+    }
+}
+
 /// Specifies one or more HTTP authentication schemes to use.
 #[derive(Clone, Debug)]
 pub struct Authentication(u8);
@@ -68,7 +95,7 @@ impl Authentication {
     /// Enable all available authentication schemes.
     pub const fn all() -> Self {
         #[allow(unused_mut)]
-        let mut all = Self::basic().0 | Self::digest().0;
+        let mut all = Self::basic().0 | Self::digest().0 | Self::ntlm().0;
 
         #[cfg(feature = "spnego")]
         {
@@ -96,6 +123,17 @@ impl Authentication {
         Authentication(0b0010)
     }
 
+    /// HTTP NTLM authentication.
+    ///
+    /// NTLM is a proprietary challenge-response protocol used by Microsoft
+    /// servers, and is still common on corporate intranets. Like Digest, the
+    /// handshake requires more than one round trip, which is handled
+    /// transparently. Prefer Negotiate authentication where possible, as NTLM
+    /// is considered insecure.
+    pub const fn ntlm() -> Self {
+        Authentication(0b1000)
+    }
+
     /// HTTP Negotiate (SPNEGO) authentication.
     ///
     /// Negotiate authentication is defined in RFC 4559 and is the most secure
@@ -131,6 +169,10 @@ impl Authentication {
             auth.digest(true);
         }
 
+        if self.contains(Authentication::ntlm()) {
+            auth.ntlm(true);
+        }
+
         #[cfg(feature = "spnego")]
         {
             if self.contains(Authentication::negotiate()) {
@@ -191,7 +233,7 @@ impl SetOpt for Proxy<Authentication> {
 
 #[cfg(test)]
 mod tests {
-    use super::Authentication;
+    use super::{Authentication, BearerToken, Credentials};
 
     #[test]
     fn auth_default() {
@@ -207,6 +249,7 @@ mod tests {
 
         assert!(auth.contains(Authentication::basic()));
         assert!(auth.contains(Authentication::digest()));
+        assert!(auth.contains(Authentication::ntlm()));
     }
 
     #[test]
@@ -221,4 +264,34 @@ mod tests {
         assert!(!auth.contains(Authentication::basic()));
         assert!(auth.contains(Authentication::digest()));
     }
+
+    #[test]
+    fn auth_combined() {
+        let auth = Authentication::digest() | Authentication::ntlm();
+
+        assert!(!auth.contains(Authentication::basic()));
+        assert!(auth.contains(Authentication::digest()));
+        assert!(auth.contains(Authentication::ntlm()));
+    }
+
+    #[test]
+    fn credentials_debug_redacts_password() {
+        let debug = format!("{:?}", Credentials::new("clark", "qwerty"));
+
+        assert!(debug.contains("clark"));
+        assert!(!debug.contains("qwerty"));
+    }
+
+    #[test]
+    fn bearer_token_is_redacted() {
+        let token = BearerToken::new("s3cr3t");
+
+        assert!(!format!("{:?}", token).contains("s3cr3t"));
+
+        let value = token.header_value().unwrap();
+
+        assert_eq!(value, "Bearer s3cr3t");
+        assert!(value.is_sensitive());
+        assert!(!format!("{:?}", value).contains("s3cr3t"));
+    }
 }
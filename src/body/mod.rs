@@ -1,8 +1,13 @@
 //! Provides types for working with request and response bodies.
 
-use futures_lite::io::{AsyncRead, BlockOn};
+use futures_lite::{
+    io::{AsyncRead, BlockOn},
+    ready,
+    Stream,
+};
 use std::{
     borrow::Cow,
+    error::Error as StdError,
     fmt,
     io::{self, Cursor, Read},
     pin::Pin,
@@ -28,7 +33,27 @@ pub use sync::Body;
 /// implements.
 ///
 /// For synchronous requests, use [`Body`] instead.
-pub struct AsyncBody(Inner);
+///
+/// # Reading response bodies
+///
+/// Since [`AsyncBody`] implements [`AsyncRead`], the body of a response can be
+/// streamed into any asynchronous sink or adapter without first being
+/// buffered in memory. Data is handed over as it is received from the
+/// network, and reads may return fewer bytes than requested. If the body is
+/// not read quickly enough, the transfer is paused until there is room in the
+/// internal buffer again, so a slow reader never causes unbounded memory use.
+///
+/// ```no_run
+/// use futures_lite::io::{copy, sink};
+///
+/// # async fn run() -> Result<(), isahc::Error> {
+/// let response = isahc::get_async("https://example.org").await?;
+///
+/// // Stream the response body into some asynchronous writer.
+/// let bytes_copied = copy(response.into_body(), &mut sink()).await?;
+/// # Ok(()) }
+/// ```
+pub struct AsyncBody(Inner, Option<Cow<'static, str>>);
 
 /// All possible body implementations.
 enum Inner {
@@ -48,7 +73,7 @@ impl AsyncBody {
     /// An empty body represents the *absence* of a body, which is semantically
     /// different than the presence of a body of zero length.
     pub const fn empty() -> Self {
-        Self(Inner::Empty)
+        Self(Inner::Empty, None)
     }
 
     /// Create a new body from a potentially static byte buffer.
@@ -76,7 +101,7 @@ impl AsyncBody {
         B: AsRef<[u8]> + 'static,
     {
         castaway::match_type!(bytes, {
-            Cursor<Cow<'static, [u8]>> as bytes => Self(Inner::Buffer(bytes)),
+            Cursor<Cow<'static, [u8]>> as bytes => Self(Inner::Buffer(bytes), None),
             &'static [u8] as bytes => Self::from_static_impl(bytes),
             &'static str as bytes => Self::from_static_impl(bytes.as_bytes()),
             Vec<u8> as bytes => Self::from(bytes),
@@ -87,7 +112,7 @@ impl AsyncBody {
 
     #[inline]
     fn from_static_impl(bytes: &'static [u8]) -> Self {
-        Self(Inner::Buffer(Cursor::new(Cow::Borrowed(bytes))))
+        Self(Inner::Buffer(Cursor::new(Cow::Borrowed(bytes))), None)
     }
 
     /// Create a streaming body that reads from the given reader.
@@ -96,11 +121,27 @@ impl AsyncBody {
     /// [chunked transfer
     /// encoding](https://tools.ietf.org/html/rfc7230#section-4.1) might be used
     /// to send the request.
+    ///
+    /// The reader is polled by the same agent that drives the request, so
+    /// uploading a streaming body never blocks a thread waiting for data.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use isahc::{prelude::*, AsyncBody};
+    ///
+    /// # async fn run(reader: impl futures_lite::AsyncRead + Send + Sync + 'static) -> Result<(), isahc::Error> {
+    /// let response = isahc::put_async(
+    ///     "https://httpbin.org/put",
+    ///     AsyncBody::from_reader(reader),
+    /// ).await?;
+    /// # Ok(()) }
+    /// ```
     pub fn from_reader<R>(read: R) -> Self
     where
         R: AsyncRead + Send + Sync + 'static,
     {
-        Self(Inner::Reader(Box::pin(read), None))
+        Self(Inner::Reader(Box::pin(read), None), None)
     }
 
     /// Create a streaming body with a known length.
@@ -116,7 +157,93 @@ impl AsyncBody {
     where
         R: AsyncRead + Send + Sync + 'static,
     {
-        Self(Inner::Reader(Box::pin(read), Some(length)))
+        Self(Inner::Reader(Box::pin(read), Some(length)), None)
+    }
+
+    /// Create a streaming body that sends each chunk of bytes produced by the
+    /// given stream.
+    ///
+    /// The body will have an unknown length. When used as a request body,
+    /// [chunked transfer
+    /// encoding](https://tools.ietf.org/html/rfc7230#section-4.1) might be used
+    /// to send the request.
+    ///
+    /// If the stream yields an error, the request is aborted and the error is
+    /// returned as the source of an [`Io`](crate::error::ErrorKind::Io) error.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use futures_lite::stream;
+    /// use isahc::{prelude::*, AsyncBody};
+    ///
+    /// # async fn run() -> Result<(), isahc::Error> {
+    /// let chunks = stream::iter(vec![
+    ///     Ok::<_, std::io::Error>("hello "),
+    ///     Ok("world"),
+    /// ]);
+    ///
+    /// let response = isahc::post_async(
+    ///     "https://httpbin.org/post",
+    ///     AsyncBody::from_stream(chunks),
+    /// ).await?;
+    /// # Ok(()) }
+    /// ```
+    pub fn from_stream<S, B, E>(stream: S) -> Self
+    where
+        S: Stream<Item = Result<B, E>> + Send + Sync + 'static,
+        B: AsRef<[u8]> + Send + Sync + 'static,
+        E: Into<Box<dyn StdError + Send + Sync>>,
+    {
+        Self::from_reader(StreamReader {
+            stream: Box::pin(stream),
+            chunk: None,
+        })
+    }
+
+    /// Create a body containing the given value serialized as JSON.
+    ///
+    /// The body will have a known length equal to the size of the serialized
+    /// value. When sent, a `Content-Type: application/json` header is added to
+    /// the request unless it already has a `Content-Type`.
+    ///
+    /// If the value cannot be serialized then an error with the
+    /// [`InvalidRequest`](crate::error::ErrorKind::InvalidRequest) kind is
+    /// returned.
+    ///
+    /// # Availability
+    ///
+    /// This method is only available when the [`json`](index.html#json) feature
+    /// is enabled.
+    #[cfg(feature = "json")]
+    pub fn json<T>(value: &T) -> Result<Self, crate::Error>
+    where
+        T: serde::Serialize + ?Sized,
+    {
+        serialize_json(value).map(|body| Self::from(body).with_content_type(Some(JSON.into())))
+    }
+
+    /// Create a body containing the given value serialized as URL-encoded
+    /// form data.
+    ///
+    /// The body will have a known length equal to the size of the serialized
+    /// value. When sent, a `Content-Type: application/x-www-form-urlencoded`
+    /// header is added to the request unless it already has a `Content-Type`.
+    ///
+    /// If the value cannot be serialized then an error with the
+    /// [`InvalidRequest`](crate::error::ErrorKind::InvalidRequest) kind is
+    /// returned.
+    ///
+    /// # Availability
+    ///
+    /// This method is only available when the [`form`](index.html#form) feature
+    /// is enabled.
+    #[cfg(feature = "form")]
+    pub fn form<T>(value: &T) -> Result<Self, crate::Error>
+    where
+        T: serde::Serialize + ?Sized,
+    {
+        serialize_form(value).map(|body| Self::from(body).with_content_type(Some(FORM.into())))
     }
 
     /// Report if this body is empty.
@@ -166,6 +293,61 @@ impl AsyncBody {
         }
     }
 
+    /// Read the rest of this body into memory as [`Bytes`](bytes::Bytes).
+    ///
+    /// This is useful for sharing a response body cheaply between tasks, or
+    /// for handing it to other libraries that work with [`Bytes`](bytes::Bytes).
+    /// Streaming bodies are read into a single buffer which is then turned
+    /// into [`Bytes`](bytes::Bytes) without being copied again. Bodies that are
+    /// already stored in memory are not copied at all.
+    ///
+    /// # Availability
+    ///
+    /// This method is only available when the [`bytes`](index.html#bytes)
+    /// feature is enabled.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # async fn run() -> Result<(), isahc::Error> {
+    /// let response = isahc::get_async("https://example.org").await?;
+    /// let bytes = response.into_body().into_bytes().await?;
+    ///
+    /// println!("Read {} bytes", bytes.len());
+    /// # Ok(()) }
+    /// ```
+    #[cfg(feature = "bytes")]
+    pub async fn into_bytes(self) -> io::Result<bytes::Bytes> {
+        use futures_lite::io::AsyncReadExt;
+
+        match self.0 {
+            Inner::Empty => Ok(bytes::Bytes::new()),
+            Inner::Buffer(cursor) => Ok(buffer_into_bytes(cursor)),
+            Inner::Reader(mut reader, len) => {
+                let capacity = len.unwrap_or_default().min(MAX_PREALLOCATION);
+                let mut buf = Vec::with_capacity(capacity as usize);
+                reader.read_to_end(&mut buf).await?;
+
+                Ok(buf.into())
+            }
+        }
+    }
+
+    /// Create a copy of this body if it is an in-memory buffer. Streaming
+    /// bodies cannot be copied and will return `None`.
+    ///
+    /// The copy always starts at the beginning of the body, regardless of how
+    /// much of this body has already been read.
+    pub(crate) fn try_clone(&self) -> Option<Self> {
+        let inner = match &self.0 {
+            Inner::Empty => Inner::Empty,
+            Inner::Buffer(cursor) => Inner::Buffer(Cursor::new(cursor.get_ref().clone())),
+            Inner::Reader(_, _) => return None,
+        };
+
+        Some(Self(inner, self.1.clone()))
+    }
+
     /// Turn this asynchronous body into a synchronous one. This is how the
     /// response body is implemented for the synchronous API.
     ///
@@ -174,14 +356,29 @@ impl AsyncBody {
     /// generally if the underlying reader only supports blocking under a
     /// specific runtime.
     pub(crate) fn into_sync(self) -> sync::Body {
-        match self.0 {
+        let content_type = self.1;
+
+        let body = match self.0 {
             Inner::Empty => sync::Body::empty(),
             Inner::Buffer(cursor) => sync::Body::from_bytes_static(cursor.into_inner()),
             Inner::Reader(reader, Some(len)) => {
                 sync::Body::from_reader_sized(BlockOn::new(reader), len)
             }
             Inner::Reader(reader, None) => sync::Body::from_reader(BlockOn::new(reader)),
-        }
+        };
+
+        body.with_content_type(content_type)
+    }
+
+    /// Get the media type of this body's content, if it was created from a
+    /// value that has one.
+    pub(crate) fn content_type(&self) -> Option<&str> {
+        self.1.as_deref()
+    }
+
+    pub(crate) fn with_content_type(mut self, content_type: Option<Cow<'static, str>>) -> Self {
+        self.1 = content_type;
+        self
     }
 }
 
@@ -213,7 +410,7 @@ impl From<()> for AsyncBody {
 
 impl From<Vec<u8>> for AsyncBody {
     fn from(body: Vec<u8>) -> Self {
-        Self(Inner::Buffer(Cursor::new(Cow::Owned(body))))
+        Self(Inner::Buffer(Cursor::new(Cow::Owned(body))), None)
     }
 }
 
@@ -253,6 +450,96 @@ impl fmt::Debug for AsyncBody {
     }
 }
 
+/// Adapter for reading from a stream of byte chunks.
+struct StreamReader<S, B> {
+    stream: Pin<Box<S>>,
+    chunk: Option<Cursor<B>>,
+}
+
+// The stream is already pinned on the heap, and chunks are never pinned.
+impl<S, B> Unpin for StreamReader<S, B> {}
+
+impl<S, B, E> AsyncRead for StreamReader<S, B>
+where
+    S: Stream<Item = Result<B, E>>,
+    B: AsRef<[u8]>,
+    E: Into<Box<dyn StdError + Send + Sync>>,
+{
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        if buf.is_empty() {
+            return Poll::Ready(Ok(0));
+        }
+
+        loop {
+            // Read from the current chunk until it is exhausted.
+            if let Some(chunk) = self.chunk.as_mut() {
+                match chunk.read(buf)? {
+                    0 => self.chunk = None,
+                    len => return Poll::Ready(Ok(len)),
+                }
+            }
+
+            match ready!(self.stream.as_mut().poll_next(cx)) {
+                Some(Ok(chunk)) => self.chunk = Some(Cursor::new(chunk)),
+                Some(Err(e)) => return Poll::Ready(Err(io::Error::new(io::ErrorKind::Other, e))),
+                None => return Poll::Ready(Ok(0)),
+            }
+        }
+    }
+}
+
+/// Largest buffer to allocate up front when reading a body of known length
+/// into memory. The length may come from a server, so it is only a hint.
+#[cfg(feature = "bytes")]
+const MAX_PREALLOCATION: u64 = 64 * 1024;
+
+/// Turn the unread remainder of an in-memory body into [`Bytes`](bytes::Bytes)
+/// without copying it.
+#[cfg(feature = "bytes")]
+fn buffer_into_bytes(cursor: Cursor<Cow<'static, [u8]>>) -> bytes::Bytes {
+    let position = cursor.position() as usize;
+    let bytes = match cursor.into_inner() {
+        Cow::Borrowed(slice) => bytes::Bytes::from_static(slice),
+        Cow::Owned(vec) => bytes::Bytes::from(vec),
+    };
+
+    bytes.slice(position.min(bytes.len())..)
+}
+
+/// Media type of bodies created from JSON values.
+#[cfg(feature = "json")]
+pub(crate) const JSON: &str = "application/json";
+
+/// Serialize a value as JSON into a buffer suitable for use as a request body.
+#[cfg(feature = "json")]
+pub(crate) fn serialize_json<T>(value: &T) -> Result<Vec<u8>, crate::Error>
+where
+    T: serde::Serialize + ?Sized,
+{
+    serde_json::to_vec(value)
+        .map_err(|e| crate::Error::new(crate::error::ErrorKind::InvalidRequest, e))
+}
+
+/// Media type of bodies created from URL-encoded form data.
+#[cfg(feature = "form")]
+pub(crate) const FORM: &str = "application/x-www-form-urlencoded";
+
+/// Serialize a value as URL-encoded form data into a buffer suitable for use
+/// as a request body.
+#[cfg(feature = "form")]
+pub(crate) fn serialize_form<T>(value: &T) -> Result<Vec<u8>, crate::Error>
+where
+    T: serde::Serialize + ?Sized,
+{
+    serde_urlencoded::to_string(value)
+        .map(String::into_bytes)
+        .map_err(|e| crate::Error::new(crate::error::ErrorKind::InvalidRequest, e))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -310,6 +597,29 @@ mod tests {
         });
     }
 
+    #[test]
+    fn clone_memory_body() {
+        block_on(async {
+            let mut body = AsyncBody::from("hello world");
+            let mut buf = String::new();
+
+            body.read_to_string(&mut buf).await.unwrap();
+
+            let mut clone = body.try_clone().unwrap();
+            buf.clear();
+
+            assert_eq!(clone.read_to_string(&mut buf).await.unwrap(), 11);
+            assert_eq!(buf, "hello world");
+        });
+    }
+
+    #[test]
+    fn cannot_clone_reader() {
+        let body = AsyncBody::from_reader(futures_lite::io::empty());
+
+        assert!(body.try_clone().is_none());
+    }
+
     #[test]
     fn cannot_reset_reader() {
         let mut body = AsyncBody::from_reader(futures_lite::io::empty());
@@ -317,6 +627,38 @@ mod tests {
         assert_eq!(body.reset(), false);
     }
 
+    #[cfg(feature = "bytes")]
+    #[test]
+    fn memory_body_into_bytes_skips_read_data() {
+        block_on(async {
+            let mut body = AsyncBody::from_bytes_static("hello world");
+            let mut buf = [0; 6];
+
+            body.read_exact(&mut buf).await.unwrap();
+
+            assert_eq!(body.into_bytes().await.unwrap(), "world");
+        });
+    }
+
+    #[cfg(feature = "bytes")]
+    #[test]
+    fn reader_into_bytes() {
+        let body = AsyncBody::from_reader("hello world".as_bytes());
+
+        assert_eq!(block_on(body.into_bytes()).unwrap(), "hello world");
+    }
+
+    #[cfg(feature = "bytes")]
+    #[test]
+    fn sync_body_into_bytes() {
+        assert_eq!(Body::empty().into_bytes().unwrap(), "");
+        assert_eq!(Body::from("hello world").into_bytes().unwrap(), "hello world");
+        assert_eq!(
+            Body::from_reader("hello world".as_bytes()).into_bytes().unwrap(),
+            "hello world"
+        );
+    }
+
     #[test]
     fn sync_memory_into_async() {
         let (body, writer) = Body::from("hello world").into_async();
@@ -17,7 +17,26 @@ use std::{
 /// implements [`Read`], which [`Body`] itself also implements.
 ///
 /// For asynchronous requests, use [`AsyncBody`] instead.
-pub struct Body(Inner);
+///
+/// # Reading response bodies
+///
+/// Since [`Body`] implements [`Read`], the body of a response can be streamed
+/// into anything that accepts a reader, such as [`std::io::copy`] or a
+/// parser, without reading the whole body into memory first. Reads block the
+/// current thread until more data is received from the network.
+///
+/// ```no_run
+/// use std::io::{BufRead, BufReader};
+///
+/// let response = isahc::get("https://example.org/big.csv")?;
+///
+/// // Process the body one line at a time as it arrives.
+/// for line in BufReader::new(response.into_body()).lines() {
+///     println!("{}", line?);
+/// }
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+pub struct Body(Inner, Option<Cow<'static, str>>);
 
 enum Inner {
     Empty,
@@ -31,7 +50,7 @@ impl Body {
     /// An empty body represents the *absence* of a body, which is semantically
     /// different than the presence of a body of zero length.
     pub const fn empty() -> Self {
-        Self(Inner::Empty)
+        Self(Inner::Empty, None)
     }
 
     /// Create a new body from a potentially static byte buffer.
@@ -59,7 +78,7 @@ impl Body {
         B: AsRef<[u8]> + 'static,
     {
         castaway::match_type!(bytes, {
-            Cursor<Cow<'static, [u8]>> as bytes => Self(Inner::Buffer(bytes)),
+            Cursor<Cow<'static, [u8]>> as bytes => Self(Inner::Buffer(bytes), None),
             Vec<u8> as bytes => Self::from(bytes),
             String as bytes => Self::from(bytes.into_bytes()),
             bytes => Self::from(bytes.as_ref().to_vec()),
@@ -72,11 +91,26 @@ impl Body {
     /// [chunked transfer
     /// encoding](https://tools.ietf.org/html/rfc7230#section-4.1) might be used
     /// to send the request.
+    ///
+    /// The reader is not read from until the request is sent, and is then read
+    /// incrementally as the body is uploaded. This makes it possible to upload
+    /// large or generated content without loading it into memory first.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use isahc::{prelude::*, Body};
+    /// use std::fs::File;
+    ///
+    /// let file = File::open("upload.bin")?;
+    /// let response = isahc::put("https://httpbin.org/put", Body::from_reader(file))?;
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
     pub fn from_reader<R>(reader: R) -> Self
     where
         R: Read + Send + Sync + 'static,
     {
-        Self(Inner::Reader(Box::new(reader), None))
+        Self(Inner::Reader(Box::new(reader), None), None)
     }
 
     /// Create a streaming body with a known length.
@@ -92,7 +126,54 @@ impl Body {
     where
         R: Read + Send + Sync + 'static,
     {
-        Self(Inner::Reader(Box::new(reader), Some(length)))
+        Self(Inner::Reader(Box::new(reader), Some(length)), None)
+    }
+
+    /// Create a body containing the given value serialized as JSON.
+    ///
+    /// The body will have a known length equal to the size of the serialized
+    /// value. When sent, a `Content-Type: application/json` header is added to
+    /// the request unless it already has a `Content-Type`.
+    ///
+    /// If the value cannot be serialized then an error with the
+    /// [`InvalidRequest`](crate::error::ErrorKind::InvalidRequest) kind is
+    /// returned.
+    ///
+    /// # Availability
+    ///
+    /// This method is only available when the [`json`](index.html#json) feature
+    /// is enabled.
+    #[cfg(feature = "json")]
+    pub fn json<T>(value: &T) -> std::result::Result<Self, crate::Error>
+    where
+        T: serde::Serialize + ?Sized,
+    {
+        super::serialize_json(value)
+            .map(|body| Self::from(body).with_content_type(Some(super::JSON.into())))
+    }
+
+    /// Create a body containing the given value serialized as URL-encoded
+    /// form data.
+    ///
+    /// The body will have a known length equal to the size of the serialized
+    /// value. When sent, a `Content-Type: application/x-www-form-urlencoded`
+    /// header is added to the request unless it already has a `Content-Type`.
+    ///
+    /// If the value cannot be serialized then an error with the
+    /// [`InvalidRequest`](crate::error::ErrorKind::InvalidRequest) kind is
+    /// returned.
+    ///
+    /// # Availability
+    ///
+    /// This method is only available when the [`form`](index.html#form) feature
+    /// is enabled.
+    #[cfg(feature = "form")]
+    pub fn form<T>(value: &T) -> std::result::Result<Self, crate::Error>
+    where
+        T: serde::Serialize + ?Sized,
+    {
+        super::serialize_form(value)
+            .map(|body| Self::from(body).with_content_type(Some(super::FORM.into())))
     }
 
     /// Report if this body is empty.
@@ -142,6 +223,43 @@ impl Body {
         }
     }
 
+    /// Read the rest of this body into memory as [`Bytes`](bytes::Bytes).
+    ///
+    /// This is useful for sharing a response body cheaply between threads, or
+    /// for handing it to other libraries that work with [`Bytes`](bytes::Bytes).
+    /// Streaming bodies are read into a single buffer which is then turned
+    /// into [`Bytes`](bytes::Bytes) without being copied again. Bodies that are
+    /// already stored in memory are not copied at all.
+    ///
+    /// # Availability
+    ///
+    /// This method is only available when the [`bytes`](index.html#bytes)
+    /// feature is enabled.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// let response = isahc::get("https://example.org")?;
+    /// let bytes = response.into_body().into_bytes()?;
+    ///
+    /// println!("Read {} bytes", bytes.len());
+    /// # Ok::<(), isahc::Error>(())
+    /// ```
+    #[cfg(feature = "bytes")]
+    pub fn into_bytes(self) -> Result<bytes::Bytes> {
+        match self.0 {
+            Inner::Empty => Ok(bytes::Bytes::new()),
+            Inner::Buffer(cursor) => Ok(super::buffer_into_bytes(cursor)),
+            Inner::Reader(mut reader, len) => {
+                let capacity = len.unwrap_or_default().min(super::MAX_PREALLOCATION);
+                let mut buf = Vec::with_capacity(capacity as usize);
+                reader.read_to_end(&mut buf)?;
+
+                Ok(buf.into())
+            }
+        }
+    }
+
     /// Convert this body into an asynchronous one.
     ///
     /// Turning a synchronous operation into an asynchronous one can be quite
@@ -154,7 +272,9 @@ impl Body {
     /// copy the bytes from the reader to the writing half of the pipe in a
     /// blocking fashion.
     pub(crate) fn into_async(self) -> (AsyncBody, Option<Writer>) {
-        match self.0 {
+        let content_type = self.1;
+
+        let (body, writer) = match self.0 {
             Inner::Empty => (AsyncBody::empty(), None),
             Inner::Buffer(cursor) => (AsyncBody::from_bytes_static(cursor.into_inner()), None),
             Inner::Reader(reader, len) => {
@@ -172,7 +292,14 @@ impl Body {
                     }),
                 )
             }
-        }
+        };
+
+        (body.with_content_type(content_type), writer)
+    }
+
+    pub(crate) fn with_content_type(mut self, content_type: Option<Cow<'static, str>>) -> Self {
+        self.1 = content_type;
+        self
     }
 }
 
@@ -200,7 +327,7 @@ impl From<()> for Body {
 
 impl From<Vec<u8>> for Body {
     fn from(body: Vec<u8>) -> Self {
-        Self(Inner::Buffer(Cursor::new(Cow::Owned(body))))
+        Self(Inner::Buffer(Cursor::new(Cow::Owned(body))), None)
     }
 }
 
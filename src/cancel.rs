@@ -0,0 +1,113 @@
+//! Explicit cancellation of requests.
+
+use slab::Slab;
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+        Mutex,
+    },
+    task::Waker,
+};
+
+/// A token for cancelling requests that are in flight.
+///
+/// Dropping a response future is enough to cancel a single request, but it is
+/// sometimes more convenient to cancel requests from somewhere else, for
+/// example to abort every request that lost a race once the first one
+/// completes. Requests can be associated with a cancellation token using
+/// [`Configurable::cancellation_token`](crate::config::Configurable::cancellation_token),
+/// and are all cancelled together when [`CancellationToken::cancel`] is
+/// called.
+///
+/// Cancelling a request aborts its transfer immediately, and the request fails
+/// with an error of kind [`Cancelled`](crate::error::ErrorKind::Cancelled). If
+/// the response has already been received, reading the rest of its body fails
+/// instead. Requests sent using a token that has already been cancelled fail
+/// without being sent.
+///
+/// Tokens are cheap to clone, and clones all refer to the same token. Once
+/// cancelled, a token stays cancelled.
+///
+/// # Examples
+///
+/// ```no_run
+/// use isahc::{prelude::*, CancellationToken, Request};
+///
+/// let token = CancellationToken::new();
+///
+/// let request = Request::get("https://example.org")
+///     .cancellation_token(token.clone())
+///     .body(())?;
+///
+/// let future = isahc::send_async(request);
+///
+/// // Changed our minds.
+/// token.cancel();
+///
+/// let error = futures_lite::future::block_on(future).unwrap_err();
+/// assert_eq!(error.kind(), &isahc::error::ErrorKind::Cancelled);
+/// # Ok::<(), isahc::Error>(())
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct CancellationToken {
+    inner: Arc<Inner>,
+}
+
+#[derive(Debug, Default)]
+struct Inner {
+    cancelled: AtomicBool,
+
+    /// Wakers for the transfers currently in flight using this token, which
+    /// ask the agent running each transfer to abort it.
+    wakers: Mutex<Slab<Waker>>,
+}
+
+impl CancellationToken {
+    /// Create a new token that has not been cancelled.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Cancel all requests using this token.
+    pub fn cancel(&self) {
+        let mut wakers = self.inner.wakers.lock().unwrap();
+
+        if !self.inner.cancelled.swap(true, Ordering::SeqCst) {
+            for waker in wakers.drain() {
+                waker.wake();
+            }
+        }
+    }
+
+    /// Check whether this token has been cancelled.
+    pub fn is_cancelled(&self) -> bool {
+        self.inner.cancelled.load(Ordering::SeqCst)
+    }
+
+    /// Register a waker to wake when this token is cancelled. If the token is
+    /// already cancelled, the waker is woken immediately.
+    ///
+    /// Returns a key for unregistering the waker again.
+    pub(crate) fn register(&self, waker: Waker) -> Option<usize> {
+        let mut wakers = self.inner.wakers.lock().unwrap();
+
+        if self.is_cancelled() {
+            waker.wake();
+            None
+        } else {
+            Some(wakers.insert(waker))
+        }
+    }
+
+    /// Unregister a waker previously registered with
+    /// [`CancellationToken::register`].
+    pub(crate) fn unregister(&self, key: usize) {
+        let mut wakers = self.inner.wakers.lock().unwrap();
+
+        // The waker is gone already if the token was cancelled.
+        if wakers.contains(key) {
+            wakers.remove(key);
+        }
+    }
+}
@@ -2,18 +2,22 @@
 
 use crate::{
     agent::{self, AgentBuilder},
+    auth::BearerToken,
     body::{AsyncBody, Body},
+    cancel::CancellationToken,
     config::{
-        client::ClientConfig,
+        client::{ClientConfig, CurlCallback},
         request::{RequestConfig, SetOpt, WithRequestConfig},
         *,
     },
     default_headers::DefaultHeadersInterceptor,
     error::{Error, ErrorKind},
-    handler::{RequestHandler, ResponseBodyReader},
+    handler::{HeaderList, RequestHandler, ResponseBodyReader},
     headers::HasHeaders,
     interceptor::{self, Interceptor, InterceptorObj},
-    parsing::header_to_curl_string,
+    parsing::header_to_curl_bytes,
+    retry::RetryInterceptor,
+    summary::{CompletionCallback, TransferSummary},
 };
 use futures_lite::{
     future::{block_on, try_zip},
@@ -31,9 +35,12 @@ use std::{
     future::Future,
     io,
     pin::Pin,
-    sync::Arc,
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        Arc,
+    },
     task::{Context, Poll},
-    time::Duration,
+    time::{Duration, Instant},
 };
 use tracing_futures::Instrument;
 
@@ -77,10 +84,14 @@ pub struct HttpClientBuilder {
     request_config: RequestConfig,
     interceptors: Vec<InterceptorObj>,
     default_headers: HeaderMap<HeaderValue>,
+    worker_threads: usize,
     error: Option<Error>,
 
     #[cfg(feature = "cookies")]
     cookie_jar: Option<crate::cookies::CookieJar>,
+
+    #[cfg(feature = "testing")]
+    mock_transport: Option<crate::testing::MockTransport>,
 }
 
 impl Default for HttpClientBuilder {
@@ -107,10 +118,14 @@ impl HttpClientBuilder {
                 InterceptorObj::new(crate::redirect::RedirectInterceptor),
             ],
             default_headers: HeaderMap::new(),
+            worker_threads: 1,
             error: None,
 
             #[cfg(feature = "cookies")]
             cookie_jar: None,
+
+            #[cfg(feature = "testing")]
+            mock_transport: None,
         }
     }
 
@@ -152,6 +167,148 @@ impl HttpClientBuilder {
         self.cookie_jar(Default::default())
     }
 
+    /// Enable persistent cookie handling for all requests using this client,
+    /// using a cookie jar loaded from the given file.
+    ///
+    /// The file is expected to be in the format written by
+    /// [`CookieJar::save`](crate::cookies::CookieJar::save). If the file does
+    /// not exist yet, the client starts with an empty cookie jar. If the file
+    /// exists but cannot be read, [`build`](HttpClientBuilder::build) will
+    /// return an error.
+    ///
+    /// Cookies are not saved back to the file automatically. To persist the
+    /// session, save the jar returned by
+    /// [`HttpClient::cookie_jar`](crate::HttpClient::cookie_jar) when you are
+    /// done with the client.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use isahc::{prelude::*, HttpClient};
+    ///
+    /// let client = HttpClient::builder()
+    ///     .cookies_from_file("cookies.txt")
+    ///     .build()?;
+    ///
+    /// client.get("https://example.org")?;
+    ///
+    /// client.cookie_jar().unwrap().save("cookies.txt")?;
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    ///
+    /// # Availability
+    ///
+    /// This method is only available when the [`cookies`](index.html#cookies)
+    /// feature is enabled.
+    #[cfg(feature = "cookies")]
+    pub fn cookies_from_file(mut self, path: impl AsRef<std::path::Path>) -> Self {
+        match crate::cookies::CookieJar::load(path) {
+            Ok(jar) => self.cookie_jar(jar),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => self.cookie_jar(Default::default()),
+            Err(e) => {
+                self.error = Some(Error::new(ErrorKind::ClientInitialization, e));
+                self
+            }
+        }
+    }
+
+    /// Set a function to be called with a summary of each transfer once it
+    /// completes, whether it succeeded or failed.
+    ///
+    /// The [`TransferSummary`] includes the request method and URI, the
+    /// response status, the number of body bytes sent and received, the total
+    /// time taken, and whether a connection was reused. This is useful for
+    /// logging or exporting metrics for every request in a single place.
+    ///
+    /// A transfer completes once the response body has been read to the end
+    /// or dropped. The function is called on the client's agent thread, so it
+    /// should return quickly to avoid delaying other requests.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use isahc::{prelude::*, HttpClient};
+    ///
+    /// let client = HttpClient::builder()
+    ///     .on_complete(|summary| {
+    ///         println!(
+    ///             "{} {} -> {:?} in {:?}",
+    ///             summary.method(),
+    ///             summary.uri(),
+    ///             summary.status(),
+    ///             summary.total_time(),
+    ///         );
+    ///     })
+    ///     .build()?;
+    ///
+    /// client.get("https://example.org")?.consume()?;
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn on_complete<F>(mut self, f: F) -> Self
+    where
+        F: Fn(&TransferSummary) + Send + Sync + 'static,
+    {
+        self.client_config.on_complete = Some(CompletionCallback::new(f));
+        self
+    }
+
+    /// Configure the curl easy handle of every request sent by this client
+    /// directly, using the given function.
+    ///
+    /// This is an escape hatch for advanced users who need a curl feature that
+    /// Isahc does not expose itself. The function is called after Isahc has
+    /// finished configuring the handle for a request, just before the request
+    /// is sent, so any options it sets override those set by Isahc. If called
+    /// more than once, all of the given functions are called in the order they
+    /// were added. Returning an error fails the request.
+    ///
+    /// Isahc relies on many curl options and callbacks to function correctly,
+    /// so changing options that Isahc sets itself can break requests in subtle
+    /// ways. In particular, the handler of the easy handle must not be
+    /// replaced. Note that the `curl` crate used must be the same version that
+    /// Isahc depends on.
+    ///
+    /// This has no effect on requests answered by a mock transport.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use isahc::{prelude::*, HttpClient};
+    ///
+    /// let client = HttpClient::builder()
+    ///     .with_curl(|easy| easy.path_as_is(true))
+    ///     .build()?;
+    ///
+    /// client.get("https://example.org/a/../b")?;
+    /// # Ok::<(), isahc::Error>(())
+    /// ```
+    pub fn with_curl<F>(mut self, f: F) -> Self
+    where
+        F: Fn(&mut curl::easy::Easy2<RequestHandler>) -> Result<(), curl::Error>
+            + Send
+            + Sync
+            + 'static,
+    {
+        self.client_config.curl_callbacks.push(CurlCallback::new(f));
+        self
+    }
+
+    /// Answer all requests sent by this client using the given mock transport,
+    /// instead of sending them over the network.
+    ///
+    /// This is intended for testing code that uses a client. See
+    /// [`MockTransport`](crate::testing::MockTransport) for details.
+    ///
+    /// # Availability
+    ///
+    /// This method is only available when the [`testing`](index.html#testing)
+    /// feature is enabled.
+    #[cfg(feature = "testing")]
+    pub fn mock_transport(mut self, mock: crate::testing::MockTransport) -> Self {
+        self.mock_transport = Some(mock);
+        self
+    }
+
     /// Add a request interceptor to the client.
     ///
     /// # Availability
@@ -171,6 +328,82 @@ impl HttpClientBuilder {
         self
     }
 
+    /// Set the number of background worker threads used to execute requests.
+    ///
+    /// Each worker thread drives its own set of transfers and its own
+    /// connection pool. Requests are assigned to workers in a round-robin
+    /// fashion. Using more than one worker can improve throughput for clients
+    /// that have many thousands of requests in flight at once, at the cost of
+    /// connections being less likely to be reused, since a connection opened
+    /// by one worker cannot be used by another.
+    ///
+    /// Note that connection limits such as
+    /// [`HttpClientBuilder::max_connections`],
+    /// [`HttpClientBuilder::max_connections_per_host`], and
+    /// [`HttpClientBuilder::connection_cache_size`] apply to each worker
+    /// individually.
+    ///
+    /// By default a single worker thread is used, which is plenty for most
+    /// applications. Setting this to `0` is the same as setting it to `1`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use isahc::HttpClient;
+    ///
+    /// let client = HttpClient::builder()
+    ///     .worker_threads(4)
+    ///     .build()?;
+    /// # Ok::<(), isahc::Error>(())
+    /// ```
+    pub fn worker_threads(mut self, threads: usize) -> Self {
+        self.worker_threads = threads.max(1);
+        self
+    }
+
+    /// Set the name of the background thread spawned to execute requests for
+    /// this client.
+    ///
+    /// Giving each client a recognizable name makes it much easier to tell
+    /// their threads apart in thread dumps, debuggers, and profilers when an
+    /// application uses several clients. If the client uses more than one
+    /// worker thread, all of them are given the same name.
+    ///
+    /// By default threads are named `isahc-agent-N`, where `N` is a number
+    /// unique to each thread. Note that some platforms truncate long thread
+    /// names; Linux for example only keeps the first 15 bytes.
+    ///
+    /// If the name contains a null byte, [`HttpClientBuilder::build`] will
+    /// return an error.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use isahc::HttpClient;
+    ///
+    /// let client = HttpClient::builder()
+    ///     .thread_name("api-client")
+    ///     .build()?;
+    /// # Ok::<(), isahc::Error>(())
+    /// ```
+    pub fn thread_name(mut self, name: impl Into<String>) -> Self {
+        let name = name.into();
+
+        if name.contains('\0') {
+            self.error = Some(Error::new(
+                ErrorKind::ClientInitialization,
+                io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    "thread name may not contain null bytes",
+                ),
+            ));
+        } else {
+            self.agent_builder = self.agent_builder.thread_name(name);
+        }
+
+        self
+    }
+
     /// Set a maximum number of simultaneous connections that this client is
     /// allowed to keep open at one time.
     ///
@@ -248,6 +481,102 @@ impl HttpClientBuilder {
         self
     }
 
+    /// Set the maximum age of a connection before it is no longer reused.
+    ///
+    /// Unlike [`HttpClientBuilder::connection_cache_ttl`], which limits how
+    /// long a connection may sit idle in the connection cache, this limits the
+    /// total amount of time since the connection was first established. Once
+    /// a connection is older than this, it will be closed instead of being
+    /// reused for a new request. This is useful for working with load balancers
+    /// that silently drop long-lived connections, or for periodically
+    /// rebalancing connections across servers.
+    ///
+    /// A connection in use by an active request is never interrupted by this
+    /// limit. The age has a resolution of one second, and durations that are
+    /// not a whole number of seconds are rounded up.
+    ///
+    /// This option requires libcurl 7.80.0 or newer. If an older version is in
+    /// use, sending a request will fail.
+    ///
+    /// By default no maximum age is enforced.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use isahc::HttpClient;
+    /// use std::time::Duration;
+    ///
+    /// let client = HttpClient::builder()
+    ///     .max_connection_age(Duration::from_secs(300))
+    ///     .build()?;
+    /// # Ok::<(), isahc::Error>(())
+    /// ```
+    pub fn max_connection_age(mut self, age: Duration) -> Self {
+        self.client_config.max_connection_age = Some(age);
+        self
+    }
+
+    /// Set how long to wait for a connection attempt using IPv6 to succeed
+    /// before also trying IPv4 in parallel.
+    ///
+    /// When a host resolves to both IPv6 and IPv4 addresses, connection
+    /// attempts are raced using the [happy
+    /// eyeballs](https://tools.ietf.org/html/rfc8305) algorithm, in which IPv6
+    /// is given a head start of this long. Lowering this can reduce connect
+    /// latency on networks where IPv6 is slow or broken, at the cost of
+    /// opening more connections that end up being discarded.
+    ///
+    /// The default head start is 200 milliseconds. Setting this to zero starts
+    /// both attempts at the same time.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use isahc::HttpClient;
+    /// use std::time::Duration;
+    ///
+    /// let client = HttpClient::builder()
+    ///     .happy_eyeballs_timeout(Duration::from_millis(50))
+    ///     .build()?;
+    /// # Ok::<(), isahc::Error>(())
+    /// ```
+    pub fn happy_eyeballs_timeout(mut self, timeout: Duration) -> Self {
+        self.client_config.happy_eyeballs_timeout = Some(timeout);
+        self
+    }
+
+    /// Set the maximum number of bytes of each response body to buffer in
+    /// memory while waiting for it to be read.
+    ///
+    /// Response bodies are received in the background as fast as the server
+    /// sends them, and are buffered until they are read. If a response body is
+    /// read more slowly than it is received, the transfer is paused once this
+    /// many bytes are buffered and is resumed once the body is read again.
+    /// This bounds the memory used when many large responses are being read
+    /// slowly, at the cost of throughput if set too low.
+    ///
+    /// Data is received in chunks of up to
+    /// [`Configurable::buffer_size`](crate::config::Configurable::buffer_size)
+    /// bytes, and a chunk is always accepted if nothing is buffered, so
+    /// setting this lower than the chunk size buffers one chunk at a time.
+    ///
+    /// By default only a few chunks are buffered for each response.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use isahc::HttpClient;
+    ///
+    /// let client = HttpClient::builder()
+    ///     .read_buffer_size(32 * 1024)
+    ///     .build()?;
+    /// # Ok::<(), isahc::Error>(())
+    /// ```
+    pub fn read_buffer_size(mut self, size: usize) -> Self {
+        self.client_config.read_buffer_size = Some(size);
+        self
+    }
+
     /// Configure DNS caching.
     ///
     /// By default, DNS entries are cached by the client executing the request
@@ -290,9 +619,14 @@ impl HttpClientBuilder {
     /// Set a mapping of DNS resolve overrides.
     ///
     /// Entries in the given map will be used first before using the default DNS
-    /// resolver for host+port pairs.
+    /// resolver for host+port pairs. Only the address that is connected to is
+    /// changed; the `Host` header and the server name used for TLS are still
+    /// taken from the request URI, which makes this useful for sending requests
+    /// to a specific server behind a load balancer or for testing.
     ///
     /// Note that DNS resolving is only performed when establishing a new
+    /// connection, so existing connections to a host may continue to be reused
+    /// regardless of the overrides given here.
     ///
     /// # Examples
     ///
@@ -315,6 +649,106 @@ impl HttpClientBuilder {
         self
     }
 
+    /// Set the `User-Agent` header to send with every request, replacing the
+    /// default of `curl/<version> isahc/<version>`.
+    ///
+    /// A `User-Agent` header set on an individual request or with
+    /// [`HttpClientBuilder::default_header`] still takes precedence over this
+    /// value.
+    ///
+    /// If the value is not a valid header value, [`HttpClientBuilder::build`]
+    /// will return an error.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use isahc::HttpClient;
+    ///
+    /// let client = HttpClient::builder()
+    ///     .user_agent("my-app/1.0")
+    ///     .build()?;
+    /// # Ok::<(), isahc::Error>(())
+    /// ```
+    pub fn user_agent(mut self, user_agent: impl Into<String>) -> Self {
+        match HeaderValue::try_from(user_agent.into()) {
+            Ok(value) => {
+                self.client_config.user_agent = Some(value);
+                self.client_config.disable_user_agent = false;
+            }
+            Err(e) => self.error = Some(Error::new(ErrorKind::ClientInitialization, e)),
+        }
+
+        self
+    }
+
+    /// Do not send a `User-Agent` header unless one is set explicitly.
+    ///
+    /// By default, a `User-Agent` header is added to every request that does
+    /// not already have one. With this option, requests are sent without a
+    /// `User-Agent` header, unless one is set on the individual request or
+    /// with [`HttpClientBuilder::default_header`]. Calling
+    /// [`HttpClientBuilder::user_agent`] afterwards enables the header again.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use isahc::HttpClient;
+    ///
+    /// let client = HttpClient::builder()
+    ///     .no_default_user_agent()
+    ///     .build()?;
+    /// # Ok::<(), isahc::Error>(())
+    /// ```
+    pub fn no_default_user_agent(mut self) -> Self {
+        self.client_config.user_agent = None;
+        self.client_config.disable_user_agent = true;
+        self
+    }
+
+    /// Append a product token to the `User-Agent` header sent with every
+    /// request, such as `my-app/1.0`.
+    ///
+    /// The token is appended to the user agent set with
+    /// [`HttpClientBuilder::user_agent`], or to the default user agent if none
+    /// is set, separated by a space. This lets servers identify your
+    /// application while still seeing the underlying HTTP client.
+    ///
+    /// If the resulting value is not a valid header value,
+    /// [`HttpClientBuilder::build`] will return an error.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use isahc::HttpClient;
+    ///
+    /// // Sends `User-Agent: curl/<version> isahc/<version> my-app/1.0`.
+    /// let client = HttpClient::builder()
+    ///     .append_user_agent("my-app/1.0")
+    ///     .build()?;
+    /// # Ok::<(), isahc::Error>(())
+    /// ```
+    pub fn append_user_agent(mut self, product: impl AsRef<str>) -> Self {
+        // Work on the raw bytes, since the current value may contain bytes
+        // that are valid in a header but not visible ASCII.
+        let mut user_agent = match self.client_config.user_agent.as_ref() {
+            Some(value) => value.as_bytes().to_vec(),
+            None => USER_AGENT.as_bytes().to_vec(),
+        };
+
+        user_agent.push(b' ');
+        user_agent.extend_from_slice(product.as_ref().as_bytes());
+
+        match HeaderValue::from_bytes(&user_agent) {
+            Ok(value) => {
+                self.client_config.user_agent = Some(value);
+                self.client_config.disable_user_agent = false;
+            }
+            Err(e) => self.error = Some(Error::new(ErrorKind::ClientInitialization, e)),
+        }
+
+        self
+    }
+
     /// Add a default header to be passed with every request.
     ///
     /// If a default header value is already defined for the given key, then a
@@ -441,6 +875,14 @@ impl HttpClientBuilder {
             return Err(err);
         }
 
+        // Request bodies must be compressed before redirects are handled, so
+        // that a body is only compressed once no matter how often it is sent.
+        #[cfg(feature = "request-compression")]
+        {
+            let interceptor = InterceptorObj::new(crate::compression::CompressionInterceptor);
+            self.interceptors.insert(0, interceptor);
+        }
+
         // Add cookie interceptor if enabled.
         #[cfg(feature = "cookies")]
         {
@@ -454,12 +896,28 @@ impl HttpClientBuilder {
             self = self.interceptor_impl(DefaultHeadersInterceptor::from(default_headers));
         }
 
+        // Retries should happen as close to the network as possible, so add
+        // the retry interceptor last.
+        self = self.interceptor_impl(RetryInterceptor);
+
+        // A mock transport stands in for the network, so it must come after
+        // all other interceptors.
+        #[cfg(feature = "testing")]
+        {
+            if let Some(mock) = self.mock_transport.take() {
+                self = self.interceptor_impl(crate::testing::MockInterceptor(mock));
+            }
+        }
+
+        let agents = (0..self.worker_threads)
+            .map(|_| self.agent_builder.spawn())
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| Error::new(ErrorKind::ClientInitialization, e))?;
+
         #[cfg(not(feature = "cookies"))]
         let inner = Inner {
-            agent: self
-                .agent_builder
-                .spawn()
-                .map_err(|e| Error::new(ErrorKind::ClientInitialization, e))?,
+            agents,
+            next_agent: AtomicUsize::new(0),
             client_config: self.client_config,
             request_config: self.request_config,
             interceptors: self.interceptors,
@@ -467,10 +925,8 @@ impl HttpClientBuilder {
 
         #[cfg(feature = "cookies")]
         let inner = Inner {
-            agent: self
-                .agent_builder
-                .spawn()
-                .map_err(|e| Error::new(ErrorKind::ClientInitialization, e))?,
+            agents,
+            next_agent: AtomicUsize::new(0),
             client_config: self.client_config,
             request_config: self.request_config,
             interceptors: self.interceptors,
@@ -489,6 +945,25 @@ impl Configurable for HttpClientBuilder {
         self.cookie_jar = Some(cookie_jar);
         self
     }
+
+    fn alpn_protocols<I, T>(mut self, protocols: I) -> Self
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<String>,
+    {
+        let protocols = protocols.into_iter().map(T::into).collect();
+
+        // Fail early instead of on every request.
+        if let ssl::AlpnProtocols::Invalid(reason) = &protocols {
+            self.error = Some(Error::new(
+                ErrorKind::ClientInitialization,
+                io::Error::new(io::ErrorKind::InvalidInput, reason.clone()),
+            ));
+        }
+
+        self.request_config.alpn_protocols = Some(protocols);
+        self
+    }
 }
 
 impl WithRequestConfig for HttpClientBuilder {
@@ -553,6 +1028,11 @@ impl<'a, K: Copy, V: Copy> HeaderPair<K, V> for &'a (K, V) {
 /// different servers. If you are creating an API client library, that might be
 /// a good place to maintain your own internal client.
 ///
+/// Cloning a client is cheap, as it only increments a reference count. All
+/// clones share the same connection pool, configuration, and background agent
+/// thread, so you can freely clone a client to move it into spawned tasks or
+/// threads instead of wrapping it in an [`Arc`] yourself.
+///
 /// # Examples
 ///
 /// ```no_run
@@ -600,8 +1080,11 @@ pub struct HttpClient {
 }
 
 struct Inner {
-    /// This is how we talk to our background agent thread.
-    agent: agent::Handle,
+    /// This is how we talk to our background agent threads.
+    agents: Vec<agent::Handle>,
+
+    /// Index of the agent to submit the next request to.
+    next_agent: AtomicUsize,
 
     /// Client-wide request configuration.
     client_config: ClientConfig,
@@ -861,6 +1344,69 @@ impl HttpClient {
         }
     }
 
+    /// Open a connection to the given URI ahead of time, so that later
+    /// requests to the same host can reuse it.
+    ///
+    /// Establishing a new connection, including any DNS lookups and TLS
+    /// handshakes, can take a significant amount of time compared to the
+    /// request itself. Latency-sensitive applications can use this method to
+    /// warm up the connection pool when they start, instead of paying this
+    /// cost on the first real request.
+    ///
+    /// The connection is established by sending a `HEAD` request to the URI
+    /// without following any redirects. Once any response is received the
+    /// connection is returned to the pool, and this method returns. The
+    /// response status is ignored, since it has no bearing on whether the
+    /// connection can be reused. An error is returned if the connection could
+    /// not be established.
+    ///
+    /// Note that a connection will only be kept if the server allows it to be
+    /// reused, and it may still be closed later if it is idle for too long, or
+    /// if the connection cache is full. See
+    /// [`HttpClientBuilder::connection_cache_size`] and
+    /// [`HttpClientBuilder::connection_cache_ttl`].
+    ///
+    /// To do this asynchronously, see [`HttpClient::preconnect_async`].
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use isahc::{prelude::*, HttpClient};
+    ///
+    /// let client = HttpClient::new()?;
+    ///
+    /// // Warm up a connection at startup.
+    /// client.preconnect("https://example.org")?;
+    ///
+    /// // This request will reuse the connection opened above.
+    /// let response = client.get("https://example.org/hello")?;
+    /// # Ok::<(), isahc::Error>(())
+    /// ```
+    pub fn preconnect<U>(&self, uri: U) -> Result<(), Error>
+    where
+        http::Uri: TryFrom<U>,
+        <http::Uri as TryFrom<U>>::Error: Into<http::Error>,
+    {
+        self.send(preconnect_request(uri)?).map(|_| ())
+    }
+
+    /// Open a connection to the given URI ahead of time asynchronously, so
+    /// that later requests to the same host can reuse it.
+    ///
+    /// See [`HttpClient::preconnect`] for details.
+    pub fn preconnect_async<U>(&self, uri: U) -> PreconnectFuture<'_>
+    where
+        http::Uri: TryFrom<U>,
+        <http::Uri as TryFrom<U>>::Error: Into<http::Error>,
+    {
+        let future = match preconnect_request(uri) {
+            Ok(request) => self.send_async(request),
+            Err(e) => ResponseFuture::error(e),
+        };
+
+        PreconnectFuture(future)
+    }
+
     /// Send an HTTP request and return the HTTP response.
     ///
     /// Upon success, will return a [`Response`] containing the status code,
@@ -914,8 +1460,11 @@ impl HttpClient {
             "send",
             method = ?request.method(),
             uri = ?request.uri(),
+            status = tracing::field::Empty,
         );
 
+        let deadline = self.request_deadline(&request);
+        let sent = Arc::new(AtomicBool::new(false));
         let mut writer_maybe = None;
 
         let request = request.map(|body| {
@@ -930,18 +1479,22 @@ impl HttpClient {
                 // is received, we can use the current thread to read from the
                 // request body synchronously while concurrently waiting for the
                 // response.
+                let future =
+                    ResponseFuture::new(self.send_async_inner(request, deadline, sent.clone()))
+                        .with_deadline(deadline, sent);
+
                 if let Some(mut writer) = writer_maybe {
                     // Note that the `send_async` future is given first; this
                     // ensures that it is polled first and thus the request is
                     // initiated before we attempt to write the request body.
-                    let (response, _) = try_zip(self.send_async_inner(request), async move {
+                    let (response, _) = try_zip(future, async move {
                         writer.write().await.map_err(Error::from)
                     })
                     .await?;
 
                     Ok(response)
                 } else {
-                    self.send_async_inner(request).await
+                    future.await
                 }
             }
             .instrument(span),
@@ -1005,27 +1558,159 @@ impl HttpClient {
             "send_async",
             method = ?request.method(),
             uri = ?request.uri(),
+            status = tracing::field::Empty,
         );
 
+        let deadline = self.request_deadline(&request);
+        let sent = Arc::new(AtomicBool::new(false));
+
         ResponseFuture::new(
-            self.send_async_inner(request.map(Into::into))
+            self.send_async_inner(request.map(Into::into), deadline, sent.clone())
                 .instrument(span),
         )
+        .with_deadline(deadline, sent)
+    }
+
+    /// Send an HTTP request and return the HTTP response asynchronously,
+    /// without borrowing the client.
+    ///
+    /// This behaves exactly like [`HttpClient::send_async`], except that the
+    /// returned future holds its own clone of the client instead of borrowing
+    /// it. This makes the future `'static`, so that it can be spawned onto an
+    /// executor and awaited elsewhere. Since clients are reference counted,
+    /// this does not create a new client or connection pool.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use isahc::{prelude::*, HttpClient, Request};
+    /// use std::thread;
+    ///
+    /// let client = HttpClient::new()?;
+    /// let future = client.send_async_owned(Request::get("https://example.org").body(())?);
+    ///
+    /// // The future can outlive the client reference that created it.
+    /// drop(client);
+    ///
+    /// let response = thread::spawn(move || futures_lite::future::block_on(future))
+    ///     .join()
+    ///     .unwrap()?;
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn send_async_owned<B>(&self, request: Request<B>) -> ResponseFuture<'static>
+    where
+        B: Into<AsyncBody>,
+    {
+        let span = tracing::debug_span!(
+            "send_async",
+            method = ?request.method(),
+            uri = ?request.uri(),
+            status = tracing::field::Empty,
+        );
+
+        let client = self.clone();
+        let request = request.map(Into::into);
+        let deadline = self.request_deadline(&request);
+        let sent = Arc::new(AtomicBool::new(false));
+        let future = {
+            let sent = sent.clone();
+            async move { client.send_async_inner(request, deadline, sent).await }
+        };
+
+        ResponseFuture::new(future.instrument(span)).with_deadline(deadline, sent)
+    }
+
+    /// Close this client, shutting down its background agent thread and
+    /// closing all of its connections.
+    ///
+    /// A client normally shuts down once the last clone of it is dropped,
+    /// which aborts any transfers still in progress. This method lets you shut
+    /// down a client at a well-defined point instead: new requests are rejected
+    /// right away, requests already in flight are given up to `timeout` to
+    /// complete, and then the agent thread is stopped. This method blocks until
+    /// the agent thread has exited.
+    ///
+    /// Since all clones of a client share the same agent, this closes every
+    /// clone of this client as well. Sending a request with a closed client
+    /// will return an error of the kind
+    /// [`ClientClosed`](crate::error::ErrorKind::ClientClosed).
+    ///
+    /// Note that a transfer is only complete once its response body has been
+    /// read to the end or dropped. If you are still holding on to a response
+    /// body on the current thread, its transfer will be aborted once the
+    /// timeout passes.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use isahc::{prelude::*, HttpClient};
+    /// use std::time::Duration;
+    ///
+    /// let client = HttpClient::new()?;
+    /// let mut response = client.get("https://example.org")?;
+    /// println!("{}", response.text()?);
+    ///
+    /// client.close(Duration::from_secs(5));
+    /// # Ok::<(), isahc::Error>(())
+    /// ```
+    pub fn close(self, timeout: Duration) {
+        let deadline = Instant::now() + timeout;
+
+        // Signal all agents first so that they drain concurrently.
+        for agent in &self.inner.agents {
+            agent.close(deadline);
+        }
+
+        for agent in &self.inner.agents {
+            agent.join();
+        }
+    }
+
+    /// Get the time by which a request being sent right now must be complete,
+    /// if it has a total timeout.
+    fn request_deadline<B>(&self, request: &Request<B>) -> Option<Instant> {
+        request
+            .extensions()
+            .get::<RequestConfig>()
+            .and_then(|config| config.total_timeout)
+            .or(self.inner.request_config.total_timeout)
+            .map(|timeout| Instant::now() + timeout)
     }
 
     /// Actually send the request. All the public methods go through here.
+    ///
+    /// The given flag is set once the request has been handed to an agent, so
+    /// that the response future can tell whether the request was ever sent.
     async fn send_async_inner(
         &self,
         mut request: Request<AsyncBody>,
+        deadline: Option<Instant>,
+        sent: Arc<AtomicBool>,
     ) -> Result<Response<AsyncBody>, Error> {
         // Populate request config, creating if necessary.
         if let Some(config) = request.extensions_mut().get_mut::<RequestConfig>() {
             // Merge request configuration with defaults.
             config.merge(&self.inner.request_config);
+            config.deadline = deadline;
+            config.sent = Some(sent);
         } else {
+            let mut config = self.inner.request_config.clone();
+            config.deadline = deadline;
+            config.sent = Some(sent);
+            request.extensions_mut().insert(config);
+        }
+
+        // Describe the body's content if it knows its own type and the caller
+        // has not already done so.
+        if let Some(content_type) = request
+            .body()
+            .content_type()
+            .and_then(|content_type| HeaderValue::from_str(content_type).ok())
+        {
             request
-                .extensions_mut()
-                .insert(self.inner.request_config.clone());
+                .headers_mut()
+                .entry(http::header::CONTENT_TYPE)
+                .or_insert(content_type);
         }
 
         let ctx = interceptor::Context {
@@ -1033,7 +1718,12 @@ impl HttpClient {
             interceptors: &self.inner.interceptors,
         };
 
-        ctx.send(request).await
+        let response = ctx.send(request).await?;
+
+        // Record the final status in the span for this request, if any.
+        tracing::Span::current().record("status", response.status().as_u16());
+
+        Ok(response)
     }
 
     fn create_easy_handle(
@@ -1057,11 +1747,6 @@ impl HttpClient {
         // Set whether curl should generate verbose debug data for us to log.
         easy.verbose(easy.get_ref().is_debug_enabled())?;
 
-        // Disable connection reuse logs if connection cache is disabled.
-        if self.inner.client_config.close_connections {
-            easy.get_mut().disable_connection_reuse_log = true;
-        }
-
         easy.signal(false)?;
 
         let request_config = request
@@ -1069,9 +1754,29 @@ impl HttpClient {
             .get::<RequestConfig>()
             .unwrap();
 
+        // Disable connection reuse logs if the connection will not be reused
+        // anyway.
+        if self.inner.client_config.close_connections
+            || request_config.forbid_reuse == Some(true)
+        {
+            easy.get_mut().disable_connection_reuse_log = true;
+        }
+
         request_config.set_opt(&mut easy)?;
         self.inner.client_config.set_opt(&mut easy)?;
 
+        easy.get_mut().enable_metrics = request_config.enable_metrics.unwrap_or(false);
+        easy.get_mut().upload_progress = request_config.upload_progress.clone();
+        easy.get_mut().download_progress = request_config.download_progress.clone();
+        easy.get_mut().max_response_body_size = request_config.max_response_body_size;
+        easy.get_mut().read_buffer_size = self.inner.client_config.read_buffer_size;
+        easy.get_mut().header_timeout = request_config.header_timeout;
+        easy.get_mut().on_complete = self.inner.client_config.on_complete.clone();
+        easy.get_mut().cancellation_token = request_config.cancellation_token.clone();
+        easy.get_mut().deadline = request_config.deadline;
+        easy.get_mut().request_method = request.method().clone();
+        easy.get_mut().request_uri = request.uri().clone();
+
         // Check if we need to disable the Expect header.
         let disable_expect_header = request_config.expect_continue
             .as_ref()
@@ -1105,7 +1810,35 @@ impl HttpClient {
             }
         }
 
-        easy.url(&uri_to_string(request.uri()))?;
+        // If a different server name is configured, request the URI using that
+        // name instead, but still connect to the host given in the URI unless
+        // a dialer has been configured to decide where to connect to.
+        match (request_config.ssl_server_name.as_deref(), request.uri().host()) {
+            (Some(name), Some(host)) if name != host => {
+                let uri = replace_uri_host(request.uri(), name).map_err(|e| {
+                    let mut error = curl::Error::new(curl_sys::CURLE_BAD_FUNCTION_ARGUMENT);
+                    error.set_extra(format!("invalid server name: {}", e));
+                    error
+                })?;
+
+                if !matches!(&request_config.dial, Some(dialer) if dialer.is_ip_socket()) {
+                    let port = request.uri().port_u16().unwrap_or_else(|| {
+                        if request.uri().scheme() == Some(&http::uri::Scheme::HTTPS) {
+                            443
+                        } else {
+                            80
+                        }
+                    });
+
+                    let mut connect_to = curl::easy::List::new();
+                    connect_to.append(&format!("{}:{}:{}:{}", name, port, host, port))?;
+                    easy.connect_to(connect_to)?;
+                }
+
+                easy.url(&uri_to_string(&uri))?;
+            }
+            _ => easy.url(&uri_to_string(request.uri()))?,
+        }
 
         // If the request has a body, then we either need to tell curl how large
         // the body is if we know it, or tell curl to use chunked encoding. If
@@ -1138,7 +1871,7 @@ impl HttpClient {
         }
 
         // Generate a header list for curl.
-        let mut headers = curl::easy::List::new();
+        let mut headers = HeaderList::new();
 
         let title_case = request
             .extensions()
@@ -1148,14 +1881,24 @@ impl HttpClient {
             .unwrap_or(false);
 
         for (name, value) in request.headers().iter() {
-            headers.append(&header_to_curl_string(name, value, title_case))?;
+            // Drop any explicit Expect header if its use has been disabled.
+            if disable_expect_header && name == http::header::EXPECT {
+                continue;
+            }
+
+            headers.append(&header_to_curl_bytes(name, value, title_case))?;
         }
 
         if disable_expect_header {
-            headers.append("Expect:")?;
+            headers.append(b"Expect:")?;
         }
 
-        easy.http_headers(headers)?;
+        headers.set_opt(&mut easy)?;
+
+        // Finally, give any custom callbacks the last word.
+        for callback in &self.inner.client_config.curl_callbacks {
+            callback.call(&mut easy)?;
+        }
 
         Ok((easy, future))
     }
@@ -1169,11 +1912,62 @@ impl crate::interceptor::Invoke for &HttpClient {
         Box::pin(async move {
             let is_head_request = request.method() == http::Method::HEAD;
 
-            // Set default user agent if not specified.
-            request
-                .headers_mut()
-                .entry(http::header::USER_AGENT)
-                .or_insert(USER_AGENT.parse().unwrap());
+            // Don't bother sending the request if it has been cancelled already.
+            let is_cancelled = request
+                .extensions()
+                .get::<RequestConfig>()
+                .and_then(|config| config.cancellation_token.as_ref())
+                .map(CancellationToken::is_cancelled)
+                .unwrap_or(false);
+
+            if is_cancelled {
+                return Err(ErrorKind::Cancelled.into());
+            }
+
+            // Likewise if the total timeout has already been reached, such as
+            // while following redirects.
+            let (deadline, sent) = request
+                .extensions()
+                .get::<RequestConfig>()
+                .map(|config| (config.deadline, config.sent.clone()))
+                .unwrap_or_default();
+
+            if matches!(deadline, Some(deadline) if Instant::now() >= deadline) {
+                let sent = sent.map(|sent| sent.load(Ordering::SeqCst)).unwrap_or(false);
+
+                return Err(crate::handler::deadline_error(!sent));
+            }
+
+            // Set default user agent if not specified, unless disabled.
+            if !self.inner.client_config.disable_user_agent
+                && !request.headers().contains_key(http::header::USER_AGENT)
+            {
+                let user_agent = match self.inner.client_config.user_agent.as_ref() {
+                    Some(value) => value.clone(),
+                    None => USER_AGENT.parse().unwrap(),
+                };
+
+                request
+                    .headers_mut()
+                    .insert(http::header::USER_AGENT, user_agent);
+            }
+
+            // Set bearer token authorization if configured and not specified.
+            if !request.headers().contains_key(http::header::AUTHORIZATION) {
+                let token_header = request
+                    .extensions()
+                    .get::<RequestConfig>()
+                    .and_then(|config| config.bearer_token.as_ref())
+                    .map(BearerToken::header_value);
+
+                if let Some(value) = token_header {
+                    let value = value.map_err(|e| Error::new(ErrorKind::InvalidRequest, e))?;
+
+                    request
+                        .headers_mut()
+                        .insert(http::header::AUTHORIZATION, value);
+                }
+            }
 
             // Check if automatic decompression is enabled; we'll need to know
             // this later after the response is sent.
@@ -1188,7 +1982,11 @@ impl crate::interceptor::Invoke for &HttpClient {
             let (easy, future) = self.create_easy_handle(request).map_err(Error::from_any)?;
 
             // Send the request to the agent to be executed.
-            self.inner.agent.submit_request(easy)?;
+            self.inner.next_agent().submit_request(easy)?;
+
+            if let Some(sent) = sent {
+                sent.store(true, Ordering::SeqCst);
+            }
 
             // Await for the response headers.
             let response = future.await?;
@@ -1236,6 +2034,20 @@ impl crate::interceptor::Invoke for &HttpClient {
     }
 }
 
+impl Inner {
+    /// Get the agent to execute the next request with, spreading requests
+    /// across all agents in turn.
+    fn next_agent(&self) -> &agent::Handle {
+        if self.agents.len() == 1 {
+            return &self.agents[0];
+        }
+
+        let index = self.next_agent.fetch_add(1, Ordering::Relaxed) % self.agents.len();
+
+        &self.agents[index]
+    }
+}
+
 impl fmt::Debug for HttpClient {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("HttpClient").finish()
@@ -1243,15 +2055,37 @@ impl fmt::Debug for HttpClient {
 }
 
 /// A future for a request being executed.
+///
+/// Dropping the future before it completes cancels the request, which aborts
+/// its transfer right away. Likewise, dropping a response before its body has
+/// been read to the end aborts the rest of the transfer.
 #[must_use = "futures do nothing unless you `.await` or poll them"]
-pub struct ResponseFuture<'c>(Pin<Box<dyn Future<Output = <Self as Future>::Output> + 'c + Send>>);
+pub struct ResponseFuture<'c> {
+    inner: Pin<Box<dyn Future<Output = <Self as Future>::Output> + 'c + Send>>,
+
+    /// Time by which the response must be received, if any.
+    deadline: Option<Instant>,
+
+    /// Whether the request has been handed to an agent yet.
+    sent: Option<Arc<AtomicBool>>,
+}
 
 impl<'c> ResponseFuture<'c> {
     fn new<F>(future: F) -> Self
     where
         F: Future<Output = <Self as Future>::Output> + Send + 'c,
     {
-        ResponseFuture(Box::pin(future))
+        ResponseFuture {
+            inner: Box::pin(future),
+            deadline: None,
+            sent: None,
+        }
+    }
+
+    fn with_deadline(mut self, deadline: Option<Instant>, sent: Arc<AtomicBool>) -> Self {
+        self.deadline = deadline;
+        self.sent = Some(sent);
+        self
     }
 
     fn error(error: Error) -> Self {
@@ -1263,7 +2097,23 @@ impl Future for ResponseFuture<'_> {
     type Output = Result<Response<AsyncBody>, Error>;
 
     fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
-        self.0.as_mut().poll(cx)
+        let poll = self.inner.as_mut().poll(cx);
+
+        // Transfers that run past the deadline are aborted by the agent, but
+        // the request might not have reached the agent yet.
+        let expired = matches!(self.deadline, Some(deadline) if Instant::now() >= deadline);
+
+        if poll.is_pending() && expired {
+            let sent = self
+                .sent
+                .as_ref()
+                .map(|sent| sent.load(Ordering::SeqCst))
+                .unwrap_or(false);
+
+            return Poll::Ready(Err(crate::handler::deadline_error(!sent)));
+        }
+
+        poll
     }
 }
 
@@ -1273,6 +2123,38 @@ impl<'c> fmt::Debug for ResponseFuture<'c> {
     }
 }
 
+/// A future for a connection being opened with
+/// [`HttpClient::preconnect_async`].
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct PreconnectFuture<'c>(ResponseFuture<'c>);
+
+impl Future for PreconnectFuture<'_> {
+    type Output = Result<(), Error>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        Pin::new(&mut self.0).poll(cx).map_ok(|_| ())
+    }
+}
+
+impl<'c> fmt::Debug for PreconnectFuture<'c> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PreconnectFuture").finish()
+    }
+}
+
+/// Build the request used to open a connection for
+/// [`HttpClient::preconnect`].
+fn preconnect_request<U>(uri: U) -> Result<Request<()>, Error>
+where
+    http::Uri: TryFrom<U>,
+    <http::Uri as TryFrom<U>>::Error: Into<http::Error>,
+{
+    http::Request::head(uri)
+        .redirect_policy(RedirectPolicy::None)
+        .body(())
+        .map_err(Error::from_any)
+}
+
 /// Response body stream. Holds a reference to the agent to ensure it is kept
 /// alive until at least this transfer is complete.
 struct ResponseBody {
@@ -1315,11 +2197,35 @@ fn uri_to_string(uri: &http::Uri) -> String {
     s
 }
 
+/// Replace the host of a URI with a different one, keeping everything else,
+/// including any user info and port.
+fn replace_uri_host(uri: &http::Uri, host: &str) -> Result<http::Uri, http::Error> {
+    let mut authority = String::new();
+
+    if let Some(current) = uri.authority() {
+        if let Some(index) = current.as_str().rfind('@') {
+            authority.push_str(&current.as_str()[..=index]);
+        }
+    }
+
+    authority.push_str(host);
+
+    if let Some(port) = uri.port() {
+        authority.push(':');
+        authority.push_str(port.as_str());
+    }
+
+    let mut parts = uri.clone().into_parts();
+    parts.authority = Some(authority.parse()?);
+
+    Ok(http::Uri::from_parts(parts)?)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    static_assertions::assert_impl_all!(HttpClient: Send, Sync);
+    static_assertions::assert_impl_all!(HttpClient: Clone, Send, Sync);
     static_assertions::assert_impl_all!(HttpClientBuilder: Send);
 
     #[test]
@@ -1350,4 +2256,24 @@ mod tests {
         let header_map = &mut builder.default_headers;
         assert!(header_map.is_empty())
     }
+
+    #[test]
+    fn append_user_agent_keeps_non_ascii_user_agent() {
+        let builder = HttpClientBuilder::new()
+            .user_agent("caf\u{e9}/1.0")
+            .append_user_agent("my-app/1.0");
+
+        assert_eq!(
+            builder.client_config.user_agent.unwrap().as_bytes(),
+            "caf\u{e9}/1.0 my-app/1.0".as_bytes()
+        );
+    }
+
+    #[test]
+    fn replace_uri_host_keeps_user_info_and_port() {
+        let uri = "https://user:pw@127.0.0.1:8443/path?query".parse().unwrap();
+        let uri = replace_uri_host(&uri, "example.org").unwrap();
+
+        assert_eq!(uri, "https://user:pw@example.org:8443/path?query");
+    }
 }
@@ -0,0 +1,258 @@
+//! Compression of request bodies.
+
+use crate::{
+    body::AsyncBody,
+    config::{request::RequestConfig, Encoding},
+    error::{Error, ErrorKind},
+    interceptor::{Context, Interceptor, InterceptorFuture},
+};
+use flate2::{
+    write::{GzEncoder, ZlibEncoder},
+    Compression,
+};
+use futures_lite::io::{AsyncRead, AsyncReadExt};
+use http::{header, HeaderValue, Request};
+use std::{
+    io::{self, Write},
+    mem,
+    pin::Pin,
+    task::{Context as TaskContext, Poll},
+};
+
+/// Size of the buffer used to read from the uncompressed body.
+const READ_BUFFER_SIZE: usize = 8192;
+
+/// Interceptor that compresses request bodies if configured to.
+///
+/// This must come before the redirect and retry interceptors, so that a
+/// request body is compressed only once even if it is sent multiple times.
+pub(crate) struct CompressionInterceptor;
+
+impl Interceptor for CompressionInterceptor {
+    type Err = Error;
+
+    fn intercept<'a>(
+        &'a self,
+        mut request: Request<AsyncBody>,
+        ctx: Context<'a>,
+    ) -> InterceptorFuture<'a, Self::Err> {
+        Box::pin(async move {
+            let encoding = request
+                .extensions()
+                .get::<RequestConfig>()
+                .and_then(|config| config.request_body_compression);
+
+            let encoding = match encoding {
+                Some(encoding) => encoding,
+                None => return ctx.send(request).await,
+            };
+
+            // Leave requests without a body, or with a body that is already
+            // encoded, alone.
+            if request.body().is_empty()
+                || request.body().len() == Some(0)
+                || request.headers().contains_key(header::CONTENT_ENCODING)
+            {
+                return ctx.send(request).await;
+            }
+
+            let mut encoder = Encoder::new(encoding)?;
+            let mut body = mem::take(request.body_mut());
+
+            *request.body_mut() = if body.try_clone().is_some() {
+                // The body is in memory, so compress it all up front. This
+                // keeps the body repeatable, and its length known.
+                let mut bytes = Vec::new();
+                body.read_to_end(&mut bytes).await?;
+
+                encoder.write_all(&bytes)?;
+                AsyncBody::from(encoder.finish()?)
+            } else {
+                AsyncBody::from_reader(CompressedReader {
+                    inner: body,
+                    encoder,
+                    position: 0,
+                    eof: false,
+                })
+            };
+
+            // Any length given for the body no longer applies.
+            request.headers_mut().remove(header::CONTENT_LENGTH);
+            request.headers_mut().insert(
+                header::CONTENT_ENCODING,
+                HeaderValue::from_static(encoding.as_str()),
+            );
+
+            ctx.send(request).await
+        })
+    }
+}
+
+/// A compressor that writes compressed data into a buffer.
+enum Encoder {
+    Gzip(GzEncoder<Vec<u8>>),
+    Deflate(ZlibEncoder<Vec<u8>>),
+}
+
+impl Encoder {
+    fn new(encoding: Encoding) -> Result<Self, Error> {
+        let level = Compression::default();
+
+        match encoding {
+            Encoding::Gzip => Ok(Encoder::Gzip(GzEncoder::new(Vec::new(), level))),
+            Encoding::Deflate => Ok(Encoder::Deflate(ZlibEncoder::new(Vec::new(), level))),
+            encoding => Err(Error::new(
+                ErrorKind::InvalidRequest,
+                io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!("cannot compress request body using {}", encoding.as_str()),
+                ),
+            )),
+        }
+    }
+
+    /// Compress the given data into the output buffer.
+    fn write_all(&mut self, data: &[u8]) -> io::Result<()> {
+        match self {
+            Encoder::Gzip(encoder) => encoder.write_all(data),
+            Encoder::Deflate(encoder) => encoder.write_all(data),
+        }
+    }
+
+    /// Write the end of the compressed stream into the output buffer.
+    fn try_finish(&mut self) -> io::Result<()> {
+        match self {
+            Encoder::Gzip(encoder) => encoder.try_finish(),
+            Encoder::Deflate(encoder) => encoder.try_finish(),
+        }
+    }
+
+    /// Finish the compressed stream and return all of the compressed data.
+    fn finish(self) -> io::Result<Vec<u8>> {
+        match self {
+            Encoder::Gzip(encoder) => encoder.finish(),
+            Encoder::Deflate(encoder) => encoder.finish(),
+        }
+    }
+
+    /// Get the buffer of compressed data produced so far.
+    fn output(&mut self) -> &mut Vec<u8> {
+        match self {
+            Encoder::Gzip(encoder) => encoder.get_mut(),
+            Encoder::Deflate(encoder) => encoder.get_mut(),
+        }
+    }
+}
+
+/// A reader that compresses a streaming body as it is read.
+struct CompressedReader {
+    inner: AsyncBody,
+    encoder: Encoder,
+
+    /// Position in the encoder output buffer of the next byte to return.
+    position: usize,
+
+    /// Set once the inner body has been read to the end.
+    eof: bool,
+}
+
+impl AsyncRead for CompressedReader {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut TaskContext<'_>,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        let this = &mut *self;
+
+        loop {
+            // Return any compressed data we already have first.
+            let position = this.position;
+            let output = this.encoder.output();
+
+            if position < output.len() {
+                let len = buf.len().min(output.len() - position);
+                buf[..len].copy_from_slice(&output[position..position + len]);
+
+                if position + len == output.len() {
+                    output.clear();
+                    this.position = 0;
+                } else {
+                    this.position += len;
+                }
+
+                return Poll::Ready(Ok(len));
+            }
+
+            if this.eof {
+                return Poll::Ready(Ok(0));
+            }
+
+            let mut chunk = [0; READ_BUFFER_SIZE];
+
+            match Pin::new(&mut this.inner).poll_read(cx, &mut chunk) {
+                Poll::Ready(Ok(0)) => {
+                    this.encoder.try_finish()?;
+                    this.eof = true;
+                }
+                Poll::Ready(Ok(len)) => this.encoder.write_all(&chunk[..len])?,
+                Poll::Ready(Err(e)) if e.kind() == io::ErrorKind::Interrupted => {}
+                Poll::Ready(Err(e)) => return Poll::Ready(Err(e)),
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::read::{GzDecoder, ZlibDecoder};
+    use futures_lite::future::block_on;
+    use std::io::Read;
+
+    fn compress_streaming(encoding: Encoding, data: &[u8]) -> Vec<u8> {
+        let mut reader = CompressedReader {
+            inner: AsyncBody::from_reader(futures_lite::io::Cursor::new(data.to_vec())),
+            encoder: Encoder::new(encoding).unwrap(),
+            position: 0,
+            eof: false,
+        };
+
+        let mut output = Vec::new();
+        block_on(reader.read_to_end(&mut output)).unwrap();
+        output
+    }
+
+    #[test]
+    fn streaming_gzip_round_trip() {
+        let data = "hello world ".repeat(10000);
+        let compressed = compress_streaming(Encoding::Gzip, data.as_bytes());
+
+        assert!(compressed.len() < data.len());
+
+        let mut decompressed = String::new();
+        GzDecoder::new(compressed.as_slice())
+            .read_to_string(&mut decompressed)
+            .unwrap();
+
+        assert_eq!(decompressed, data);
+    }
+
+    #[test]
+    fn streaming_deflate_round_trip() {
+        let data = "hello world ".repeat(10000);
+        let compressed = compress_streaming(Encoding::Deflate, data.as_bytes());
+
+        let mut decompressed = String::new();
+        ZlibDecoder::new(compressed.as_slice())
+            .read_to_string(&mut decompressed)
+            .unwrap();
+
+        assert_eq!(decompressed, data);
+    }
+
+    #[test]
+    fn unsupported_encoding_is_rejected() {
+        assert!(Encoder::new(Encoding::Brotli).is_err());
+    }
+}
@@ -1,15 +1,33 @@
 use super::{
     dns::{DnsCache, ResolveMap},
-    request::SetOpt,
+    request::{setopt_long, SetOpt},
 };
-use std::time::Duration;
+use crate::{handler::RequestHandler, summary::CompletionCallback};
+use curl::easy::Easy2;
+use http::HeaderValue;
+use std::{fmt, os::raw::c_long, sync::Arc, time::Duration};
+
+/// Happy eyeballs timeout option, which is not yet exposed by the curl crate.
+/// Requires libcurl 7.59.0 or newer.
+const CURLOPT_HAPPY_EYEBALLS_TIMEOUT_MS: curl_sys::CURLoption = curl_sys::CURLOPTTYPE_LONG + 271;
+
+/// Maximum connection lifetime option, which is not yet exposed by the curl
+/// crate. Requires libcurl 7.80.0 or newer.
+const CURLOPT_MAXLIFETIME_CONN: curl_sys::CURLoption = curl_sys::CURLOPTTYPE_LONG + 314;
 
 #[derive(Debug, Default)]
 pub(crate) struct ClientConfig {
     pub(crate) connection_cache_ttl: Option<Duration>,
+    pub(crate) max_connection_age: Option<Duration>,
+    pub(crate) happy_eyeballs_timeout: Option<Duration>,
     pub(crate) close_connections: bool,
     pub(crate) dns_cache: Option<DnsCache>,
     pub(crate) dns_resolve: Option<ResolveMap>,
+    pub(crate) on_complete: Option<CompletionCallback>,
+    pub(crate) curl_callbacks: Vec<CurlCallback>,
+    pub(crate) read_buffer_size: Option<usize>,
+    pub(crate) user_agent: Option<HeaderValue>,
+    pub(crate) disable_user_agent: bool,
 }
 
 impl SetOpt for ClientConfig {
@@ -18,6 +36,21 @@ impl SetOpt for ClientConfig {
             easy.maxage_conn(ttl)?;
         }
 
+        if let Some(age) = self.max_connection_age {
+            // Round up to whole seconds, since zero means no limit at all.
+            let mut secs = age.as_secs();
+
+            if age.subsec_nanos() > 0 {
+                secs += 1;
+            }
+
+            setopt_long(easy, CURLOPT_MAXLIFETIME_CONN, secs.min(c_long::MAX as u64) as c_long)?;
+        }
+
+        if let Some(timeout) = self.happy_eyeballs_timeout {
+            setopt_long(easy, CURLOPT_HAPPY_EYEBALLS_TIMEOUT_MS, timeout.as_millis() as c_long)?;
+        }
+
         if let Some(cache) = self.dns_cache.as_ref() {
             cache.set_opt(easy)?;
         }
@@ -26,6 +59,38 @@ impl SetOpt for ClientConfig {
             map.set_opt(easy)?;
         }
 
-        easy.forbid_reuse(self.close_connections)
+        // Only ever enable this, so that requests can still forbid reuse of
+        // their own connections.
+        if self.close_connections {
+            easy.forbid_reuse(true)?;
+        }
+
+        Ok(())
+    }
+}
+
+type CurlFn = dyn Fn(&mut Easy2<RequestHandler>) -> Result<(), curl::Error> + Send + Sync;
+
+/// A callback that is given direct access to the curl easy handle of every
+/// request, after Isahc has configured it.
+#[derive(Clone)]
+pub(crate) struct CurlCallback(Arc<CurlFn>);
+
+impl CurlCallback {
+    pub(crate) fn new<F>(f: F) -> Self
+    where
+        F: Fn(&mut Easy2<RequestHandler>) -> Result<(), curl::Error> + Send + Sync + 'static,
+    {
+        CurlCallback(Arc::new(f))
+    }
+
+    pub(crate) fn call(&self, easy: &mut Easy2<RequestHandler>) -> Result<(), curl::Error> {
+        (self.0)(easy)
+    }
+}
+
+impl fmt::Debug for CurlCallback {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("CurlCallback(..)")
     }
 }
@@ -102,6 +102,11 @@ impl Dialer {
     pub fn unix_socket(path: impl Into<std::path::PathBuf>) -> Self {
         Self(Inner::UnixSocket(path.into()))
     }
+
+    /// Check whether this dialer connects to a specific IP socket.
+    pub(crate) fn is_ip_socket(&self) -> bool {
+        matches!(self.0, Inner::IpSocket(_))
+    }
 }
 
 impl Default for Dialer {
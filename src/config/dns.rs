@@ -17,6 +17,10 @@ pub enum DnsCache {
     Disable,
 
     /// Enable DNS caching and keep entries in the cache for the given duration.
+    ///
+    /// The timeout has a resolution of one second. Durations that are not a
+    /// whole number of seconds are rounded up, so that a short timeout never
+    /// disables caching by accident.
     Timeout(Duration),
 
     /// Enable DNS caching and cache entries forever.
@@ -36,14 +40,30 @@ impl From<Duration> for DnsCache {
     }
 }
 
+impl DnsCache {
+    /// Get the value of `CURLOPT_DNS_CACHE_TIMEOUT` in seconds for this
+    /// configuration.
+    fn timeout_secs(&self) -> i64 {
+        match self {
+            DnsCache::Disable => 0,
+            DnsCache::Timeout(duration) => {
+                let mut secs = duration.as_secs();
+
+                if duration.subsec_nanos() > 0 {
+                    secs += 1;
+                }
+
+                secs.min(i64::MAX as u64) as i64
+            }
+            DnsCache::Forever => -1,
+        }
+    }
+}
+
 impl SetOpt for DnsCache {
     #[allow(unsafe_code)]
     fn set_opt<H>(&self, easy: &mut Easy2<H>) -> Result<(), curl::Error> {
-        let value = match self {
-            DnsCache::Disable => 0,
-            DnsCache::Timeout(duration) => duration.as_secs() as i64,
-            DnsCache::Forever => -1,
-        };
+        let value = self.timeout_secs();
 
         // Use unsafe API, because safe API doesn't let us set to -1.
         unsafe {
@@ -94,3 +114,19 @@ impl SetOpt for ResolveMap {
         easy.resolve(list)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+
+    #[test_case(DnsCache::Disable, 0)]
+    #[test_case(DnsCache::Forever, -1)]
+    #[test_case(DnsCache::default(), 60)]
+    #[test_case(Duration::from_secs(5).into(), 5)]
+    #[test_case(Duration::from_millis(500).into(), 1)]
+    #[test_case(Duration::from_millis(1500).into(), 2)]
+    fn dns_cache_timeout(cache: DnsCache, expected: i64) {
+        assert_eq!(cache.timeout_secs(), expected);
+    }
+}
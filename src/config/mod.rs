@@ -8,31 +8,36 @@
 // supported options, and also helps avoid having a massive function that does
 // all the configuring.
 //
-// When adding new config options, remember to add methods for setting the
-// option both in HttpClientBuilder and RequestBuilderExt. In addition, be sure
-// to update the client code to apply the option when configuring an easy
+// When adding new config options, add a field to the request configuration
+// and a method for setting it to the Configurable trait, which makes it
+// available on both HttpClientBuilder and request builders. In addition, be
+// sure to update the client code to apply the option when configuring an easy
 // handle.
 
 use self::{proxy::Proxy, request::SetOpt};
 use crate::{
-    auth::{Authentication, Credentials},
+    auth::{Authentication, BearerToken, Credentials},
+    cancel::CancellationToken,
     is_http_version_supported,
 };
 use curl::easy::Easy2;
-use std::{net::IpAddr, time::Duration};
+use std::{iter::FromIterator, net::IpAddr, time::Duration};
 
 pub(crate) mod client;
 pub(crate) mod dial;
 pub(crate) mod dns;
+pub(crate) mod progress;
 pub(crate) mod proxy;
 pub(crate) mod redirect;
 pub(crate) mod request;
+pub(crate) mod retry;
 pub(crate) mod ssl;
 
 pub use dial::{Dialer, DialerParseError};
 pub use dns::{DnsCache, ResolveMap};
-pub use redirect::RedirectPolicy;
-pub use ssl::{CaCertificate, ClientCertificate, PrivateKey, SslOption};
+pub use redirect::{RedirectAction, RedirectAttempt, RedirectPolicy};
+pub use retry::{RetryContext, RetryPolicy};
+pub use ssl::{CaCertificate, ClientCertificate, PrivateKey, SslOption, TlsVersion};
 
 /// Provides additional methods when building a request for configuring various
 /// execution-related options on how the request should be sent.
@@ -42,6 +47,31 @@ pub use ssl::{CaCertificate, ClientCertificate, PrivateKey, SslOption};
 /// for an [`HttpClient`](crate::HttpClient) by invoking them on an
 /// [`HttpClientBuilder`](crate::HttpClientBuilder).
 ///
+/// Options set on a request take precedence over the defaults of the client
+/// used to send it. Options not set on a request fall back to the client
+/// defaults.
+///
+/// # Examples
+///
+/// ```no_run
+/// use isahc::{config::RedirectPolicy, prelude::*, HttpClient, Request};
+/// use std::time::Duration;
+///
+/// let client = HttpClient::builder()
+///     .timeout(Duration::from_secs(30))
+///     .redirect_policy(RedirectPolicy::Follow)
+///     .build()?;
+///
+/// // Use a shorter timeout and do not follow redirects for this request only.
+/// let request = Request::get("https://example.org")
+///     .timeout(Duration::from_secs(5))
+///     .redirect_policy(RedirectPolicy::None)
+///     .body(())?;
+///
+/// let response = client.send(request)?;
+/// # Ok::<(), isahc::Error>(())
+/// ```
+///
 /// This trait is sealed and cannot be implemented for types outside of Isahc.
 pub trait Configurable: request::WithRequestConfig {
     /// Specify a maximum amount of time that a complete request/response cycle
@@ -84,6 +114,45 @@ pub trait Configurable: request::WithRequestConfig {
         })
     }
 
+    /// Specify a maximum amount of time that a request is allowed to take in
+    /// total, measured from when the request is sent until the response body
+    /// has been received.
+    ///
+    /// Unlike [`Configurable::timeout`], which only starts counting once the
+    /// transfer has started, this also includes any time spent waiting for the
+    /// request to be sent, such as when the connection limits of the client
+    /// have been reached and the request has to wait for a connection to become
+    /// available. Following redirects and retrying the request also count
+    /// towards the same limit. This makes it suitable for enforcing strict
+    /// end-to-end latency requirements.
+    ///
+    /// If the limit is reached before the request could be sent, the request
+    /// fails with a [`QueueTimeout`](crate::error::ErrorKind::QueueTimeout)
+    /// error. Otherwise it fails with a
+    /// [`Timeout`](crate::error::ErrorKind::Timeout) error, or reading the
+    /// response body fails if the response has already been received.
+    ///
+    /// If not set, no total timeout will be enforced.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use isahc::{prelude::*, HttpClient};
+    /// use std::time::Duration;
+    ///
+    /// let client = HttpClient::builder()
+    ///     .max_connections(4)
+    ///     .total_timeout(Duration::from_secs(2))
+    ///     .build()?;
+    /// # Ok::<(), isahc::Error>(())
+    /// ```
+    #[must_use = "builders have no effect if unused"]
+    fn total_timeout(self, timeout: Duration) -> Self {
+        self.with_config(move |config| {
+            config.total_timeout = Some(timeout);
+        })
+    }
+
     /// Set a timeout for establishing connections to a host.
     ///
     /// If not set, a default connect timeout of 300 seconds will be used.
@@ -97,7 +166,27 @@ pub trait Configurable: request::WithRequestConfig {
     /// Specify a maximum amount of time where transfer rate can go below
     /// a minimum speed limit. `low_speed` is that limit in bytes/s.
     ///
+    /// Unlike [`Configurable::timeout`], this does not limit how long a
+    /// transfer may take overall. A large download that is slow but still
+    /// making progress will be allowed to continue, while a transfer that has
+    /// stalled will be aborted with a
+    /// [`Timeout`](crate::error::ErrorKind::Timeout) error.
+    ///
     /// If not set, no low speed limits are imposed.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use isahc::{prelude::*, Request};
+    /// use std::time::Duration;
+    ///
+    /// // Abort if we receive less than 1 KiB/s for 30 seconds straight.
+    /// let response = Request::get("https://example.org/large-file.iso")
+    ///     .low_speed_timeout(1024, Duration::from_secs(30))
+    ///     .body(())?
+    ///     .send()?;
+    /// # Ok::<(), isahc::Error>(())
+    /// ```
     #[must_use = "builders have no effect if unused"]
     fn low_speed_timeout(self, low_speed: u32, timeout: Duration) -> Self {
         self.with_config(move |config| {
@@ -105,6 +194,74 @@ pub trait Configurable: request::WithRequestConfig {
         })
     }
 
+    /// Specify a maximum amount of time to wait for the response headers to be
+    /// received before aborting the request.
+    ///
+    /// This is measured from when the request begins to be sent until the
+    /// complete response header has been received, and includes DNS
+    /// resolution, connecting to the server, and writing the request. Unlike
+    /// [`Configurable::timeout`], this timeout no longer applies once the
+    /// response headers have arrived, so a response body may take as long as
+    /// it needs to be streamed. The two can be combined.
+    ///
+    /// If the timeout is reached, the request fails with a
+    /// [`Timeout`](crate::error::ErrorKind::Timeout) error. The timeout is
+    /// checked periodically while the request is in progress, roughly once per
+    /// second, so the request may be aborted up to about a second after the
+    /// timeout has passed.
+    ///
+    /// If not set, no header timeout will be enforced.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use isahc::{prelude::*, HttpClient};
+    /// use std::time::Duration;
+    ///
+    /// // Give up on servers that take too long to respond, but allow large
+    /// // downloads to take as long as they need.
+    /// let client = HttpClient::builder()
+    ///     .header_timeout(Duration::from_secs(10))
+    ///     .build()?;
+    /// # Ok::<(), isahc::Error>(())
+    /// ```
+    #[must_use = "builders have no effect if unused"]
+    fn header_timeout(self, timeout: Duration) -> Self {
+        self.with_config(move |config| {
+            config.header_timeout = Some(timeout);
+        })
+    }
+
+    /// Set a token that can be used to cancel requests while they are in
+    /// flight.
+    ///
+    /// When the token is cancelled, any requests using it are aborted and fail
+    /// with a [`Cancelled`](crate::error::ErrorKind::Cancelled) error. See
+    /// [`CancellationToken`] for details.
+    ///
+    /// If set on a client, every request sent by the client can be cancelled
+    /// using the same token. Since a token stays cancelled once cancelled, the
+    /// client will not be able to send any more requests afterwards.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use isahc::{prelude::*, CancellationToken, Request};
+    ///
+    /// let token = CancellationToken::new();
+    ///
+    /// let request = Request::get("https://example.org")
+    ///     .cancellation_token(token.clone())
+    ///     .body(())?;
+    /// # Ok::<(), isahc::Error>(())
+    /// ```
+    #[must_use = "builders have no effect if unused"]
+    fn cancellation_token(self, token: CancellationToken) -> Self {
+        self.with_config(move |config| {
+            config.cancellation_token = Some(token);
+        })
+    }
+
     /// Configure how the use of HTTP versions should be negotiated with the
     /// server.
     ///
@@ -137,9 +294,60 @@ pub trait Configurable: request::WithRequestConfig {
         })
     }
 
+    /// Set the application protocols to offer to the server via ALPN when
+    /// establishing a secure connection.
+    ///
+    /// ALPN is how the HTTP version to use for a secure connection is agreed
+    /// upon with the server. The protocols that can be offered are tied to the
+    /// HTTP versions the client is allowed to use, so only `h2` and `http/1.1`
+    /// are supported, and setting this overrides
+    /// [`Configurable::version_negotiation`]:
+    ///
+    /// - `h2` and `http/1.1` offers both, and HTTP/2 is preferred. This is the
+    ///   same as [`VersionNegotiation::latest_compatible`] when HTTP/2 support
+    ///   is available.
+    /// - `http/1.1` alone offers only HTTP/1.1. This is the same as
+    ///   [`VersionNegotiation::http11`].
+    /// - An empty list disables ALPN, in which case secure connections will
+    ///   use HTTP/1.1.
+    ///
+    /// Insecure connections are never affected by this option, and keep using
+    /// HTTP/1.1. Offering `h2` alone is not supported, since curl can only do
+    /// so by also using HTTP/2 for insecure connections; use
+    /// [`VersionNegotiation::http2`] instead if that is what you want.
+    ///
+    /// The order of the protocols given does not matter. Any other list of
+    /// protocols is rejected:
+    /// [`HttpClientBuilder::build`](crate::HttpClientBuilder::build) will
+    /// return an error, and requests will fail with an
+    /// [`InvalidRequest`](crate::error::ErrorKind::InvalidRequest) error.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use isahc::{prelude::*, HttpClient};
+    ///
+    /// // Never offer HTTP/2 to servers.
+    /// let client = HttpClient::builder()
+    ///     .alpn_protocols(vec!["http/1.1"])
+    ///     .build()?;
+    /// # Ok::<(), isahc::Error>(())
+    /// ```
+    #[must_use = "builders have no effect if unused"]
+    fn alpn_protocols<I, T>(self, protocols: I) -> Self
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<String>,
+    {
+        self.with_config(move |config| {
+            config.alpn_protocols = Some(protocols.into_iter().map(T::into).collect());
+        })
+    }
+
     /// Set a policy for automatically following server redirects.
     ///
-    /// The default is to not follow redirects.
+    /// The default is to not follow redirects. To decide whether to follow each
+    /// redirect yourself, use [`RedirectPolicy::custom`].
     ///
     /// # Examples
     ///
@@ -167,6 +375,46 @@ pub trait Configurable: request::WithRequestConfig {
         })
     }
 
+    /// Set a policy for automatically retrying requests that fail.
+    ///
+    /// The default policy is [`RetryPolicy::None`], which never retries a
+    /// request. See [`RetryPolicy`] for details on which requests are eligible
+    /// to be retried.
+    ///
+    /// If a response is eventually received after retrying, the number of
+    /// retries made is available via
+    /// [`ResponseExt::retry_count`](crate::ResponseExt::retry_count). If the
+    /// final attempt fails with an error, that error is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use isahc::{config::RetryPolicy, prelude::*, HttpClient};
+    /// use std::time::Duration;
+    ///
+    /// let client = HttpClient::builder()
+    ///     .retry_policy(RetryPolicy::Limit(3))
+    ///     .build()?;
+    ///
+    /// // Or decide for yourself when to retry.
+    /// let client = HttpClient::builder()
+    ///     .retry_policy(RetryPolicy::custom(|ctx| {
+    ///         if ctx.retries() < 5 && ctx.error().map_or(false, |e| e.is_timeout()) {
+    ///             Some(Duration::from_secs(1))
+    ///         } else {
+    ///             None
+    ///         }
+    ///     }))
+    ///     .build()?;
+    /// # Ok::<(), isahc::Error>(())
+    /// ```
+    #[must_use = "builders have no effect if unused"]
+    fn retry_policy(self, policy: RetryPolicy) -> Self {
+        self.with_config(move |config| {
+            config.retry_policy = Some(policy);
+        })
+    }
+
     /// Update the `Referer` header automatically when following redirects.
     #[must_use = "builders have no effect if unused"]
     fn auto_referer(self) -> Self {
@@ -175,12 +423,123 @@ pub trait Configurable: request::WithRequestConfig {
         })
     }
 
+    /// Allow sensitive headers and credentials to be forwarded when following
+    /// redirects to any of the given hosts.
+    ///
+    /// By default, when a redirect points to a different scheme, host, or port
+    /// than the request that was redirected, the `Authorization`, `Cookie`,
+    /// and `Proxy-Authorization` headers are removed from the redirected
+    /// request, along with any configured
+    /// [`credentials`](Configurable::credentials). This prevents credentials
+    /// from being leaked to a third party by a malicious or misconfigured
+    /// server.
+    ///
+    /// If you need credentials to be sent across hosts, such as when an API
+    /// redirects to a separate host that requires the same authentication,
+    /// you can list the hosts to trust here. Host names are compared
+    /// case-insensitively.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use isahc::{config::RedirectPolicy, prelude::*, HttpClient};
+    ///
+    /// let client = HttpClient::builder()
+    ///     .redirect_policy(RedirectPolicy::Follow)
+    ///     .trust_redirect_hosts(vec!["api.example.com", "files.example.com"])
+    ///     .build()?;
+    /// # Ok::<(), isahc::Error>(())
+    /// ```
+    #[must_use = "builders have no effect if unused"]
+    fn trust_redirect_hosts<I, T>(self, hosts: I) -> Self
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<String>,
+    {
+        let hosts = hosts.into_iter().map(Into::into).collect();
+
+        self.with_config(move |config| {
+            config.trusted_redirect_hosts = Some(hosts);
+        })
+    }
+
+    /// Refuse to follow redirects from an HTTPS URI to an insecure one.
+    ///
+    /// Following such a redirect would send the next request, and receive its
+    /// response, in plain text, which could allow an attacker on the network
+    /// to read or tamper with it. When enabled, a redirect from `https` to
+    /// any other scheme fails with an error of the kind
+    /// [`InsecureRedirect`](crate::error::ErrorKind::InsecureRedirect)
+    /// instead. Redirects from `http` to `http` or `https` are still followed.
+    ///
+    /// To refuse redirects to anything other than HTTPS, use
+    /// [`Configurable::redirect_https_only`].
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use isahc::{config::RedirectPolicy, prelude::*, HttpClient};
+    ///
+    /// let client = HttpClient::builder()
+    ///     .redirect_policy(RedirectPolicy::Follow)
+    ///     .deny_redirect_downgrade()
+    ///     .build()?;
+    /// # Ok::<(), isahc::Error>(())
+    /// ```
+    #[must_use = "builders have no effect if unused"]
+    fn deny_redirect_downgrade(self) -> Self {
+        self.with_config(move |config| {
+            config.deny_redirect_downgrade = Some(true);
+        })
+    }
+
+    /// Only follow redirects to HTTPS URIs.
+    ///
+    /// When enabled, a redirect to any URI whose scheme is not `https` fails
+    /// with an error of the kind
+    /// [`InsecureRedirect`](crate::error::ErrorKind::InsecureRedirect), even
+    /// if the original request was not made over HTTPS. This is stricter than
+    /// [`Configurable::deny_redirect_downgrade`].
+    #[must_use = "builders have no effect if unused"]
+    fn redirect_https_only(self) -> Self {
+        self.with_config(move |config| {
+            config.redirect_https_only = Some(true);
+        })
+    }
+
     /// Set a cookie jar to use to accept, store, and supply cookies for
     /// incoming responses and outgoing requests.
     ///
     /// A cookie jar can be shared across multiple requests or with an entire
     /// client, allowing cookies to be persisted across multiple requests.
     ///
+    /// Since cloning a cookie jar only creates a new handle to the same
+    /// cookies, the same jar can also be given to multiple clients. This lets
+    /// clients with different configurations share a single cookie session.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use isahc::{cookies::CookieJar, prelude::*, HttpClient};
+    /// use std::time::Duration;
+    ///
+    /// let jar = CookieJar::new();
+    ///
+    /// let client = HttpClient::builder()
+    ///     .cookie_jar(jar.clone())
+    ///     .build()?;
+    ///
+    /// let slow_client = HttpClient::builder()
+    ///     .cookie_jar(jar.clone())
+    ///     .timeout(Duration::from_secs(300))
+    ///     .build()?;
+    ///
+    /// // Cookies set by the server on login are also sent by the other client.
+    /// client.post("https://example.org/login", "user=me")?;
+    /// slow_client.get("https://example.org/reports/large")?;
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    ///
     /// # Availability
     ///
     /// This method is only available when the [`cookies`](index.html#cookies)
@@ -204,6 +563,23 @@ pub trait Configurable: request::WithRequestConfig {
     /// If you do not specify a specific value for the
     /// [`Accept-Encoding`](https://developer.mozilla.org/en-US/docs/Web/HTTP/Headers/Accept-Encoding)
     /// header, Isahc will set one for you automatically based on this option.
+    ///
+    /// If set to false, the response body is returned exactly as it was sent
+    /// by the server, which is useful when proxying or caching responses, or
+    /// when verifying checksums of the raw content. The `Content-Encoding`
+    /// header is always left intact on the response, so you can still tell
+    /// whether the body is compressed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use isahc::{prelude::*, HttpClient};
+    ///
+    /// let client = HttpClient::builder()
+    ///     .automatic_decompression(false)
+    ///     .build()?;
+    /// # Ok::<(), isahc::Error>(())
+    /// ```
     #[must_use = "builders have no effect if unused"]
     fn automatic_decompression(self, decompress: bool) -> Self {
         self.with_config(move |config| {
@@ -211,6 +587,90 @@ pub trait Configurable: request::WithRequestConfig {
         })
     }
 
+    /// Set which compression algorithms to advertise to the server in the
+    /// [`Accept-Encoding`](https://developer.mozilla.org/en-US/docs/Web/HTTP/Headers/Accept-Encoding)
+    /// request header when automatic decompression is enabled.
+    ///
+    /// Responses using any of the given encodings will be decoded
+    /// transparently. Encodings that the underlying curl library is not able
+    /// to decode are left out, so that servers are never asked to use them. Use
+    /// [`is_encoding_supported`](crate::is_encoding_supported) to check which
+    /// encodings are available. If none of the given encodings are available,
+    /// only uncompressed responses are asked for.
+    ///
+    /// This option has no effect if automatic decompression is disabled, or if
+    /// an `Accept-Encoding` header is set explicitly on the request.
+    ///
+    /// The default is to advertise every encoding that is available, which is
+    /// also the behavior if an empty list is given.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use isahc::{config::Encoding, prelude::*, HttpClient};
+    ///
+    /// // Only ask for gzip-compressed responses.
+    /// let client = HttpClient::builder()
+    ///     .accept_encodings(vec![Encoding::Gzip])
+    ///     .build()?;
+    /// # Ok::<(), isahc::Error>(())
+    /// ```
+    #[must_use = "builders have no effect if unused"]
+    fn accept_encodings<I>(self, encodings: I) -> Self
+    where
+        I: IntoIterator<Item = Encoding>,
+    {
+        self.with_config(move |config| {
+            config.accept_encodings = Some(encodings.into_iter().collect());
+        })
+    }
+
+    /// Compress request bodies using the given encoding, and set the
+    /// `Content-Encoding` request header accordingly.
+    ///
+    /// Only [`Encoding::Gzip`] and [`Encoding::Deflate`] are supported for
+    /// request bodies; sending a request configured with any other encoding
+    /// will return an [`InvalidRequest`](crate::error::ErrorKind::InvalidRequest)
+    /// error.
+    ///
+    /// Bodies held in memory are compressed up front, so that they can still
+    /// be sent again if a redirect or retry requires it. Streaming bodies are
+    /// compressed as they are sent, and so are sent without a known length.
+    ///
+    /// Requests without a body, or with an empty body, are sent unchanged.
+    /// Requests that already have a `Content-Encoding` header are also left
+    /// alone, since their body is assumed to be encoded already.
+    ///
+    /// Not all servers accept compressed request bodies, so only enable this
+    /// for servers known to support it. By default request bodies are not
+    /// compressed.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use isahc::{config::Encoding, prelude::*, HttpClient};
+    ///
+    /// let client = HttpClient::builder()
+    ///     .request_body_compression(Encoding::Gzip)
+    ///     .build()?;
+    ///
+    /// client.post("https://example.org/upload", "a highly compressible body")?;
+    /// # Ok::<(), isahc::Error>(())
+    /// ```
+    ///
+    /// # Availability
+    ///
+    /// This method is only available when the
+    /// [`request-compression`](index.html#request-compression) feature is
+    /// enabled.
+    #[cfg(feature = "request-compression")]
+    #[must_use = "builders have no effect if unused"]
+    fn request_body_compression(self, encoding: Encoding) -> Self {
+        self.with_config(move |config| {
+            config.request_body_compression = Some(encoding);
+        })
+    }
+
     /// Configure the use of the `Expect` request header when sending request
     /// bodies with HTTP/1.1.
     ///
@@ -310,33 +770,196 @@ pub trait Configurable: request::WithRequestConfig {
 
     /// Set the credentials to use for HTTP authentication.
     ///
-    /// This setting will do nothing unless you also set one or more
-    /// authentication methods using [`Configurable::authentication`].
-    #[must_use = "builders have no effect if unused"]
-    fn credentials(self, credentials: Credentials) -> Self {
-        self.with_config(move |config| {
-            config.credentials = Some(credentials);
-        })
-    }
-
-    /// Enable TCP keepalive with a given probe interval.
+    /// This setting will do nothing unless you also set one or more
+    /// authentication methods using [`Configurable::authentication`].
+    #[must_use = "builders have no effect if unused"]
+    fn credentials(self, credentials: Credentials) -> Self {
+        self.with_config(move |config| {
+            config.credentials = Some(credentials);
+        })
+    }
+
+    /// Authenticate requests using HTTP Basic authentication with the given
+    /// username and password.
+    ///
+    /// This is shorthand for enabling [`Authentication::basic`] and setting
+    /// [`Credentials`] at the same time, and replaces any authentication
+    /// schemes set previously. The credentials are sent with the first
+    /// request rather than waiting for the server to ask for them. An empty
+    /// password is allowed.
+    ///
+    /// The password is never included in `Debug` output.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use isahc::{prelude::*, HttpClient};
+    ///
+    /// let client = HttpClient::builder()
+    ///     .basic_auth("clark", "qwerty")
+    ///     .build()?;
+    ///
+    /// let response = client.get("https://httpbin.org/basic-auth/clark/qwerty")?;
+    /// # Ok::<(), isahc::Error>(())
+    /// ```
+    #[must_use = "builders have no effect if unused"]
+    fn basic_auth(self, username: impl Into<String>, password: impl Into<String>) -> Self {
+        let credentials = Credentials::new(username, password);
+
+        self.with_config(move |config| {
+            config.authentication = Some(Authentication::basic());
+            config.credentials = Some(credentials);
+        })
+    }
+
+    /// Authenticate requests using a bearer token.
+    ///
+    /// The token is sent in an `Authorization: Bearer <token>` header with
+    /// each request, unless the request already has an `Authorization` header
+    /// of its own. Like other credentials, the token is not sent along when
+    /// following a redirect to a different host.
+    ///
+    /// The token is never included in `Debug` output, and the resulting
+    /// header value is marked as sensitive.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use isahc::{prelude::*, HttpClient};
+    ///
+    /// let client = HttpClient::builder()
+    ///     .bearer_auth("my-secret-token")
+    ///     .build()?;
+    ///
+    /// let response = client.get("https://api.example.org/user")?;
+    /// # Ok::<(), isahc::Error>(())
+    /// ```
+    #[must_use = "builders have no effect if unused"]
+    fn bearer_auth(self, token: impl Into<String>) -> Self {
+        let token = BearerToken::new(token);
+
+        self.with_config(move |config| {
+            config.bearer_token = Some(token);
+        })
+    }
+
+    /// Enable TCP keepalive with a given probe interval.
+    ///
+    /// To also control how long a connection must be idle before the first
+    /// probe is sent and how many probes are sent, see
+    /// [`Configurable::tcp_keepalive_idle`] and
+    /// [`Configurable::tcp_keepalive_count`].
+    #[must_use = "builders have no effect if unused"]
+    fn tcp_keepalive(self, interval: Duration) -> Self {
+        self.with_config(move |config| {
+            config.tcp_keepalive = Some(interval);
+        })
+    }
+
+    /// Enable TCP keepalive, and set how long a connection must be idle before
+    /// the first keepalive probe is sent.
+    ///
+    /// Some firewalls and NAT devices drop connections that have been idle for
+    /// a short time. Setting the idle time below that window keeps such
+    /// connections alive, which the probe interval set with
+    /// [`Configurable::tcp_keepalive`] alone cannot do.
+    ///
+    /// The idle time is rounded down to whole seconds.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use isahc::{prelude::*, HttpClient};
+    /// use std::time::Duration;
+    ///
+    /// let client = HttpClient::builder()
+    ///     .tcp_keepalive_idle(Duration::from_secs(30))
+    ///     .tcp_keepalive(Duration::from_secs(10))
+    ///     .tcp_keepalive_count(3)
+    ///     .build()?;
+    /// # Ok::<(), isahc::Error>(())
+    /// ```
+    #[must_use = "builders have no effect if unused"]
+    fn tcp_keepalive_idle(self, idle: Duration) -> Self {
+        self.with_config(move |config| {
+            config.tcp_keepalive_idle = Some(idle);
+        })
+    }
+
+    /// Enable TCP keepalive, and set the number of unanswered keepalive probes
+    /// to send before the connection is considered dead.
+    ///
+    /// This option requires libcurl 8.9.0 or newer. If an older version is in
+    /// use, sending a request will fail.
+    #[must_use = "builders have no effect if unused"]
+    fn tcp_keepalive_count(self, count: u32) -> Self {
+        self.with_config(move |config| {
+            config.tcp_keepalive_count = Some(count);
+        })
+    }
+
+    /// Enables the `TCP_NODELAY` option on connect.
+    #[must_use = "builders have no effect if unused"]
+    fn tcp_nodelay(self) -> Self {
+        self.with_config(move |config| {
+            config.tcp_nodelay = Some(true);
+        })
+    }
+
+    /// Always open a new connection for requests, instead of reusing an
+    /// existing connection from the connection cache.
+    ///
+    /// The new connection may still be added to the connection cache and be
+    /// reused by later requests afterwards. Use
+    /// [`Configurable::forbid_reuse`] to prevent that as well. This is useful
+    /// for measuring the latency of establishing connections, or for spreading
+    /// requests over several servers behind a load balancer.
+    ///
+    /// The default value is `false`.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use isahc::{prelude::*, Request};
+    ///
+    /// let response = Request::get("https://example.org")
+    ///     .fresh_connect(true)
+    ///     .body(())?
+    ///     .send()?;
+    ///
+    /// assert_eq!(response.connection_reused(), Some(false));
+    /// # Ok::<(), isahc::Error>(())
+    /// ```
     #[must_use = "builders have no effect if unused"]
-    fn tcp_keepalive(self, interval: Duration) -> Self {
+    fn fresh_connect(self, enable: bool) -> Self {
         self.with_config(move |config| {
-            config.tcp_keepalive = Some(interval);
+            config.fresh_connect = Some(enable);
         })
     }
 
-    /// Enables the `TCP_NODELAY` option on connect.
+    /// Close the connection used by requests once they are complete, instead
+    /// of keeping it in the connection cache for reuse by later requests.
+    ///
+    /// Setting the connection cache size of a client to zero using
+    /// [`HttpClientBuilder::connection_cache_size`](crate::HttpClientBuilder::connection_cache_size)
+    /// has the same effect for all requests sent by the client.
+    ///
+    /// The default value is `false`.
     #[must_use = "builders have no effect if unused"]
-    fn tcp_nodelay(self) -> Self {
+    fn forbid_reuse(self, enable: bool) -> Self {
         self.with_config(move |config| {
-            config.tcp_nodelay = Some(true);
+            config.forbid_reuse = Some(enable);
         })
     }
 
     /// Bind local socket connections to a particular network interface.
     ///
+    /// The interface is not validated until a connection is actually made. If
+    /// the given interface does not exist or cannot be bound to, sending the
+    /// request will fail with a
+    /// [`ClientInitialization`](crate::error::ErrorKind::ClientInitialization)
+    /// error describing the interface that could not be used.
+    ///
     /// # Examples
     ///
     /// Bind to an IP address.
@@ -385,6 +1008,25 @@ pub trait Configurable: request::WithRequestConfig {
     /// This does not affect requests with an explicit IP address as the host.
     ///
     /// The default is [`IpVersion::Any`].
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use isahc::{config::IpVersion, prelude::*, HttpClient, Request};
+    ///
+    /// // Work around a network with broken IPv6 connectivity.
+    /// let client = HttpClient::builder()
+    ///     .ip_version(IpVersion::V4)
+    ///     .build()?;
+    ///
+    /// // Check whether a host is reachable over IPv6 for a single request.
+    /// let response = client.send(
+    ///     Request::get("https://example.org")
+    ///         .ip_version(IpVersion::V6)
+    ///         .body(())?,
+    /// )?;
+    /// # Ok::<(), isahc::Error>(())
+    /// ```
     #[must_use = "builders have no effect if unused"]
     fn ip_version(self, version: IpVersion) -> Self {
         self.with_config(move |config| {
@@ -452,10 +1094,23 @@ pub trait Configurable: request::WithRequestConfig {
     /// - **`socks5`**: SOCKS5 Proxy.
     /// - **`socks5h`**: SOCKS5 Proxy. Proxy resolves URL hostname.
     ///
-    /// By default no proxy will be used, unless one is specified in either the
-    /// `http_proxy` or `https_proxy` environment variables.
+    /// By default no proxy will be used, unless one is specified in the
+    /// environment. The following environment variables are honored, in the
+    /// same way as the `curl` command-line tool:
+    ///
+    /// - **`http_proxy`**: Proxy to use for `http://` URIs. Only the lowercase
+    ///   form is checked, since `HTTP_PROXY` may be set by an attacker via a
+    ///   request header in CGI-like environments.
+    /// - **`https_proxy`** or **`HTTPS_PROXY`**: Proxy to use for `https://`
+    ///   URIs.
+    /// - **`all_proxy`** or **`ALL_PROXY`**: Proxy to use for any URI if a more
+    ///   specific variable is not set.
+    /// - **`no_proxy`** or **`NO_PROXY`**: A comma-separated list of hosts that
+    ///   should be connected to directly. Entries match the host exactly or any
+    ///   subdomain of it, and a single `*` disables the proxy for all hosts.
     ///
-    /// Setting to `None` explicitly disables the use of a proxy.
+    /// Setting a proxy explicitly overrides any proxy set in the environment,
+    /// and setting to `None` explicitly disables the use of a proxy.
     ///
     /// # Examples
     ///
@@ -489,6 +1144,16 @@ pub trait Configurable: request::WithRequestConfig {
 
     /// Disable proxy usage for the provided list of hosts.
     ///
+    /// Requests to hosts in this list will connect directly, even if a proxy
+    /// is set explicitly or in the environment. Each entry matches either the
+    /// host name exactly, or any subdomain of it; for example, `example.com`
+    /// matches both `example.com` and `api.example.com`, but not
+    /// `myexample.com`. IP addresses are also accepted and match exactly. The
+    /// special entry `*` disables the proxy for all hosts.
+    ///
+    /// Setting this list overrides any list set in the `no_proxy` environment
+    /// variable.
+    ///
     /// # Examples
     ///
     /// ```
@@ -573,6 +1238,80 @@ pub trait Configurable: request::WithRequestConfig {
         })
     }
 
+    /// Set the preferred size of the buffer used to receive the response, in
+    /// bytes.
+    ///
+    /// A larger buffer can improve throughput for large downloads over links
+    /// with a high bandwidth-delay product, while a smaller buffer reduces
+    /// memory usage when many transfers are active at once. The size is only a
+    /// hint, and is clamped by curl to between 1 kilobyte and 10 megabytes.
+    ///
+    /// The default is 16 kilobytes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use isahc::{prelude::*, HttpClient};
+    ///
+    /// let client = HttpClient::builder()
+    ///     .buffer_size(512 * 1024)
+    ///     .build()?;
+    /// # Ok::<(), isahc::Error>(())
+    /// ```
+    #[must_use = "builders have no effect if unused"]
+    fn buffer_size(self, size: usize) -> Self {
+        self.with_config(move |config| {
+            config.buffer_size = Some(size);
+        })
+    }
+
+    /// Set the preferred size of the buffer used to send the request body, in
+    /// bytes.
+    ///
+    /// Like [`Configurable::buffer_size`], this is only a hint and is clamped
+    /// by curl to between 16 kilobytes and 2 megabytes.
+    ///
+    /// The default is 64 kilobytes.
+    #[must_use = "builders have no effect if unused"]
+    fn upload_buffer_size(self, size: usize) -> Self {
+        self.with_config(move |config| {
+            config.upload_buffer_size = Some(size);
+        })
+    }
+
+    /// Set a maximum size for the response body, in bytes.
+    ///
+    /// If the server sends more data than this, the transfer is aborted and
+    /// reading the response body will fail with a
+    /// [`ResponseBodyTooLarge`](crate::error::ErrorKind::ResponseBodyTooLarge)
+    /// error. If the limit is exceeded before the response is returned, then
+    /// the request itself will fail with the same error.
+    ///
+    /// The limit is checked against the bytes actually received rather than
+    /// any `Content-Length` declared by the server, so it also applies to
+    /// responses of unknown length. If automatic decompression is enabled, the
+    /// limit applies to the decompressed body.
+    ///
+    /// The default is unlimited.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use isahc::{prelude::*, HttpClient};
+    ///
+    /// // Refuse to download more than 10 MiB.
+    /// let client = HttpClient::builder()
+    ///     .max_response_body_size(10 * 1024 * 1024)
+    ///     .build()?;
+    /// # Ok::<(), isahc::Error>(())
+    /// ```
+    #[must_use = "builders have no effect if unused"]
+    fn max_response_body_size(self, max: u64) -> Self {
+        self.with_config(move |config| {
+            config.max_response_body_size = Some(max);
+        })
+    }
+
     /// Set a custom SSL/TLS client certificate to use for client connections.
     ///
     /// If a format is not supported by the underlying SSL/TLS engine, an error
@@ -615,6 +1354,28 @@ pub trait Configurable: request::WithRequestConfig {
     ///     .build()?;
     /// # Ok::<(), isahc::Error>(())
     /// ```
+    ///
+    /// Certificates and keys can also be supplied directly from memory, such
+    /// as when they are loaded from a secret store, without writing them to
+    /// disk first:
+    ///
+    /// ```
+    /// use isahc::{
+    ///     config::{ClientCertificate, PrivateKey},
+    ///     prelude::*,
+    ///     HttpClient,
+    /// };
+    ///
+    /// # let cert_bytes = Vec::new();
+    /// # let key_bytes = Vec::new();
+    /// let client = HttpClient::builder()
+    ///     .ssl_client_certificate(ClientCertificate::pem(
+    ///         cert_bytes,
+    ///         PrivateKey::pem(key_bytes, None),
+    ///     ))
+    ///     .build()?;
+    /// # Ok::<(), isahc::Error>(())
+    /// ```
     #[must_use = "builders have no effect if unused"]
     fn ssl_client_certificate(self, certificate: ClientCertificate) -> Self {
         self.with_config(move |config| {
@@ -641,6 +1402,12 @@ pub trait Configurable: request::WithRequestConfig {
     /// let client = HttpClient::builder()
     ///     .ssl_ca_certificate(CaCertificate::file("ca.pem"))
     ///     .build()?;
+    ///
+    /// // Certificates can also be loaded from memory.
+    /// # let pem_bytes = Vec::new();
+    /// let client = HttpClient::builder()
+    ///     .ssl_ca_certificate(CaCertificate::pem(pem_bytes))
+    ///     .build()?;
     /// # Ok::<(), isahc::Error>(())
     /// ```
     #[must_use = "builders have no effect if unused"]
@@ -668,6 +1435,101 @@ pub trait Configurable: request::WithRequestConfig {
         })
     }
 
+    /// Pin the public key of the server, rejecting any connection where the
+    /// server does not present one of the given public keys.
+    ///
+    /// Each key should be given as a base64-encoded SHA-256 hash of the
+    /// server's public key, prefixed with `sha256//`. When multiple keys are
+    /// given, the connection is allowed if any one of them matches. If the
+    /// server's public key does not match, the request will fail with a
+    /// [`BadServerCertificate`](crate::error::ErrorKind::BadServerCertificate)
+    /// error.
+    ///
+    /// Public key pinning is a defense against man-in-the-middle attacks using
+    /// certificates issued by a compromised certificate authority. Keep in
+    /// mind that if the server's key is rotated, requests will fail until the
+    /// pinned keys are updated.
+    ///
+    /// The default is unset and no pinning is performed.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use isahc::{prelude::*, HttpClient};
+    ///
+    /// let client = HttpClient::builder()
+    ///     .ssl_pinned_public_key(vec![
+    ///         "sha256//YhKJKSzoTt2b5FP18fvpHo7fJYqQCjAa3HWY3tvRMwE=",
+    ///         "sha256//t62CeU2tQiqkexU74Gxa2eg7fRbEgoChTociMee9wno=",
+    ///     ])
+    ///     .build()?;
+    /// # Ok::<(), isahc::Error>(())
+    /// ```
+    #[must_use = "builders have no effect if unused"]
+    fn ssl_pinned_public_key<I, T>(self, keys: I) -> Self
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<String>,
+    {
+        self.with_config(move |config| {
+            config.ssl_pinned_public_keys = Some(keys.into_iter().map(T::into).collect());
+        })
+    }
+
+    /// Set the minimum version of TLS that may be negotiated with the server.
+    ///
+    /// Connections to servers that do not support this version or newer will
+    /// fail. Depending on the SSL/TLS engine in use, some older versions may
+    /// already be disabled regardless of this setting.
+    ///
+    /// The default is unset and will result in the default minimum version of
+    /// the SSL/TLS engine being used.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use isahc::{config::TlsVersion, prelude::*, HttpClient};
+    ///
+    /// // Refuse to use TLS 1.0 or 1.1.
+    /// let client = HttpClient::builder()
+    ///     .ssl_min_version(TlsVersion::V1_2)
+    ///     .build()?;
+    /// # Ok::<(), isahc::Error>(())
+    /// ```
+    #[must_use = "builders have no effect if unused"]
+    fn ssl_min_version(self, version: TlsVersion) -> Self {
+        self.with_config(move |config| {
+            config.ssl_min_version = Some(version);
+        })
+    }
+
+    /// Set the maximum version of TLS that may be negotiated with the server.
+    ///
+    /// This is mostly useful for testing compatibility with servers or
+    /// intermediaries that do not yet support newer protocol versions.
+    ///
+    /// The default is unset and will result in the newest version supported
+    /// by the SSL/TLS engine being allowed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use isahc::{config::TlsVersion, prelude::*, HttpClient};
+    ///
+    /// // Only use TLS 1.2.
+    /// let client = HttpClient::builder()
+    ///     .ssl_min_version(TlsVersion::V1_2)
+    ///     .ssl_max_version(TlsVersion::V1_2)
+    ///     .build()?;
+    /// # Ok::<(), isahc::Error>(())
+    /// ```
+    #[must_use = "builders have no effect if unused"]
+    fn ssl_max_version(self, version: TlsVersion) -> Self {
+        self.with_config(move |config| {
+            config.ssl_max_version = Some(version);
+        })
+    }
+
     /// Set various options for this request that control SSL/TLS behavior.
     ///
     /// Most options are for disabling security checks that introduce security
@@ -709,6 +1571,74 @@ pub trait Configurable: request::WithRequestConfig {
         })
     }
 
+    /// Require the server to staple a valid OCSP response to its certificate
+    /// during the TLS handshake.
+    ///
+    /// When enabled, the status of the server certificate is checked using
+    /// the OCSP response stapled by the server, and the request fails with a
+    /// [`BadServerCertificate`](crate::error::ErrorKind::BadServerCertificate)
+    /// error if the server does not provide one, or if the certificate has been
+    /// revoked. This provides a strong guarantee that the certificate has not
+    /// been revoked, but only works with servers that support OCSP stapling.
+    ///
+    /// Not all TLS engines support verifying OCSP responses, in which case
+    /// requests will fail if this is enabled.
+    ///
+    /// The default value is `false`.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use isahc::{prelude::*, HttpClient};
+    ///
+    /// let client = HttpClient::builder()
+    ///     .ssl_verify_status(true)
+    ///     .build()?;
+    /// # Ok::<(), isahc::Error>(())
+    /// ```
+    #[must_use = "builders have no effect if unused"]
+    fn ssl_verify_status(self, enable: bool) -> Self {
+        self.with_config(move |config| {
+            config.ssl_verify_status = Some(enable);
+        })
+    }
+
+    /// Set the server name to present to the server, instead of the host in
+    /// the request URI.
+    ///
+    /// Requests are still sent to the host given in the URI, but the given
+    /// name is used everywhere else the host would be: as the server name
+    /// (SNI) sent during the TLS handshake, to validate the certificate of the
+    /// server, and in the `Host` header unless one is set explicitly. This is
+    /// useful when connecting to a server by its IP address, but the server
+    /// selects which site to serve based on the server name, such as multiple
+    /// environments sharing a single address.
+    ///
+    /// If only the address needs to be overridden for a given host name, it is
+    /// usually simpler to use the host name in the URI and override its
+    /// address instead, using
+    /// [`HttpClientBuilder::dns_resolve`](crate::HttpClientBuilder::dns_resolve)
+    /// or [`Configurable::dial`]. The server name always follows the host in
+    /// the URI in that case.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use isahc::{prelude::*, Request};
+    ///
+    /// let response = Request::get("https://203.0.113.10/status")
+    ///     .ssl_server_name("staging.example.org")
+    ///     .body(())?
+    ///     .send()?;
+    /// # Ok::<(), isahc::Error>(())
+    /// ```
+    #[must_use = "builders have no effect if unused"]
+    fn ssl_server_name(self, name: impl Into<String>) -> Self {
+        self.with_config(move |config| {
+            config.ssl_server_name = Some(name.into());
+        })
+    }
+
     /// Enable or disable sending HTTP header names in Title-Case instead of
     /// lowercase form.
     ///
@@ -747,6 +1677,83 @@ pub trait Configurable: request::WithRequestConfig {
             config.enable_metrics = Some(enable);
         })
     }
+
+    /// Set a function to be called periodically with the progress of sending
+    /// the request body.
+    ///
+    /// The function is given the number of bytes of the request body sent so
+    /// far, and the total size of the request body if it is known in advance.
+    /// It is called whenever the number of bytes sent changes, which makes it
+    /// suitable for driving a progress bar for large uploads.
+    ///
+    /// The function is called from the thread that drives all requests for the
+    /// client, so it should return quickly and must never block, such as by
+    /// waiting on a lock held by code awaiting the response. Otherwise the
+    /// progress of all other requests using the same client will be stalled.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use isahc::{prelude::*, Request};
+    ///
+    /// let response = Request::put("https://httpbin.org/put")
+    ///     .on_upload_progress(|sent, total| match total {
+    ///         Some(total) => println!("sent {} of {} bytes", sent, total),
+    ///         None => println!("sent {} bytes", sent),
+    ///     })
+    ///     .body(vec![0; 10 * 1024 * 1024])?
+    ///     .send()?;
+    /// # Ok::<(), isahc::Error>(())
+    /// ```
+    #[must_use = "builders have no effect if unused"]
+    fn on_upload_progress<F>(self, f: F) -> Self
+    where
+        F: Fn(u64, Option<u64>) + Send + Sync + 'static,
+    {
+        self.with_config(move |config| {
+            config.upload_progress = Some(progress::ProgressCallback::new(f));
+        })
+    }
+
+    /// Set a function to be called periodically with the progress of
+    /// receiving the response body.
+    ///
+    /// The function is given the number of bytes of the response body received
+    /// so far, and the total size of the response body if the server declared
+    /// it with a `Content-Length` header. Both values count bytes as they are
+    /// received over the network, before any automatic decompression. The
+    /// function is called whenever the number of bytes received changes, at
+    /// most as often as curl reports progress.
+    ///
+    /// The function is called from the thread that drives all requests for the
+    /// client, so it should return quickly and must never block. See
+    /// [`Configurable::on_upload_progress`] for more details.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use isahc::{prelude::*, Request};
+    ///
+    /// let mut response = Request::get("https://example.org/large-file.iso")
+    ///     .on_download_progress(|received, total| match total {
+    ///         Some(total) => println!("received {} of {} bytes", received, total),
+    ///         None => println!("received {} bytes", received),
+    ///     })
+    ///     .body(())?
+    ///     .send()?;
+    ///
+    /// response.copy_to_file("large-file.iso")?;
+    /// # Ok::<(), isahc::Error>(())
+    /// ```
+    #[must_use = "builders have no effect if unused"]
+    fn on_download_progress<F>(self, f: F) -> Self
+    where
+        F: Fn(u64, Option<u64>) + Send + Sync + 'static,
+    {
+        self.with_config(move |config| {
+            config.download_progress = Some(progress::ProgressCallback::new(f));
+        })
+    }
 }
 
 /// A strategy for selecting what HTTP versions should be used when
@@ -815,7 +1822,24 @@ impl VersionNegotiation {
     /// always result in an error.
     ///
     /// This strategy is often referred to as [HTTP/2 with Prior
-    /// Knowledge](https://http2.github.io/http2-spec/#known-http).
+    /// Knowledge](https://http2.github.io/http2-spec/#known-http). For insecure
+    /// `http://` URIs, no `Upgrade` request is made and the connection begins
+    /// speaking HTTP/2 immediately, which makes it suitable for cleartext HTTP/2
+    /// (h2c) services that are known to support it.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use isahc::{config::VersionNegotiation, prelude::*, Request};
+    ///
+    /// let response = Request::get("http://internal-service:8080/status")
+    ///     .version_negotiation(VersionNegotiation::http2())
+    ///     .body(())?
+    ///     .send()?;
+    ///
+    /// assert_eq!(response.version(), isahc::http::Version::HTTP_2);
+    /// # Ok::<(), isahc::Error>(())
+    /// ```
     pub const fn http2() -> Self {
         Self(VersionNegotiationInner::Strict(
             curl::easy::HttpVersion::V2PriorKnowledge,
@@ -939,7 +1963,7 @@ impl SetOpt for NetworkInterface {
 }
 
 /// Supported IP versions that can be used.
-#[derive(Clone, Debug)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
 pub enum IpVersion {
     /// Use IPv4 addresses only. IPv6 addresses will be ignored.
     V4,
@@ -970,6 +1994,72 @@ impl SetOpt for IpVersion {
     }
 }
 
+/// A compression algorithm that may be used to encode response bodies.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Encoding {
+    /// The [gzip](https://tools.ietf.org/html/rfc1952) format.
+    Gzip,
+
+    /// The [zlib](https://tools.ietf.org/html/rfc1950) format, using the
+    /// deflate algorithm.
+    Deflate,
+
+    /// The [Brotli](https://tools.ietf.org/html/rfc7932) format.
+    Brotli,
+
+    /// The [Zstandard](https://tools.ietf.org/html/rfc8878) format.
+    ///
+    /// Decoding this format requires a curl library built with Zstandard
+    /// support, which the version of curl included with the `static-curl`
+    /// feature is not.
+    Zstd,
+}
+
+impl Encoding {
+    /// Get the name of this encoding as used in HTTP headers.
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            Encoding::Gzip => "gzip",
+            Encoding::Deflate => "deflate",
+            Encoding::Brotli => "br",
+            Encoding::Zstd => "zstd",
+        }
+    }
+}
+
+/// A list of encodings to advertise in the `Accept-Encoding` header.
+#[derive(Clone, Debug)]
+pub(crate) struct AcceptEncodings(String);
+
+impl AcceptEncodings {
+    pub(crate) fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl FromIterator<Encoding> for AcceptEncodings {
+    fn from_iter<I: IntoIterator<Item = Encoding>>(iter: I) -> Self {
+        let encodings = iter.into_iter().collect::<Vec<_>>();
+
+        // An empty string tells curl to advertise every encoding available.
+        if encodings.is_empty() {
+            return AcceptEncodings(String::new());
+        }
+
+        let supported = encodings
+            .into_iter()
+            .filter(|encoding| crate::is_encoding_supported(*encoding))
+            .map(|encoding| encoding.as_str())
+            .collect::<Vec<_>>();
+
+        if supported.is_empty() {
+            AcceptEncodings(String::from("identity"))
+        } else {
+            AcceptEncodings(supported.join(", "))
+        }
+    }
+}
+
 /// Controls the use of the `Expect` request header when sending request bodies
 /// with HTTP/1.1.
 ///
@@ -1011,6 +2101,10 @@ impl ExpectContinue {
     }
 
     /// Disable the use and handling of the `Expect` request header.
+    ///
+    /// The request body is sent immediately after the request headers without
+    /// waiting for the server. Any `Expect` header set on the request
+    /// explicitly is not sent either.
     pub const fn disabled() -> Self {
         Self {
             timeout: None,
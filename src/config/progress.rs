@@ -0,0 +1,27 @@
+//! Configuration options related to transfer progress reporting.
+
+use std::{fmt, sync::Arc};
+
+/// A callback that is invoked with the number of body bytes transferred so
+/// far, and the total size of the body if known.
+#[derive(Clone)]
+pub(crate) struct ProgressCallback(Arc<dyn Fn(u64, Option<u64>) + Send + Sync>);
+
+impl ProgressCallback {
+    pub(crate) fn new<F>(f: F) -> Self
+    where
+        F: Fn(u64, Option<u64>) + Send + Sync + 'static,
+    {
+        ProgressCallback(Arc::new(f))
+    }
+
+    pub(crate) fn call(&self, transferred: u64, total: Option<u64>) {
+        (self.0)(transferred, total)
+    }
+}
+
+impl fmt::Debug for ProgressCallback {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("ProgressCallback(..)")
+    }
+}
@@ -1,7 +1,10 @@
+use http::{StatusCode, Uri};
+use std::{fmt, sync::Arc};
+
 /// Describes a policy for handling server redirects.
 ///
 /// The default is to not follow redirects.
-#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[derive(Clone)]
 pub enum RedirectPolicy {
     /// Do not apply any special treatment to redirect responses. The response
     /// will be returned as-is and redirects will not be followed.
@@ -14,6 +17,53 @@ pub enum RedirectPolicy {
 
     /// Follow redirects automatically up to a maximum number of redirects.
     Limit(u32),
+
+    /// Decide whether to follow each redirect using a custom function.
+    ///
+    /// The function is called for every redirect response received, and
+    /// returns a [`RedirectAction`] indicating whether the redirect should be
+    /// followed, followed to a different location, or not followed at all. A
+    /// large limit on the total number of redirects is still enforced to
+    /// prevent infinite redirect loops.
+    #[allow(clippy::type_complexity)]
+    Custom(Arc<dyn Fn(&RedirectAttempt<'_>) -> RedirectAction + Send + Sync>),
+}
+
+impl RedirectPolicy {
+    /// Create a redirect policy that uses a custom function to decide whether
+    /// to follow a redirect.
+    ///
+    /// See [`RedirectPolicy::Custom`] for details.
+    ///
+    /// # Examples
+    ///
+    /// Only follow redirects that stay on the same host:
+    ///
+    /// ```no_run
+    /// use isahc::{
+    ///     config::{RedirectAction, RedirectPolicy},
+    ///     prelude::*,
+    ///     Request,
+    /// };
+    ///
+    /// let response = Request::get("https://example.org")
+    ///     .redirect_policy(RedirectPolicy::custom(|attempt| {
+    ///         if attempt.location().host() == attempt.uri().host() {
+    ///             RedirectAction::Follow
+    ///         } else {
+    ///             RedirectAction::Stop
+    ///         }
+    ///     }))
+    ///     .body(())?
+    ///     .send()?;
+    /// # Ok::<(), isahc::Error>(())
+    /// ```
+    pub fn custom<F>(f: F) -> Self
+    where
+        F: Fn(&RedirectAttempt<'_>) -> RedirectAction + Send + Sync + 'static,
+    {
+        RedirectPolicy::Custom(Arc::new(f))
+    }
 }
 
 impl Default for RedirectPolicy {
@@ -21,3 +71,79 @@ impl Default for RedirectPolicy {
         RedirectPolicy::None
     }
 }
+
+impl PartialEq for RedirectPolicy {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (RedirectPolicy::None, RedirectPolicy::None) => true,
+            (RedirectPolicy::Follow, RedirectPolicy::Follow) => true,
+            (RedirectPolicy::Limit(a), RedirectPolicy::Limit(b)) => a == b,
+            // Custom policies are only equal if they share the same function.
+            (RedirectPolicy::Custom(a), RedirectPolicy::Custom(b)) => Arc::ptr_eq(a, b),
+            _ => false,
+        }
+    }
+}
+
+impl Eq for RedirectPolicy {}
+
+impl fmt::Debug for RedirectPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RedirectPolicy::None => f.write_str("None"),
+            RedirectPolicy::Follow => f.write_str("Follow"),
+            RedirectPolicy::Limit(limit) => f.debug_tuple("Limit").field(limit).finish(),
+            RedirectPolicy::Custom(_) => f.write_str("Custom(..)"),
+        }
+    }
+}
+
+/// Information about a redirect received from the server, used by
+/// [`RedirectPolicy::Custom`] to decide whether it should be followed.
+#[derive(Debug)]
+pub struct RedirectAttempt<'a> {
+    pub(crate) status: StatusCode,
+    pub(crate) uri: &'a Uri,
+    pub(crate) location: &'a Uri,
+    pub(crate) redirects: u32,
+}
+
+impl RedirectAttempt<'_> {
+    /// Get the redirect status code returned by the server.
+    pub fn status(&self) -> StatusCode {
+        self.status
+    }
+
+    /// Get the URI of the request that was redirected.
+    pub fn uri(&self) -> &Uri {
+        self.uri
+    }
+
+    /// Get the URI the server is redirecting to, resolved against the URI of
+    /// the request.
+    pub fn location(&self) -> &Uri {
+        self.location
+    }
+
+    /// Get the number of redirects that have been followed so far. This is
+    /// zero for the first redirect.
+    pub fn redirects(&self) -> u32 {
+        self.redirects
+    }
+}
+
+/// The action to take for a redirect, as decided by
+/// [`RedirectPolicy::Custom`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum RedirectAction {
+    /// Follow the redirect to the location given by the server.
+    Follow,
+
+    /// Follow the redirect, but to the given URI instead of the location given
+    /// by the server. A relative URI is resolved against the URI of the
+    /// request that was redirected.
+    FollowTo(Uri),
+
+    /// Do not follow the redirect, and return the redirect response as-is.
+    Stop,
+}
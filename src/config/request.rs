@@ -2,6 +2,19 @@
 
 use super::{proxy::Proxy, *};
 use curl::easy::Easy2;
+use std::{
+    os::raw::c_long,
+    sync::{atomic::AtomicBool, Arc},
+    time::Instant,
+};
+
+/// TCP keepalive probe count option, which is not yet exposed by the curl
+/// crate. Requires libcurl 8.9.0 or newer.
+const CURLOPT_TCP_KEEPCNT: curl_sys::CURLoption = curl_sys::CURLOPTTYPE_LONG + 326;
+
+/// OCSP stapling verification option, which is not yet exposed by the curl
+/// crate.
+const CURLOPT_SSL_VERIFYSTATUS: curl_sys::CURLoption = curl_sys::CURLOPTTYPE_LONG + 232;
 
 /// Base trait for any object that can be configured for requests, such as an
 /// HTTP request builder or an HTTP client.
@@ -17,9 +30,24 @@ pub(crate) trait SetOpt {
     fn set_opt<H>(&self, easy: &mut Easy2<H>) -> Result<(), curl::Error>;
 }
 
+/// Set a long option that is not yet exposed by the curl crate.
+pub(crate) fn setopt_long<H>(
+    easy: &mut Easy2<H>,
+    option: curl_sys::CURLoption,
+    value: c_long,
+) -> Result<(), curl::Error> {
+    #[allow(unsafe_code)]
+    unsafe {
+        match curl_sys::curl_easy_setopt(easy.raw(), option, value) {
+            curl_sys::CURLE_OK => Ok(()),
+            code => Err(curl::Error::new(code)),
+        }
+    }
+}
+
 // Define this struct inside a macro to reduce some boilerplate.
 macro_rules! define_request_config {
-    ($($field:ident: $t:ty,)*) => {
+    ($($(#[$meta:meta])* $field:ident: $t:ty,)*) => {
         /// Configuration for an HTTP request.
         ///
         /// This struct is not exposed directly, but rather is interacted with
@@ -27,6 +55,7 @@ macro_rules! define_request_config {
         #[derive(Clone, Debug, Default)]
         pub struct RequestConfig {
             $(
+                $(#[$meta])*
                 pub(crate) $field: $t,
             )*
         }
@@ -50,9 +79,12 @@ macro_rules! define_request_config {
             /// config.
             pub(crate) fn merge(&mut self, defaults: &Self) {
                 $(
-                    if self.$field.is_none() {
-                        if let Some(value) = defaults.$field.as_ref() {
-                            self.$field = Some(value.clone());
+                    $(#[$meta])*
+                    {
+                        if self.$field.is_none() {
+                            if let Some(value) = defaults.$field.as_ref() {
+                                self.$field = Some(value.clone());
+                            }
                         }
                     }
                 )*
@@ -66,13 +98,20 @@ define_request_config! {
     timeout: Option<Duration>,
     connect_timeout: Option<Duration>,
     low_speed_timeout: Option<(u32, Duration)>,
+    header_timeout: Option<Duration>,
     version_negotiation: Option<VersionNegotiation>,
+    alpn_protocols: Option<ssl::AlpnProtocols>,
     automatic_decompression: Option<bool>,
+    accept_encodings: Option<AcceptEncodings>,
     expect_continue: Option<ExpectContinue>,
     authentication: Option<Authentication>,
     credentials: Option<Credentials>,
     tcp_keepalive: Option<Duration>,
+    tcp_keepalive_idle: Option<Duration>,
+    tcp_keepalive_count: Option<u32>,
     tcp_nodelay: Option<bool>,
+    fresh_connect: Option<bool>,
+    forbid_reuse: Option<bool>,
     interface: Option<NetworkInterface>,
     ip_version: Option<IpVersion>,
     dial: Option<Dialer>,
@@ -82,16 +121,39 @@ define_request_config! {
     proxy_credentials: Option<Proxy<Credentials>>,
     max_upload_speed: Option<u64>,
     max_download_speed: Option<u64>,
+    buffer_size: Option<usize>,
+    upload_buffer_size: Option<usize>,
     ssl_client_certificate: Option<ClientCertificate>,
     ssl_ca_certificate: Option<CaCertificate>,
     ssl_ciphers: Option<ssl::Ciphers>,
+    ssl_pinned_public_keys: Option<ssl::PinnedPublicKeys>,
+    ssl_min_version: Option<TlsVersion>,
+    ssl_max_version: Option<TlsVersion>,
     ssl_options: Option<SslOption>,
+    ssl_verify_status: Option<bool>,
+    ssl_server_name: Option<String>,
     enable_metrics: Option<bool>,
+    upload_progress: Option<progress::ProgressCallback>,
+    download_progress: Option<progress::ProgressCallback>,
+    max_response_body_size: Option<u64>,
+    cancellation_token: Option<CancellationToken>,
 
     // Used by interceptors
     redirect_policy: Option<RedirectPolicy>,
+    retry_policy: Option<RetryPolicy>,
     auto_referer: Option<bool>,
+    trusted_redirect_hosts: Option<Vec<String>>,
+    deny_redirect_downgrade: Option<bool>,
+    redirect_https_only: Option<bool>,
     title_case_headers: Option<bool>,
+    bearer_token: Option<BearerToken>,
+    #[cfg(feature = "request-compression")]
+    request_body_compression: Option<Encoding>,
+
+    // Used by the client and agent
+    total_timeout: Option<Duration>,
+    deadline: Option<Instant>,
+    sent: Option<Arc<AtomicBool>>,
 }
 
 impl SetOpt for RequestConfig {
@@ -113,14 +175,25 @@ impl SetOpt for RequestConfig {
             negotiation.set_opt(easy)?;
         }
 
+        // The ALPN protocols determine the HTTP version as well, so this must
+        // come after version negotiation in order to override it.
+        if let Some(protocols) = self.alpn_protocols.as_ref() {
+            protocols.set_opt(easy)?;
+        }
+
         #[allow(unsafe_code)]
         {
             if let Some(enable) = self.automatic_decompression {
                 if enable {
                     // Enable automatic decompression, and also populate the
-                    // Accept-Encoding header with all supported encodings if not
-                    // explicitly set.
-                    easy.accept_encoding("")?;
+                    // Accept-Encoding header with the chosen encodings (or all
+                    // supported encodings by default) if not explicitly set.
+                    easy.accept_encoding(
+                        self.accept_encodings
+                            .as_ref()
+                            .map(AcceptEncodings::as_str)
+                            .unwrap_or(""),
+                    )?;
                 } else {
                     // Use raw FFI because safe wrapper doesn't let us set to null.
                     unsafe {
@@ -149,15 +222,38 @@ impl SetOpt for RequestConfig {
             credentials.set_opt(easy)?;
         }
 
-        if let Some(interval) = self.tcp_keepalive {
+        // Setting any keepalive option enables TCP keepalive.
+        if self.tcp_keepalive.is_some()
+            || self.tcp_keepalive_idle.is_some()
+            || self.tcp_keepalive_count.is_some()
+        {
             easy.tcp_keepalive(true)?;
+        }
+
+        if let Some(interval) = self.tcp_keepalive {
             easy.tcp_keepintvl(interval)?;
         }
 
+        if let Some(idle) = self.tcp_keepalive_idle {
+            easy.tcp_keepidle(idle)?;
+        }
+
+        if let Some(count) = self.tcp_keepalive_count {
+            setopt_long(easy, CURLOPT_TCP_KEEPCNT, count as c_long)?;
+        }
+
         if let Some(enable) = self.tcp_nodelay {
             easy.tcp_nodelay(enable)?;
         }
 
+        if let Some(enable) = self.fresh_connect {
+            easy.fresh_connect(enable)?;
+        }
+
+        if let Some(enable) = self.forbid_reuse {
+            easy.forbid_reuse(enable)?;
+        }
+
         if let Some(interface) = self.interface.as_ref() {
             interface.set_opt(easy)?;
         }
@@ -197,6 +293,14 @@ impl SetOpt for RequestConfig {
             easy.max_recv_speed(max)?;
         }
 
+        if let Some(size) = self.buffer_size {
+            easy.buffer_size(size)?;
+        }
+
+        if let Some(size) = self.upload_buffer_size {
+            easy.upload_buffer_size(size)?;
+        }
+
         if let Some(cert) = self.ssl_client_certificate.as_ref() {
             cert.set_opt(easy)?;
         }
@@ -209,12 +313,35 @@ impl SetOpt for RequestConfig {
             ciphers.set_opt(easy)?;
         }
 
+        if let Some(keys) = self.ssl_pinned_public_keys.as_ref() {
+            keys.set_opt(easy)?;
+        }
+
+        if self.ssl_min_version.is_some() || self.ssl_max_version.is_some() {
+            easy.ssl_min_max_version(
+                TlsVersion::to_curl(self.ssl_min_version),
+                TlsVersion::to_curl(self.ssl_max_version),
+            )?;
+        }
+
         if let Some(options) = self.ssl_options.as_ref() {
             options.set_opt(easy)?;
         }
 
+        if let Some(enable) = self.ssl_verify_status {
+            setopt_long(easy, CURLOPT_SSL_VERIFYSTATUS, enable as c_long)?;
+        }
+
+        // Progress callbacks are needed for metrics, progress reporting, and
+        // for enforcing the header timeout.
+        let report_progress = self.upload_progress.is_some()
+            || self.download_progress.is_some()
+            || self.header_timeout.is_some();
+
         if let Some(enable) = self.enable_metrics {
-            easy.progress(enable)?;
+            easy.progress(enable || report_progress)?;
+        } else if report_progress {
+            easy.progress(true)?;
         }
 
         Ok(())
@@ -0,0 +1,124 @@
+use crate::error::Error;
+use http::{Method, StatusCode, Uri};
+use std::{fmt, sync::Arc, time::Duration};
+
+/// Describes a policy for automatically retrying failed requests.
+///
+/// Requests are only ever retried if their body can be sent again. Bodies that
+/// stream their contents from a reader cannot be rewound, and so requests with
+/// such bodies are never retried, even for idempotent methods. Requests with
+/// in-memory bodies (or no body at all) may be retried for any method.
+///
+/// The default is to not retry requests.
+#[derive(Clone)]
+pub enum RetryPolicy {
+    /// Do not retry failed requests.
+    ///
+    /// This is the default policy.
+    None,
+
+    /// Retry failed requests up to a maximum number of times.
+    ///
+    /// A request is considered to have failed if a network error occurred
+    /// while sending it, or if the server responded with one of the following
+    /// status codes:
+    ///
+    /// - 408 Request Timeout
+    /// - 429 Too Many Requests
+    /// - 502 Bad Gateway
+    /// - 503 Service Unavailable
+    /// - 504 Gateway Timeout
+    ///
+    /// Between each attempt the client waits using an exponential backoff,
+    /// unless the server specifies a delay in seconds using a `Retry-After`
+    /// header.
+    Limit(u32),
+
+    /// Decide whether to retry using a custom function.
+    ///
+    /// The function is called after each failed attempt, including any
+    /// response with a 4xx or 5xx status code, and returns how long to wait
+    /// before retrying the request. Returning `None` stops retrying and the
+    /// most recent response or error is returned as-is.
+    #[allow(clippy::type_complexity)]
+    Custom(Arc<dyn Fn(&RetryContext<'_>) -> Option<Duration> + Send + Sync>),
+}
+
+impl RetryPolicy {
+    /// Create a retry policy that uses a custom function to decide whether to
+    /// retry a request.
+    ///
+    /// See [`RetryPolicy::Custom`] for details.
+    pub fn custom<F>(f: F) -> Self
+    where
+        F: Fn(&RetryContext<'_>) -> Option<Duration> + Send + Sync + 'static,
+    {
+        RetryPolicy::Custom(Arc::new(f))
+    }
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        RetryPolicy::None
+    }
+}
+
+impl fmt::Debug for RetryPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RetryPolicy::None => f.write_str("None"),
+            RetryPolicy::Limit(limit) => f.debug_tuple("Limit").field(limit).finish(),
+            RetryPolicy::Custom(_) => f.write_str("Custom(..)"),
+        }
+    }
+}
+
+/// Information about a failed attempt to send a request, used to decide
+/// whether the request should be retried.
+#[derive(Debug)]
+pub struct RetryContext<'a> {
+    pub(crate) retries: u32,
+    pub(crate) method: &'a Method,
+    pub(crate) uri: &'a Uri,
+    pub(crate) status: Option<StatusCode>,
+    pub(crate) retry_after: Option<Duration>,
+    pub(crate) error: Option<&'a Error>,
+}
+
+impl RetryContext<'_> {
+    /// Get the number of times the request has been retried so far. This is
+    /// zero after the initial attempt fails.
+    pub fn retries(&self) -> u32 {
+        self.retries
+    }
+
+    /// Get the method of the request.
+    pub fn method(&self) -> &Method {
+        self.method
+    }
+
+    /// Get the URI of the request.
+    pub fn uri(&self) -> &Uri {
+        self.uri
+    }
+
+    /// Get the status code returned by the server, if a response was received.
+    pub fn status(&self) -> Option<StatusCode> {
+        self.status
+    }
+
+    /// Get the delay requested by the server via the `Retry-After` header, if
+    /// any.
+    ///
+    /// Only delays specified as a number of seconds are supported, and delays
+    /// longer than 30 seconds are shortened to 30 seconds.
+    pub fn retry_after(&self) -> Option<Duration> {
+        self.retry_after
+    }
+
+    /// Get the error that caused the attempt to fail, if no response was
+    /// received.
+    pub fn error(&self) -> Option<&Error> {
+        self.error
+    }
+}
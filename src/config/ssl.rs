@@ -1,13 +1,17 @@
 //! Configuration options related to SSL/TLS.
 
-use super::SetOpt;
-use curl::easy::{Easy2, SslOpt};
+use super::{request::setopt_long, SetOpt};
+use curl::easy::{Easy2, HttpVersion, SslOpt, SslVersion};
 use std::{
     iter::FromIterator,
     ops::{BitOr, BitOrAssign},
     path::PathBuf,
 };
 
+/// Option for enabling or disabling ALPN, which is not exposed by the curl
+/// crate.
+const CURLOPT_SSL_ENABLE_ALPN: curl_sys::CURLoption = curl_sys::CURLOPTTYPE_LONG + 226;
+
 #[derive(Clone, Debug)]
 enum PathOrBlob {
     Path(PathBuf),
@@ -281,12 +285,23 @@ impl SetOpt for PrivateKey {
     }
 }
 
-/// A public CA certificate bundle file.
+/// A public CA certificate bundle used to verify server certificates.
 #[derive(Clone, Debug)]
 pub struct CaCertificate {
-    /// Path to the certificate bundle file. Currently only file paths are
-    /// supported.
-    path: PathBuf,
+    /// Where to find the CA certificates.
+    source: CaSource,
+}
+
+#[derive(Clone, Debug)]
+enum CaSource {
+    /// Path to a certificate bundle file.
+    File(PathBuf),
+
+    /// Path to a directory containing individual certificate files.
+    Directory(PathBuf),
+
+    /// A PEM-encoded certificate bundle in memory.
+    Blob(Vec<u8>),
 }
 
 impl CaCertificate {
@@ -298,14 +313,52 @@ impl CaCertificate {
     /// using the offending certificate.
     pub fn file(ca_bundle_path: impl Into<PathBuf>) -> Self {
         Self {
-            path: ca_bundle_path.into(),
+            source: CaSource::File(ca_bundle_path.into()),
+        }
+    }
+
+    /// Get CA certificates from a directory containing individual certificate
+    /// files.
+    ///
+    /// Depending on the SSL/TLS engine in use, the directory may need to be
+    /// prepared first using a tool such as `openssl rehash`. Not all engines
+    /// support loading certificates from a directory.
+    ///
+    /// The directory is not checked here. If the directory does not exist or is
+    /// not supported by the underlying SSL/TLS engine, an error will be
+    /// returned when attempting to send a request.
+    pub fn directory(ca_directory_path: impl Into<PathBuf>) -> Self {
+        Self {
+            source: CaSource::Directory(ca_directory_path.into()),
+        }
+    }
+
+    /// Use a PEM-encoded CA certificate bundle stored in the given byte buffer.
+    ///
+    /// The certificate object takes ownership of the byte buffer. If a borrowed
+    /// type is supplied, such as `&[u8]`, then the bytes will be copied.
+    ///
+    /// The certificates are not parsed or validated here. If the bundle is
+    /// malformed or in-memory bundles are not supported by the underlying
+    /// SSL/TLS engine, an error will be returned when attempting to send a
+    /// request.
+    pub fn pem<B>(bytes: B) -> Self
+    where
+        B: Into<Vec<u8>>,
+    {
+        Self {
+            source: CaSource::Blob(bytes.into()),
         }
     }
 }
 
 impl SetOpt for CaCertificate {
     fn set_opt<H>(&self, easy: &mut Easy2<H>) -> Result<(), curl::Error> {
-        easy.cainfo(&self.path)
+        match &self.source {
+            CaSource::File(path) => easy.cainfo(path),
+            CaSource::Directory(path) => easy.capath(path),
+            CaSource::Blob(bytes) => easy.ssl_cainfo_blob(bytes.as_slice()),
+        }
     }
 }
 
@@ -324,11 +377,128 @@ impl SetOpt for Ciphers {
     }
 }
 
+#[derive(Clone, Debug)]
+pub(crate) struct PinnedPublicKeys(String);
+
+impl FromIterator<String> for PinnedPublicKeys {
+    fn from_iter<I: IntoIterator<Item = String>>(iter: I) -> Self {
+        PinnedPublicKeys(iter.into_iter().collect::<Vec<_>>().join(";"))
+    }
+}
+
+impl SetOpt for PinnedPublicKeys {
+    fn set_opt<H>(&self, easy: &mut Easy2<H>) -> Result<(), curl::Error> {
+        easy.pinned_public_key(&self.0)
+    }
+}
+
+/// The application protocols to offer to the server via ALPN.
+///
+/// Curl does not accept an arbitrary list of protocols to offer, but derives
+/// the list from the HTTP versions it is allowed to use instead. Only the
+/// lists that can be offered this way without also changing how insecure
+/// connections are made are supported, and the protocols given are validated
+/// as soon as they are configured.
+#[derive(Clone, Debug)]
+pub(crate) enum AlpnProtocols {
+    /// Offer both `h2` and `http/1.1`.
+    Http2AndHttp11,
+
+    /// Offer only `http/1.1`.
+    Http11,
+
+    /// Do not use ALPN at all.
+    Disabled,
+
+    /// The protocols given cannot be offered, for the given reason.
+    Invalid(String),
+}
+
+impl FromIterator<String> for AlpnProtocols {
+    fn from_iter<I: IntoIterator<Item = String>>(iter: I) -> Self {
+        let mut h2 = false;
+        let mut http11 = false;
+
+        for protocol in iter {
+            match protocol.as_str() {
+                "h2" => h2 = true,
+                "http/1.1" => http11 = true,
+                protocol => {
+                    return AlpnProtocols::Invalid(format!(
+                        "unsupported ALPN protocol: {}",
+                        protocol
+                    ));
+                }
+            }
+        }
+
+        match (h2, http11) {
+            (true, true) => AlpnProtocols::Http2AndHttp11,
+            // Curl can only offer h2 alone by also using HTTP/2 for insecure
+            // connections, which is what version negotiation is for.
+            (true, false) => AlpnProtocols::Invalid(String::from(
+                "offering only h2 via ALPN is not supported, use \
+                 `VersionNegotiation::http2()` instead",
+            )),
+            (false, true) => AlpnProtocols::Http11,
+            (false, false) => AlpnProtocols::Disabled,
+        }
+    }
+}
+
+impl SetOpt for AlpnProtocols {
+    fn set_opt<H>(&self, easy: &mut Easy2<H>) -> Result<(), curl::Error> {
+        match self {
+            AlpnProtocols::Http2AndHttp11 => easy.http_version(HttpVersion::V2TLS),
+            AlpnProtocols::Http11 => easy.http_version(HttpVersion::V11),
+            AlpnProtocols::Disabled => setopt_long(easy, CURLOPT_SSL_ENABLE_ALPN, 0),
+            AlpnProtocols::Invalid(reason) => {
+                let mut error = curl::Error::new(curl_sys::CURLE_BAD_FUNCTION_ARGUMENT);
+                error.set_extra(reason.clone());
+                Err(error)
+            }
+        }
+    }
+}
+
+/// A version of the TLS protocol.
+///
+/// Used to restrict which protocol versions may be negotiated with a server.
+/// Versions are ordered from oldest to newest.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum TlsVersion {
+    /// TLS 1.0.
+    V1_0,
+
+    /// TLS 1.1.
+    V1_1,
+
+    /// TLS 1.2.
+    V1_2,
+
+    /// TLS 1.3.
+    V1_3,
+}
+
+impl TlsVersion {
+    /// Get the curl version constant for an optional version bound, where an
+    /// unspecified bound uses the default of the SSL/TLS engine.
+    pub(crate) fn to_curl(version: Option<Self>) -> SslVersion {
+        match version {
+            None => SslVersion::Default,
+            Some(TlsVersion::V1_0) => SslVersion::Tlsv10,
+            Some(TlsVersion::V1_1) => SslVersion::Tlsv11,
+            Some(TlsVersion::V1_2) => SslVersion::Tlsv12,
+            Some(TlsVersion::V1_3) => SslVersion::Tlsv13,
+        }
+    }
+}
+
 /// A flag that can be used to alter the behavior of SSL/TLS connections.
 ///
 /// Most options are for disabling security checks that introduce security
 /// risks, but may be required as a last resort.
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub struct SslOption(usize);
 
 impl Default for SslOption {
@@ -368,7 +538,20 @@ impl SslOption {
     /// library).
     pub const DANGER_ACCEPT_REVOKED_CERTS: Self = SslOption(0b0100);
 
-    const fn contains(self, other: Self) -> bool {
+    /// Check if all of the options in `other` are also enabled in this set of
+    /// options.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use isahc::config::SslOption;
+    ///
+    /// let options = SslOption::DANGER_ACCEPT_INVALID_CERTS | SslOption::DANGER_ACCEPT_INVALID_HOSTS;
+    ///
+    /// assert!(options.contains(SslOption::DANGER_ACCEPT_INVALID_HOSTS));
+    /// assert!(!options.contains(SslOption::DANGER_ACCEPT_REVOKED_CERTS));
+    /// ```
+    pub const fn contains(self, other: Self) -> bool {
         (self.0 & other.0) == other.0
     }
 }
@@ -393,6 +576,10 @@ impl SetOpt for SslOption {
         let mut opt = SslOpt::new();
         opt.no_revoke(self.contains(Self::DANGER_ACCEPT_REVOKED_CERTS));
 
+        if *self != Self::NONE {
+            tracing::debug!(options = ?self, "using dangerous SSL/TLS options");
+        }
+
         easy.ssl_options(&opt)?;
         easy.ssl_verify_peer(!self.contains(Self::DANGER_ACCEPT_INVALID_CERTS))?;
         easy.ssl_verify_host(!self.contains(Self::DANGER_ACCEPT_INVALID_HOSTS))
@@ -401,7 +588,12 @@ impl SetOpt for SslOption {
 
 #[cfg(test)]
 mod tests {
-    use super::SslOption;
+    use super::{AlpnProtocols, SslOption, TlsVersion};
+    use curl::easy::SslVersion;
+
+    fn alpn(protocols: &[&str]) -> AlpnProtocols {
+        protocols.iter().map(|protocol| protocol.to_string()).collect()
+    }
 
     #[test]
     fn default_ssl_options() {
@@ -424,4 +616,38 @@ mod tests {
         assert!(!options.contains(SslOption::DANGER_ACCEPT_INVALID_CERTS));
         assert!(options.contains(SslOption::DANGER_ACCEPT_INVALID_HOSTS));
     }
+
+    #[test]
+    fn tls_versions_are_ordered() {
+        assert!(TlsVersion::V1_0 < TlsVersion::V1_1);
+        assert!(TlsVersion::V1_1 < TlsVersion::V1_2);
+        assert!(TlsVersion::V1_2 < TlsVersion::V1_3);
+    }
+
+    #[test]
+    fn tls_version_to_curl() {
+        assert_eq!(TlsVersion::to_curl(None) as i64, SslVersion::Default as i64);
+        assert_eq!(
+            TlsVersion::to_curl(Some(TlsVersion::V1_2)) as i64,
+            SslVersion::Tlsv12 as i64
+        );
+    }
+
+    #[test]
+    fn alpn_protocols_are_validated() {
+        assert!(matches!(alpn(&["h2", "http/1.1"]), AlpnProtocols::Http2AndHttp11));
+        assert!(matches!(alpn(&["http/1.1", "h2"]), AlpnProtocols::Http2AndHttp11));
+        assert!(matches!(alpn(&["http/1.1"]), AlpnProtocols::Http11));
+        assert!(matches!(alpn(&[]), AlpnProtocols::Disabled));
+    }
+
+    #[test]
+    fn unsupported_alpn_protocol_is_rejected() {
+        assert!(matches!(alpn(&["h2", "spdy/3"]), AlpnProtocols::Invalid(_)));
+    }
+
+    #[test]
+    fn h2_only_alpn_is_rejected() {
+        assert!(matches!(alpn(&["h2"]), AlpnProtocols::Invalid(_)));
+    }
 }
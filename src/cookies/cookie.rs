@@ -17,6 +17,26 @@ impl fmt::Display for ParseError {
 
 impl Error for ParseError {}
 
+/// The value of the `SameSite` attribute of a cookie, which restricts when the
+/// cookie is sent along with cross-site requests.
+///
+/// Since Isahc is not a browser, the only cross-site requests it makes are when
+/// following a redirect from one site to another. In that case, the site of
+/// the original request is used to decide whether the redirected request is
+/// cross-site.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SameSite {
+    /// The cookie is only sent with same-site requests.
+    Strict,
+
+    /// The cookie is sent with same-site requests, and with cross-site
+    /// requests that use a safe method such as `GET`.
+    Lax,
+
+    /// The cookie is sent with both same-site and cross-site requests.
+    None,
+}
+
 /// Builder for a [`Cookie`].
 ///
 /// ```rust
@@ -49,6 +69,12 @@ pub struct CookieBuilder {
     /// True if the cookie is marked as secure (limited in scope to HTTPS).
     secure: Option<bool>,
 
+    /// True if the cookie is marked as HTTP only (not accessible to scripts).
+    http_only: Option<bool>,
+
+    /// Restriction on sending the cookie with cross-site requests, if any.
+    same_site: Option<SameSite>,
+
     /// Time when this cookie expires. If not present, then this is a session
     /// cookie that expires when the current client session ends.
     expiration: Option<SystemTime>,
@@ -68,6 +94,8 @@ impl CookieBuilder {
             domain: None,
             path: None,
             secure: None,
+            http_only: None,
+            same_site: None,
             expiration: None,
         }
     }
@@ -96,6 +124,18 @@ impl CookieBuilder {
         self
     }
 
+    /// True if the cookie is marked as HTTP only (not accessible to scripts).
+    pub fn http_only(mut self, http_only: bool) -> Self {
+        self.http_only = Some(http_only);
+        self
+    }
+
+    /// Restricts when the cookie is sent with cross-site requests.
+    pub fn same_site(mut self, same_site: SameSite) -> Self {
+        self.same_site = Some(same_site);
+        self
+    }
+
     /// Time when this cookie expires. If not present, then this is a session
     /// cookie that expires when the current client session ends.
     pub fn expiration<T>(mut self, expiration: T) -> Self
@@ -118,18 +158,25 @@ impl CookieBuilder {
             domain,
             path,
             secure,
+            http_only,
+            same_site,
             expiration,
         } = self;
 
         let mut cookie = Cookie::new(name, value)?;
         cookie.domain = domain;
         cookie.path = path;
+        cookie.same_site = same_site;
         cookie.expiration = expiration;
 
         if let Some(secure) = secure {
             cookie.secure = secure;
         }
 
+        if let Some(http_only) = http_only {
+            cookie.http_only = http_only;
+        }
+
         Ok(cookie)
     }
 }
@@ -166,6 +213,12 @@ pub struct Cookie {
     /// True if the cookie is marked as secure (limited in scope to HTTPS).
     secure: bool,
 
+    /// True if the cookie is marked as HTTP only (not accessible to scripts).
+    http_only: bool,
+
+    /// Restriction on sending the cookie with cross-site requests, if any.
+    same_site: Option<SameSite>,
+
     /// Time when this cookie expires. If not present, then this is a session
     /// cookie that expires when the current client session ends.
     expiration: Option<SystemTime>,
@@ -194,6 +247,8 @@ impl Cookie {
                 domain: None,
                 path: None,
                 secure: false,
+                http_only: false,
+                same_site: None,
                 expiration: None,
             })
         } else {
@@ -241,24 +296,56 @@ impl Cookie {
     }
 
     /// Get the domain of the cookie, if specified.
+    ///
+    /// If the cookie was set without a `Domain` attribute, then this returns
+    /// `None` and the cookie only applies to the exact host that set it.
     #[inline]
-    pub(crate) fn domain(&self) -> Option<&str> {
+    pub fn domain(&self) -> Option<&str> {
         self.domain.as_deref()
     }
 
     /// Get the path of the cookie, if specified.
+    ///
+    /// If the cookie was set without a `Path` attribute, then this returns
+    /// `None` and the cookie applies to a default path derived from the URI
+    /// that set it.
     #[inline]
-    pub(crate) fn path(&self) -> Option<&str> {
+    pub fn path(&self) -> Option<&str> {
         self.path.as_deref()
     }
 
     /// Get whether this cookie was marked as being secure only. If `true`, this
     /// cookie will only be sent to the server for HTTPS requests.
     #[inline]
-    pub(crate) fn is_secure(&self) -> bool {
+    pub fn is_secure(&self) -> bool {
         self.secure
     }
 
+    /// Get whether this cookie was marked as HTTP only. Such cookies are meant
+    /// to be hidden from client-side scripts, and have no effect on how Isahc
+    /// sends the cookie.
+    #[inline]
+    pub fn is_http_only(&self) -> bool {
+        self.http_only
+    }
+
+    /// Get the value of the `SameSite` attribute of this cookie, if it was
+    /// specified.
+    ///
+    /// Cookies without the attribute are sent with all requests, including
+    /// cross-site requests.
+    #[inline]
+    pub fn same_site(&self) -> Option<SameSite> {
+        self.same_site
+    }
+
+    /// Get the time when this cookie expires, or `None` if this is a session
+    /// cookie.
+    #[inline]
+    pub fn expiration(&self) -> Option<SystemTime> {
+        self.expiration
+    }
+
     /// Get whether this cookie should be persisted across sessions.
     #[inline]
     #[allow(unused)]
@@ -288,11 +375,12 @@ impl Cookie {
         let mut cookie_domain = None;
         let mut cookie_path = None;
         let mut cookie_secure = false;
+        let mut cookie_http_only = false;
+        let mut cookie_same_site = None;
         let mut cookie_expiration = None;
 
-        // Look for known attribute names and parse them. Note that there are
-        // multiple attributes in the spec that we don't parse right now because we
-        // do not care about them, including HttpOnly and SameSite.
+        // Look for known attribute names and parse them. Unknown attributes
+        // are ignored.
         for attribute in attributes {
             if let Some((name, value)) = split_at_first(attribute, &b'=') {
                 if name.eq_ignore_ascii_case(b"Expires") {
@@ -304,8 +392,14 @@ impl Cookie {
                         }
                     }
                 } else if name.eq_ignore_ascii_case(b"Domain") {
+                    // An empty domain attribute is ignored, as per RFC 6265,
+                    // section 5.2.3.
                     if let Ok(value) = str::from_utf8(value) {
-                        cookie_domain = Some(value.trim_start_matches('.').to_lowercase());
+                        let value = value.trim_start_matches('.');
+
+                        if !value.is_empty() {
+                            cookie_domain = Some(value.to_lowercase());
+                        }
                     }
                 } else if name.eq_ignore_ascii_case(b"Max-Age") {
                     if let Ok(value) = str::from_utf8(value) {
@@ -318,9 +412,20 @@ impl Cookie {
                     if let Ok(value) = str::from_utf8(value) {
                         cookie_path = Some(value.to_owned());
                     }
+                } else if name.eq_ignore_ascii_case(b"SameSite") {
+                    // Unrecognized values are ignored.
+                    if value.eq_ignore_ascii_case(b"Strict") {
+                        cookie_same_site = Some(SameSite::Strict);
+                    } else if value.eq_ignore_ascii_case(b"Lax") {
+                        cookie_same_site = Some(SameSite::Lax);
+                    } else if value.eq_ignore_ascii_case(b"None") {
+                        cookie_same_site = Some(SameSite::None);
+                    }
                 }
             } else if attribute.eq_ignore_ascii_case(b"Secure") {
                 cookie_secure = true;
+            } else if attribute.eq_ignore_ascii_case(b"HttpOnly") {
+                cookie_http_only = true;
             }
         }
 
@@ -328,6 +433,8 @@ impl Cookie {
             name: cookie_name,
             value: cookie_value,
             secure: cookie_secure,
+            http_only: cookie_http_only,
+            same_site: cookie_same_site,
             expiration: cookie_expiration,
             domain: cookie_domain,
             path: cookie_path,
@@ -493,6 +600,7 @@ mod tests {
         assert_eq!(cookie.path(), Some("/sub"));
         assert_eq!(cookie.domain.as_deref(), Some("baz.com"));
         assert!(cookie.is_secure());
+        assert!(!cookie.is_http_only());
         assert!(!cookie.is_expired());
         assert!(
             cookie
@@ -512,6 +620,7 @@ mod tests {
             .domain("baz.com")
             .path("/sub")
             .secure(true)
+            .http_only(true)
             .expiration(exp)
             .build()
             .unwrap();
@@ -521,6 +630,31 @@ mod tests {
         assert_eq!(cookie.path(), Some("/sub"));
         assert_eq!(cookie.domain.as_deref(), Some("baz.com"));
         assert!(cookie.is_secure());
-        assert_eq!(cookie.expiration, Some(exp));
+        assert!(cookie.is_http_only());
+        assert_eq!(cookie.expiration(), Some(exp));
+    }
+
+    #[test_case("foo=bar; Domain=")]
+    #[test_case("foo=bar; Domain=.")]
+    fn parse_empty_domain_is_ignored(s: &str) {
+        assert_eq!(Cookie::parse(s).unwrap().domain(), None);
+    }
+
+    #[test_case("foo=bar; SameSite=Strict", Some(SameSite::Strict))]
+    #[test_case("foo=bar; samesite=lax", Some(SameSite::Lax))]
+    #[test_case("foo=bar; SameSite=None; Secure", Some(SameSite::None))]
+    #[test_case("foo=bar; SameSite=bogus", None)]
+    #[test_case("foo=bar", None)]
+    fn parse_same_site(s: &str, same_site: Option<SameSite>) {
+        assert_eq!(Cookie::parse(s).unwrap().same_site(), same_site);
+    }
+
+    #[test]
+    fn parse_http_only() {
+        let cookie = Cookie::parse("foo=bar; HttpOnly; Secure").unwrap();
+
+        assert!(cookie.is_http_only());
+        assert!(cookie.is_secure());
+        assert!(!Cookie::parse("foo=bar; Secure").unwrap().is_http_only());
     }
 }
@@ -1,14 +1,15 @@
 //! Interceptor that provides automatic cookie session management for any
 //! request with an attached cookie jar.
 
-use super::{Cookie, CookieJar};
+use super::{Cookie, CookieJar, SameSite};
 use crate::{
     body::AsyncBody,
     error::Error,
     interceptor::{Context, Interceptor, InterceptorFuture},
+    redirect::RedirectOrigin,
     response::ResponseExt,
 };
-use http::Request;
+use http::{Method, Request, Uri};
 use std::convert::TryInto;
 
 #[derive(Debug)]
@@ -49,8 +50,25 @@ impl Interceptor for CookieInterceptor {
                     .map(|value| value.as_bytes().to_vec())
                     .unwrap_or_default();
 
+                // If this request is the result of a redirect from another
+                // site, then cookies restricted to same-site requests must be
+                // withheld.
+                let cross_site = request
+                    .extensions()
+                    .get::<RedirectOrigin>()
+                    .map(|origin| !is_same_site(&origin.0, request.uri()))
+                    .unwrap_or(false);
+
                 // Append cookies in the jar to the cookie header value.
                 for cookie in jar.get_for_uri(request.uri()) {
+                    if cross_site && !allowed_cross_site(&cookie, request.method()) {
+                        tracing::debug!(
+                            "cookie '{}' withheld from cross-site request",
+                            cookie.name()
+                        );
+                        continue;
+                    }
+
                     if !cookie_string.is_empty() {
                         cookie_string.extend_from_slice(b"; ");
                     }
@@ -107,3 +125,77 @@ impl Interceptor for CookieInterceptor {
         })
     }
 }
+
+/// Check whether a cookie may be sent with a cross-site request using the
+/// given method.
+fn allowed_cross_site(cookie: &Cookie, method: &Method) -> bool {
+    match cookie.same_site() {
+        Some(SameSite::Strict) => false,
+        // Lax cookies are only sent cross-site with safe methods.
+        Some(SameSite::Lax) => matches!(
+            *method,
+            Method::GET | Method::HEAD | Method::OPTIONS | Method::TRACE
+        ),
+        Some(SameSite::None) | None => true,
+    }
+}
+
+/// Check whether two URIs belong to the same site, meaning they share the same
+/// scheme and registrable domain.
+///
+/// Without the public suffix list the registrable domain cannot be known, so
+/// the hosts must match exactly instead.
+fn is_same_site(a: &Uri, b: &Uri) -> bool {
+    if a.scheme() != b.scheme() {
+        return false;
+    }
+
+    let (a, b) = match (a.host(), b.host()) {
+        (Some(a), Some(b)) => (a.to_lowercase(), b.to_lowercase()),
+        _ => return false,
+    };
+
+    if a == b {
+        return true;
+    }
+
+    #[cfg(feature = "psl")]
+    {
+        if let Some(domain) = super::psl::registrable_domain(&a) {
+            return super::psl::registrable_domain(&b).as_ref() == Some(&domain);
+        }
+    }
+
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+
+    #[test_case("https://example.com/a", "https://example.com/b" => true)]
+    #[test_case("https://EXAMPLE.com", "https://example.com:8443" => true)]
+    #[test_case("https://example.com", "http://example.com" => false)]
+    #[test_case("https://example.com", "https://example.org" => false)]
+    #[test_case("https://a.example.co.uk", "https://b.example.co.uk" => cfg!(feature = "psl"))]
+    #[test_case("https://a.co.uk", "https://b.co.uk" => false)]
+    fn same_site(a: &str, b: &str) -> bool {
+        is_same_site(&a.parse().unwrap(), &b.parse().unwrap())
+    }
+
+    #[test_case(None, Method::POST => true)]
+    #[test_case(Some(SameSite::None), Method::POST => true)]
+    #[test_case(Some(SameSite::Lax), Method::GET => true)]
+    #[test_case(Some(SameSite::Lax), Method::POST => false)]
+    #[test_case(Some(SameSite::Strict), Method::GET => false)]
+    fn cross_site(same_site: Option<SameSite>, method: Method) -> bool {
+        let mut cookie = Cookie::builder("foo", "bar");
+
+        if let Some(same_site) = same_site {
+            cookie = cookie.same_site(same_site);
+        }
+
+        allowed_cross_site(&cookie.build().unwrap(), &method)
+    }
+}
@@ -4,11 +4,22 @@ use std::{
     collections::HashSet,
     error::Error,
     fmt,
+    fs::File,
     hash::{Hash, Hasher},
+    io::{self, BufRead, BufReader, BufWriter, Write},
     net::{Ipv4Addr, Ipv6Addr},
+    path::Path,
     sync::{Arc, RwLock},
+    time::{Duration, UNIX_EPOCH},
 };
 
+/// Header line written at the top of cookie files, which some tools look for
+/// to recognize the format.
+const COOKIE_FILE_HEADER: &str = "# Netscape HTTP Cookie File";
+
+/// Prefix used by curl to mark HTTP only cookies in cookie files.
+const HTTP_ONLY_PREFIX: &str = "#HttpOnly_";
+
 /// Returned when a [`Cookie`] fails to be added to the [`CookieJar`].
 #[derive(Clone, Debug)]
 pub struct CookieRejectedError {
@@ -114,11 +125,187 @@ impl CookieJar {
         cookies
     }
 
+    /// Get a copy of all the cookies in the jar, regardless of which domain
+    /// or path they belong to.
+    ///
+    /// Like [`CookieJar::get_for_uri`], the returned iterator is a snapshot of
+    /// the jar at the time this function was called. Cookies that have expired
+    /// are not included.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use isahc::cookies::CookieJar;
+    ///
+    /// let jar = CookieJar::new();
+    ///
+    /// for cookie in jar.iter() {
+    ///     println!("{}={} (domain: {:?})", cookie.name(), cookie.value(), cookie.domain());
+    /// }
+    /// ```
+    pub fn iter(&self) -> impl Iterator<Item = Cookie> {
+        self.cookies
+            .read()
+            .unwrap()
+            .iter()
+            .filter(|cookie| !cookie.cookie.is_expired())
+            .map(|c| c.cookie.clone())
+            .collect::<Vec<_>>()
+            .into_iter()
+    }
+
+    /// Remove all cookies with the given name that match the given URI.
+    ///
+    /// Returns the cookies that were removed, if any.
+    pub fn remove(&self, uri: &Uri, cookie_name: &str) -> Vec<Cookie> {
+        let mut jar = self.cookies.write().unwrap();
+        let mut removed = Vec::new();
+
+        jar.retain(|cookie| {
+            if cookie.matches(uri) && cookie.cookie.name() == cookie_name {
+                removed.push(cookie.cookie.clone());
+                false
+            } else {
+                true
+            }
+        });
+
+        removed
+    }
+
     /// Remove all cookies from this cookie jar.
     pub fn clear(&self) {
         self.cookies.write().unwrap().clear();
     }
 
+    /// Load a cookie jar from a file previously written by
+    /// [`CookieJar::save`].
+    ///
+    /// The file is expected to be in the Netscape cookie file format, which is
+    /// also used by curl's `--cookie-jar` option and various browser
+    /// extensions. Cookies in the file that have already expired are skipped.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use isahc::{cookies::CookieJar, prelude::*, HttpClient};
+    ///
+    /// let jar = CookieJar::load("cookies.txt")?;
+    /// let client = HttpClient::builder()
+    ///     .cookie_jar(jar.clone())
+    ///     .build()?;
+    ///
+    /// client.get("https://example.org")?;
+    ///
+    /// jar.save("cookies.txt")?;
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn load<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let jar = Self::new();
+        jar.read_from(BufReader::new(File::open(path)?))?;
+
+        Ok(jar)
+    }
+
+    /// Save all cookies in this jar to a file, replacing the file if it
+    /// already exists.
+    ///
+    /// Cookies are written in the Netscape cookie file format, and can be
+    /// loaded back in using [`CookieJar::load`]. Session cookies are saved as
+    /// well, so that a session can be resumed later.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        self.write_to(&mut writer)?;
+
+        writer.flush()
+    }
+
+    fn read_from<R: BufRead>(&self, reader: R) -> io::Result<()> {
+        let mut jar = self.cookies.write().unwrap();
+
+        for (index, line) in reader.lines().enumerate() {
+            let line = line?;
+
+            // Curl marks HttpOnly cookies using a special prefix, which would
+            // otherwise look like a comment.
+            let (line, http_only) = if line.starts_with(HTTP_ONLY_PREFIX) {
+                (&line[HTTP_ONLY_PREFIX.len()..], true)
+            } else if line.trim().is_empty() || line.starts_with('#') {
+                continue;
+            } else {
+                (line.as_str(), false)
+            };
+
+            let cookie = parse_cookie_file_line(line, http_only).ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("invalid cookie on line {} of cookie file", index + 1),
+                )
+            })?;
+
+            if !cookie.cookie.is_expired() {
+                jar.replace(cookie);
+            }
+        }
+
+        Ok(())
+    }
+
+    fn write_to<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        let jar = self.cookies.read().unwrap();
+
+        // Sort the cookies so that the output is stable.
+        let mut cookies = jar
+            .iter()
+            .filter(|cookie| !cookie.cookie.is_expired())
+            .collect::<Vec<_>>();
+
+        cookies.sort_by(|a, b| {
+            a.domain_value
+                .cmp(&b.domain_value)
+                .then_with(|| a.path_value.cmp(&b.path_value))
+                .then_with(|| a.cookie.name().cmp(b.cookie.name()))
+        });
+
+        writeln!(writer, "{}", COOKIE_FILE_HEADER)?;
+
+        for cookie in cookies {
+            let http_only_prefix = if cookie.cookie.is_http_only() {
+                HTTP_ONLY_PREFIX
+            } else {
+                ""
+            };
+
+            // Cookies that apply to subdomains are conventionally written
+            // with a leading dot.
+            let domain_prefix = if cookie.is_host_only() { "" } else { "." };
+
+            // Session cookies are written with an expiration of zero.
+            let expires = cookie
+                .cookie
+                .expiration()
+                .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+                .map(|duration| duration.as_secs())
+                .unwrap_or(0);
+
+            writeln!(
+                writer,
+                "{}{}{}\t{}\t{}\t{}\t{}\t{}\t{}",
+                http_only_prefix,
+                domain_prefix,
+                cookie.domain_value,
+                format_cookie_file_bool(!cookie.is_host_only()),
+                cookie.path_value,
+                format_cookie_file_bool(cookie.cookie.is_secure()),
+                expires,
+                cookie.cookie.name(),
+                cookie.cookie.value(),
+            )?;
+        }
+
+        Ok(())
+    }
+
     /// Set a cookie for the given absolute request URI.
     ///
     /// If the cookie was set successfully, returns the cookie that previously existed for
@@ -290,6 +477,64 @@ impl PartialEq for CookieWithContext {
 
 impl Eq for CookieWithContext {}
 
+/// Parse a single cookie from a line in a Netscape cookie file.
+fn parse_cookie_file_line(line: &str, http_only: bool) -> Option<CookieWithContext> {
+    let fields = line.split('\t').collect::<Vec<_>>();
+
+    if fields.len() != 7 {
+        return None;
+    }
+
+    let domain = fields[0].trim_start_matches('.').to_lowercase();
+    let include_subdomains = parse_cookie_file_bool(fields[1])?;
+    let path = fields[2];
+    let secure = parse_cookie_file_bool(fields[3])?;
+    let expires = fields[4].parse::<u64>().ok()?;
+
+    if domain.is_empty() || !path.starts_with('/') {
+        return None;
+    }
+
+    let mut builder = Cookie::builder(fields[5], fields[6])
+        .path(path)
+        .secure(secure)
+        .http_only(http_only);
+
+    // Only cookies set with a Domain attribute apply to subdomains.
+    if include_subdomains {
+        builder = builder.domain(domain.clone());
+    }
+
+    // An expiration of zero indicates a session cookie.
+    if expires > 0 {
+        builder = builder.expiration(UNIX_EPOCH.checked_add(Duration::from_secs(expires))?);
+    }
+
+    Some(CookieWithContext {
+        domain_value: domain,
+        path_value: path.to_owned(),
+        cookie: builder.build().ok()?,
+    })
+}
+
+fn format_cookie_file_bool(value: bool) -> &'static str {
+    if value {
+        "TRUE"
+    } else {
+        "FALSE"
+    }
+}
+
+fn parse_cookie_file_bool(value: &str) -> Option<bool> {
+    if value.eq_ignore_ascii_case("TRUE") {
+        Some(true)
+    } else if value.eq_ignore_ascii_case("FALSE") {
+        Some(false)
+    } else {
+        None
+    }
+}
+
 // http://tools.ietf.org/html/rfc6265#section-5.1.3
 fn domain_matches(string: &str, domain_string: &str) -> bool {
     if domain_string.eq_ignore_ascii_case(string) {
@@ -412,6 +657,57 @@ mod tests {
         }
     }
 
+    #[cfg(feature = "psl")]
+    #[test_case("co.uk", "https://www.example.co.uk")]
+    #[test_case("uk", "https://www.example.co.uk")]
+    #[test_case("com.au", "https://shop.example.com.au")]
+    fn cookie_for_public_suffix_is_rejected(domain: &str, uri: &str) {
+        let jar = CookieJar::default();
+        let cookie = Cookie::parse(format!("foo=bar; domain={}", domain)).unwrap();
+
+        assert_eq!(
+            jar.set(cookie, &uri.parse().unwrap()).unwrap_err().kind(),
+            CookieRejectedErrorKind::InvalidCookieDomain
+        );
+    }
+
+    #[cfg(feature = "psl")]
+    #[test_case("example.co.uk", "https://www.example.co.uk")]
+    #[test_case("shop.example.com.au", "https://shop.example.com.au")]
+    fn cookie_for_registrable_domain_is_accepted(domain: &str, uri: &str) {
+        let jar = CookieJar::default();
+        let cookie = Cookie::parse(format!("foo=bar; domain={}", domain)).unwrap();
+
+        assert!(jar.set(cookie, &uri.parse().unwrap()).is_ok());
+    }
+
+    #[test]
+    fn cookie_domain_attribute_must_match_on_label_boundary() {
+        let jar = CookieJar::default();
+
+        assert_eq!(
+            jar.set(
+                Cookie::parse("foo=bar; domain=ample.com").unwrap(),
+                &"https://example.com".parse().unwrap(),
+            )
+            .unwrap_err()
+            .kind(),
+            CookieRejectedErrorKind::DomainMismatch
+        );
+
+        // Cookies with a domain attribute also apply to subdomains, but not to
+        // unrelated domains sharing a suffix.
+        let uri = "https://www.example.com".parse().unwrap();
+        jar.set(Cookie::parse("foo=bar; domain=example.com").unwrap(), &uri).unwrap();
+
+        assert!(jar
+            .get_by_name(&"https://a.b.example.com".parse().unwrap(), "foo")
+            .is_some());
+        assert!(jar
+            .get_by_name(&"https://notexample.com".parse().unwrap(), "foo")
+            .is_none());
+    }
+
     #[test]
     fn expire_a_cookie() {
         let uri: Uri = "https://example.com/foo".parse().unwrap();
@@ -430,6 +726,137 @@ mod tests {
         assert!(jar.get_for_uri(&uri).into_iter().next().is_none());
     }
 
+    #[test]
+    fn cookie_file_round_trip() {
+        let uri: Uri = "https://www.example.com/foo/bar".parse().unwrap();
+        let jar = CookieJar::default();
+
+        jar.set(Cookie::parse("session=abc").unwrap(), &uri).unwrap();
+        jar.set(
+            Cookie::builder("id", "123")
+                .domain("example.com")
+                .path("/")
+                .secure(true)
+                .expiration(UNIX_EPOCH + Duration::from_secs(4_096_337_280))
+                .build()
+                .unwrap(),
+            &uri,
+        )
+        .unwrap();
+
+        let mut buf = Vec::new();
+        jar.write_to(&mut buf).unwrap();
+
+        assert_eq!(
+            String::from_utf8(buf.clone()).unwrap(),
+            "# Netscape HTTP Cookie File\n\
+             .example.com\tTRUE\t/\tTRUE\t4096337280\tid\t123\n\
+             www.example.com\tFALSE\t/foo\tFALSE\t0\tsession\tabc\n"
+        );
+
+        let loaded = CookieJar::new();
+        loaded.read_from(buf.as_slice()).unwrap();
+
+        // Host-only cookies must not leak to subdomains after loading.
+        assert_eq!(loaded.get_by_name(&uri, "session").unwrap(), "abc");
+        assert!(loaded
+            .get_by_name(&"https://sub.www.example.com/foo".parse().unwrap(), "session")
+            .is_none());
+
+        // Domain cookies still apply to subdomains, but only over HTTPS.
+        let id = loaded
+            .get_by_name(&"https://api.example.com/".parse().unwrap(), "id")
+            .unwrap();
+        assert_eq!(id, "123");
+        assert_eq!(
+            id.expiration(),
+            Some(UNIX_EPOCH + Duration::from_secs(4_096_337_280))
+        );
+        assert!(loaded
+            .get_by_name(&"http://api.example.com/".parse().unwrap(), "id")
+            .is_none());
+    }
+
+    #[test]
+    fn cookie_file_skips_comments_and_expired_cookies() {
+        let jar = CookieJar::new();
+
+        jar.read_from(
+            &b"# Netscape HTTP Cookie File\n\
+               \n\
+               #HttpOnly_example.com\tFALSE\t/\tFALSE\t0\ta\t1\n\
+               example.com\tFALSE\t/\tFALSE\t1\tb\t2\r\n"[..],
+        )
+        .unwrap();
+
+        let uri = "http://example.com/".parse().unwrap();
+
+        assert_eq!(jar.get_by_name(&uri, "a").unwrap(), "1");
+        assert!(jar.get_by_name(&uri, "a").unwrap().is_http_only());
+        assert!(jar.get_by_name(&uri, "b").is_none());
+    }
+
+    #[test]
+    fn remove_cookie() {
+        let uri: Uri = "https://example.com/foo".parse().unwrap();
+        let other_uri: Uri = "https://example.org/".parse().unwrap();
+        let jar = CookieJar::default();
+
+        jar.set(Cookie::parse("foo=bar").unwrap(), &uri).unwrap();
+        jar.set(Cookie::parse("baz=qux").unwrap(), &uri).unwrap();
+        jar.set(Cookie::parse("foo=bar").unwrap(), &other_uri).unwrap();
+
+        let removed = jar.remove(&uri, "foo");
+
+        assert_eq!(removed.len(), 1);
+        assert_eq!(removed[0], "bar");
+        assert!(jar.get_by_name(&uri, "foo").is_none());
+        assert!(jar.get_by_name(&uri, "baz").is_some());
+        assert!(jar.get_by_name(&other_uri, "foo").is_some());
+        assert!(jar.remove(&uri, "foo").is_empty());
+    }
+
+    #[test]
+    fn iter_all_cookies() {
+        let jar = CookieJar::default();
+
+        jar.set(
+            Cookie::parse("a=1; Domain=example.com; HttpOnly").unwrap(),
+            &"https://www.example.com".parse().unwrap(),
+        )
+        .unwrap();
+        jar.set(Cookie::parse("b=2").unwrap(), &"https://example.org".parse().unwrap()).unwrap();
+
+        let mut cookies = jar.iter().collect::<Vec<_>>();
+        cookies.sort_by(|a, b| a.name().cmp(b.name()));
+
+        assert_eq!(cookies.len(), 2);
+        assert_eq!(cookies[0].domain(), Some("example.com"));
+        assert!(cookies[0].is_http_only());
+        assert_eq!(cookies[1].domain(), None);
+        assert!(!cookies[1].is_http_only());
+    }
+
+    #[test]
+    fn cookie_file_with_invalid_line_is_rejected() {
+        let jar = CookieJar::new();
+        let error = jar
+            .read_from(&b"example.com\tFALSE\t/\tMAYBE\t0\ta\t1\n"[..])
+            .unwrap_err();
+
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn cookie_file_with_oversized_expiry_is_rejected() {
+        let jar = CookieJar::new();
+        let error = jar
+            .read_from(&b"example.com\tFALSE\t/\tFALSE\t18446744073709551615\ta\t1\n"[..])
+            .unwrap_err();
+
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+    }
+
     #[test_case("127.0.0.1", "127.0.0.1", true)]
     #[test_case(".127.0.0.2", "127.0.0.2", true)]
     #[test_case("bar.com", "bar.com", true)]
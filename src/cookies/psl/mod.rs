@@ -126,6 +126,21 @@ pub(crate) fn is_public_suffix(domain: impl AsRef<str>) -> bool {
     })
 }
 
+/// Get the registrable domain of the given host, which is the public suffix of
+/// the host plus one more label. Returns `None` if the host has no known public
+/// suffix.
+pub(crate) fn registrable_domain(host: impl AsRef<str>) -> Option<String> {
+    let host = host.as_ref().as_bytes();
+
+    with_cache(|cache| {
+        cache
+            .list
+            .domain(host)
+            .filter(|domain| domain.suffix().is_known())
+            .map(|domain| String::from_utf8_lossy(domain.as_bytes()).into_owned())
+    })
+}
+
 /// Execute a given closure with a reference to the list cache. If the list is
 /// out of date, attempt to refresh it first before continuing.
 fn with_cache<T>(f: impl FnOnce(&ListCache) -> T) -> T {
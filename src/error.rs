@@ -23,6 +23,16 @@ pub enum ErrorKind {
     /// The server certificate could not be validated.
     BadServerCertificate,
 
+    /// The request was cancelled, either using a
+    /// [`CancellationToken`](crate::CancellationToken) or by dropping its
+    /// response future.
+    Cancelled,
+
+    /// The HTTP client has been closed with
+    /// [`HttpClient::close`](crate::HttpClient::close) and can no longer send
+    /// requests.
+    ClientClosed,
+
     /// The HTTP client failed to initialize.
     ///
     /// This error can occur when trying to create a client with invalid
@@ -35,6 +45,13 @@ pub enum ErrorKind {
     /// the request on the specified port.
     ConnectionFailed,
 
+    /// A redirect was not followed because it pointed to an insecure location,
+    /// as configured by
+    /// [`Configurable::deny_redirect_downgrade`](crate::config::Configurable::deny_redirect_downgrade)
+    /// or
+    /// [`Configurable::redirect_https_only`](crate::config::Configurable::redirect_https_only).
+    InsecureRedirect,
+
     /// The server either returned a response using an unknown or unsupported
     /// encoding format, or the response encoding was malformed.
     InvalidContentEncoding,
@@ -73,6 +90,12 @@ pub enum ErrorKind {
     /// likely to produce the same error.
     ProtocolViolation,
 
+    /// The request timed out while waiting for its turn to be sent, for example
+    /// because the connection limits of the client were reached and no
+    /// connection became available in time. No part of the request will have
+    /// been sent to the server.
+    QueueTimeout,
+
     /// Request processing could not continue because the client needed to
     /// re-send the request body, but was unable to rewind the body stream to
     /// the beginning in order to do so.
@@ -84,6 +107,22 @@ pub enum ErrorKind {
     /// [`AsyncBody::from_bytes_static`][crate::AsyncBody::from_bytes_static].
     RequestBodyNotRewindable,
 
+    /// The response body was larger than the maximum size allowed by the
+    /// request configuration, and the transfer was aborted.
+    ///
+    /// See
+    /// [`Configurable::max_response_body_size`](crate::config::Configurable::max_response_body_size).
+    ResponseBodyTooLarge,
+
+    /// The connection was closed before the entire response body was received,
+    /// so the body received is shorter than the length declared by the server
+    /// in the `Content-Length` header.
+    ///
+    /// This usually indicates a network problem or a server that crashed while
+    /// sending the response. The part of the body received before the error
+    /// should not be trusted to be complete.
+    ResponseBodyTruncated,
+
     /// A request or operation took longer than the configured timeout time.
     Timeout,
 
@@ -91,6 +130,9 @@ pub enum ErrorKind {
     TlsEngine,
 
     /// Number of redirects hit the maximum configured amount.
+    ///
+    /// The error message includes the redirect limit and the URI at which it
+    /// was reached.
     TooManyRedirects,
 
     /// An unknown error occurred. This likely indicates a problem in the HTTP
@@ -108,8 +150,11 @@ impl ErrorKind {
         match self {
             Self::BadClientCertificate => Some("a problem occurred with the local certificate"),
             Self::BadServerCertificate => Some("the server certificate could not be validated"),
+            Self::Cancelled => Some("the request was cancelled"),
+            Self::ClientClosed => Some("the client has been closed"),
             Self::ClientInitialization => Some("failed to initialize client"),
             Self::ConnectionFailed => Some("failed to connect to the server"),
+            Self::InsecureRedirect => Some("refused to follow a redirect to an insecure location"),
             Self::InvalidContentEncoding => Some(
                 "the server either returned a response using an unknown or unsupported encoding format, or the response encoding was malformed",
             ),
@@ -121,9 +166,16 @@ impl ErrorKind {
             Self::ProtocolViolation => {
                 Some("the server made an unrecoverable HTTP protocol violation")
             }
+            Self::QueueTimeout => Some("request timed out while waiting to be sent"),
             Self::RequestBodyNotRewindable => {
                 Some("request body could not be re-sent because it is not rewindable")
             }
+            Self::ResponseBodyTooLarge => {
+                Some("response body exceeded the maximum allowed size")
+            }
+            Self::ResponseBodyTruncated => {
+                Some("response body was shorter than the declared content length")
+            }
             Self::Timeout => {
                 Some("request or operation took longer than the configured timeout time")
             }
@@ -172,6 +224,7 @@ struct Inner {
     source: Option<Box<dyn SourceError>>,
     local_addr: OnceCell<SocketAddr>,
     remote_addr: OnceCell<SocketAddr>,
+    retry_count: OnceCell<u32>,
 }
 
 impl Error {
@@ -195,12 +248,30 @@ impl Error {
             source: Some(Box::new(source)),
             local_addr: OnceCell::new(),
             remote_addr: OnceCell::new(),
+            retry_count: OnceCell::new(),
         }))
     }
 
     /// Create a new error from a given error kind and response.
     pub(crate) fn with_response<B>(kind: ErrorKind, response: &Response<B>) -> Self {
-        let error = Self::from(kind);
+        Self::with_response_context(kind, None, response)
+    }
+
+    /// Create a new error from a given error kind, response, and context
+    /// string.
+    pub(crate) fn with_response_context<B>(
+        kind: ErrorKind,
+        context: Option<String>,
+        response: &Response<B>,
+    ) -> Self {
+        let error = Self(Arc::new(Inner {
+            kind,
+            context,
+            source: None,
+            local_addr: OnceCell::new(),
+            remote_addr: OnceCell::new(),
+            retry_count: OnceCell::new(),
+        }));
 
         if let Some(addr) = response.local_addr() {
             let _ = error.0.local_addr.set(addr);
@@ -234,6 +305,8 @@ impl Error {
                         || error.is_ssl_cacert()
                         || error.is_ssl_cipher()
                         || error.is_ssl_issuer_error()
+                        || error.code() == curl_sys::CURLE_SSL_PINNEDPUBKEYNOTMATCH
+                        || error.code() == curl_sys::CURLE_SSL_INVALIDCERTSTATUS
                     {
                         ErrorKind::BadServerCertificate
                     } else if error.is_interface_failed() {
@@ -244,7 +317,7 @@ impl Error {
                         ErrorKind::InvalidContentEncoding
                     } else if error.is_login_denied() {
                         ErrorKind::InvalidCredentials
-                    } else if error.is_url_malformed() {
+                    } else if error.is_url_malformed() || error.is_bad_function_argument() {
                         ErrorKind::InvalidRequest
                     } else if error.is_couldnt_resolve_host() || error.is_couldnt_resolve_proxy() {
                         ErrorKind::NameResolution
@@ -262,9 +335,10 @@ impl Error {
                         || error.is_upload_failed()
                         || error.is_send_fail_rewind()
                         || error.is_aborted_by_callback()
-                        || error.is_partial_file()
                     {
                         ErrorKind::Io
+                    } else if error.is_partial_file() {
+                        ErrorKind::ResponseBodyTruncated
                     } else if error.is_ssl_engine_initfailed()
                         || error.is_ssl_engine_notfound()
                         || error.is_ssl_engine_setfailed()
@@ -301,6 +375,19 @@ impl Error {
     /// if the reason for the error is unknown. Unknown errors may be an
     /// indication of a bug, or an error condition that we do not recognize
     /// appropriately. Either way, please report such occurrences to us!
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use isahc::error::ErrorKind;
+    ///
+    /// match isahc::get("https://example.org") {
+    ///     Ok(response) => println!("status: {}", response.status()),
+    ///     Err(e) if e.kind() == ErrorKind::Timeout => eprintln!("timed out, try again"),
+    ///     Err(e) if e.kind() == ErrorKind::NameResolution => eprintln!("unknown host"),
+    ///     Err(e) => eprintln!("request failed: {}", e),
+    /// }
+    /// ```
     #[inline]
     pub fn kind(&self) -> &ErrorKind {
         &self.0.kind
@@ -315,6 +402,7 @@ impl Error {
     pub fn is_client(&self) -> bool {
         match self.kind() {
             ErrorKind::BadClientCertificate
+            | ErrorKind::ClientClosed
             | ErrorKind::ClientInitialization
             | ErrorKind::InvalidCredentials
             | ErrorKind::InvalidRequest
@@ -339,6 +427,7 @@ impl Error {
     pub fn is_server(&self) -> bool {
         match self.kind() {
             ErrorKind::BadServerCertificate
+            | ErrorKind::InsecureRedirect
             | ErrorKind::ProtocolViolation
             | ErrorKind::TooManyRedirects => true,
             _ => false,
@@ -358,7 +447,25 @@ impl Error {
     /// Sometimes retrying the request once or twice is enough to resolve the
     /// error.
     pub fn is_timeout(&self) -> bool {
-        self.kind() == ErrorKind::Timeout
+        match self.kind() {
+            ErrorKind::QueueTimeout | ErrorKind::Timeout => true,
+            _ => false,
+        }
+    }
+
+    /// Returns true if this error was caused by failing to connect to the
+    /// server.
+    ///
+    /// No part of the request will have been sent to the server, so it is
+    /// usually safe to retry the request regardless of its method.
+    pub fn is_connect(&self) -> bool {
+        self.kind() == ErrorKind::ConnectionFailed
+    }
+
+    /// Returns true if this error was caused by the number of redirects
+    /// exceeding the maximum configured by the redirect policy.
+    pub fn is_too_many_redirects(&self) -> bool {
+        self.kind() == ErrorKind::TooManyRedirects
     }
 
     /// Returns true if this error is related to SSL/TLS.
@@ -389,6 +496,15 @@ impl Error {
         self.0.remote_addr.get().cloned()
     }
 
+    /// Get the number of times the request was retried before this error was
+    /// returned by the final attempt.
+    ///
+    /// This will always be zero unless a retry policy is configured using
+    /// [`Configurable::retry_policy`](crate::config::Configurable::retry_policy).
+    pub fn retry_count(&self) -> u32 {
+        self.0.retry_count.get().cloned().unwrap_or(0)
+    }
+
     pub(crate) fn with_local_addr(self, addr: SocketAddr) -> Self {
         let _ = self.0.local_addr.set(addr);
         self
@@ -398,6 +514,11 @@ impl Error {
         let _ = self.0.remote_addr.set(addr);
         self
     }
+
+    pub(crate) fn with_retry_count(self, count: u32) -> Self {
+        let _ = self.0.retry_count.set(count);
+        self
+    }
 }
 
 impl StdError for Error {
@@ -424,6 +545,7 @@ impl fmt::Debug for Error {
             )
             .field("local_addr", &self.0.local_addr.get())
             .field("remote_addr", &self.0.remote_addr.get())
+            .field("retry_count", &self.0.retry_count.get())
             .finish()
     }
 }
@@ -446,6 +568,7 @@ impl From<ErrorKind> for Error {
             source: None,
             local_addr: OnceCell::new(),
             remote_addr: OnceCell::new(),
+            retry_count: OnceCell::new(),
         }))
     }
 }
@@ -474,7 +597,7 @@ impl From<Error> for io::Error {
     fn from(error: Error) -> Self {
         let kind = match error.kind() {
             ErrorKind::ConnectionFailed => io::ErrorKind::ConnectionRefused,
-            ErrorKind::Timeout => io::ErrorKind::TimedOut,
+            ErrorKind::QueueTimeout | ErrorKind::Timeout => io::ErrorKind::TimedOut,
             _ => io::ErrorKind::Other,
         };
 
@@ -524,6 +647,72 @@ impl<T: StdError + Send + Sync + 'static> SourceError for T {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use test_case::test_case;
 
     static_assertions::assert_impl_all!(Error: Send, Sync);
+
+    #[test_case(curl_sys::CURLE_OPERATION_TIMEDOUT, Error::is_timeout)]
+    #[test_case(curl_sys::CURLE_COULDNT_CONNECT, Error::is_connect)]
+    #[test_case(curl_sys::CURLE_PEER_FAILED_VERIFICATION, Error::is_tls)]
+    #[test_case(curl_sys::CURLE_SSL_CERTPROBLEM, Error::is_tls)]
+    #[test_case(curl_sys::CURLE_TOO_MANY_REDIRECTS, Error::is_too_many_redirects)]
+    #[test_case(curl_sys::CURLE_COULDNT_RESOLVE_HOST, Error::is_network)]
+    fn classify_curl_errors(code: curl_sys::CURLcode, predicate: fn(&Error) -> bool) {
+        let error = Error::from_any(curl::Error::new(code));
+
+        assert!(predicate(&error));
+    }
+
+    #[test]
+    fn curl_error_is_preserved_as_source() {
+        let error = Error::from_any(curl::Error::new(curl_sys::CURLE_COULDNT_RESOLVE_HOST));
+
+        assert_eq!(error.kind(), &ErrorKind::NameResolution);
+        assert_eq!(
+            error
+                .source()
+                .unwrap()
+                .downcast_ref::<curl::Error>()
+                .unwrap()
+                .code(),
+            curl_sys::CURLE_COULDNT_RESOLVE_HOST
+        );
+    }
+
+    #[test]
+    fn http_error_is_preserved_as_source() {
+        let error = Error::from(
+            http::Request::builder()
+                .uri("not a valid uri")
+                .body(())
+                .unwrap_err(),
+        );
+
+        assert_eq!(error.kind(), &ErrorKind::InvalidRequest);
+        assert!(error.source().unwrap().is::<http::Error>());
+    }
+
+    #[test]
+    fn io_error_is_preserved_as_source() {
+        let error = Error::from(io::Error::new(io::ErrorKind::BrokenPipe, "pipe closed"));
+
+        assert_eq!(error.kind(), &ErrorKind::Io);
+        assert_eq!(
+            error
+                .source()
+                .unwrap()
+                .downcast_ref::<io::Error>()
+                .unwrap()
+                .kind(),
+            io::ErrorKind::BrokenPipe
+        );
+    }
+
+    #[test]
+    fn pinned_public_key_mismatch_is_bad_server_certificate() {
+        let error = Error::from_any(curl::Error::new(curl_sys::CURLE_SSL_PINNEDPUBKEYNOTMATCH));
+
+        assert_eq!(error.kind(), &ErrorKind::BadServerCertificate);
+        assert!(error.is_tls());
+    }
 }
@@ -2,14 +2,18 @@
 
 use crate::{
     body::AsyncBody,
+    cancel::CancellationToken,
+    config::progress::ProgressCallback,
     error::{Error, ErrorKind},
     metrics::Metrics,
     parsing::{parse_header, parse_status_line},
-    response::{LocalAddr, RemoteAddr},
+    response::{ConnectionReused, LocalAddr, RemoteAddr},
+    summary::{CompletionCallback, TransferSummary},
+    tls::TlsInfo,
     trailer::TrailerWriter,
 };
 use async_channel::Sender;
-use curl::easy::{InfoType, ReadError, SeekResult, WriteError};
+use curl::easy::{Easy2, InfoType, ReadError, SeekResult, WriteError};
 use curl_sys::CURL;
 use futures_lite::io::{AsyncRead, AsyncWrite};
 use http::Response;
@@ -17,7 +21,7 @@ use once_cell::sync::OnceCell;
 use sluice::pipe;
 use std::{
     ascii,
-    ffi::CStr,
+    ffi::{CStr, CString},
     fmt,
     future::Future,
     io,
@@ -26,14 +30,23 @@ use std::{
     os::raw::{c_char, c_long},
     pin::Pin,
     ptr,
-    sync::Arc,
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        Arc,
+    },
     task::{Context, Poll, Waker},
+    time::{Duration, Instant},
 };
 
 pub(crate) struct RequestBody(pub(crate) AsyncBody);
 
 /// Manages the state of a single request/response life cycle.
 ///
+/// This is the handler of the curl easy handle used to send each request. Its
+/// internals are private; it is only exposed so that the easy handle passed to
+/// [`HttpClientBuilder::with_curl`](crate::HttpClientBuilder::with_curl) can be
+/// named.
+///
 /// During the lifetime of a handler, it will receive callbacks from curl about
 /// the progress of the request, and the handler will incrementally build up a
 /// response struct as the response is received.
@@ -46,7 +59,7 @@ pub(crate) struct RequestBody(pub(crate) AsyncBody);
 ///
 /// If dropped before the response is finished, the associated future will be
 /// completed with an error.
-pub(crate) struct RequestHandler {
+pub struct RequestHandler {
     /// A tracing span for grouping log events under. Since a request is
     /// processed asynchronously inside an agent thread, this span helps
     /// maintain a link to the parent context where the request is actually
@@ -89,9 +102,34 @@ pub(crate) struct RequestHandler {
     /// headers out-of-band from the response headers and body.
     response_trailer_writer: TrailerWriter,
 
+    /// Whether metrics should be collected for this request.
+    pub(crate) enable_metrics: bool,
+
     /// Metrics object for publishing metrics data to. Lazily initialized.
     metrics: Option<Metrics>,
 
+    /// Time at which the request was submitted to an agent.
+    pub(crate) submitted_at: Option<Instant>,
+
+    /// Time spent between the request being submitted to an agent and curl
+    /// starting the transfer.
+    queue_time: Option<Duration>,
+
+    /// Callback to report upload progress to, if any.
+    pub(crate) upload_progress: Option<ProgressCallback>,
+
+    /// Total size of the request body, if known.
+    upload_total: Option<u64>,
+
+    /// Number of request body bytes last reported as sent.
+    upload_reported: u64,
+
+    /// Callback to report download progress to, if any.
+    pub(crate) download_progress: Option<ProgressCallback>,
+
+    /// Number of response body bytes last reported as received.
+    download_reported: u64,
+
     /// Raw pointer to the associated curl easy handle. The pointer is not owned
     /// by this struct, but the parent struct to this one, so we know it will be
     /// valid at least for the lifetime of this struct (assuming all other
@@ -100,6 +138,55 @@ pub(crate) struct RequestHandler {
 
     /// If true, do not warn about prematurely closed responses.
     pub(crate) disable_connection_reuse_log: bool,
+
+    /// Maximum number of response body bytes to accept before aborting.
+    pub(crate) max_response_body_size: Option<u64>,
+
+    /// Maximum number of bytes of the response body to buffer before pausing
+    /// the transfer, if any.
+    pub(crate) read_buffer_size: Option<usize>,
+
+    /// Number of response body bytes received so far.
+    response_body_received: u64,
+
+    /// Maximum amount of time to wait for the response headers, if any.
+    pub(crate) header_timeout: Option<Duration>,
+
+    /// Time at which the request is aborted if the response headers have not
+    /// been received yet. Set when the request begins.
+    header_deadline: Option<Instant>,
+
+    /// Set once the final (non-informational) response header has been
+    /// received in full.
+    response_headers_received: bool,
+
+    /// Callback to report a summary of the transfer to once it completes, if
+    /// any.
+    pub(crate) on_complete: Option<CompletionCallback>,
+
+    /// Method of the request.
+    pub(crate) request_method: http::Method,
+
+    /// URI of the request.
+    pub(crate) request_uri: http::Uri,
+
+    /// Information about the TLS session of the connection, captured once the
+    /// final response headers are received.
+    tls_info: Option<TlsInfo>,
+
+    /// Request headers given to curl. Curl does not copy the list, so it must
+    /// be kept alive for as long as the handle is.
+    request_headers: Option<HeaderList>,
+
+    /// Token that can be used to cancel this transfer.
+    pub(crate) cancellation_token: Option<CancellationToken>,
+
+    /// Key of the waker registered with the cancellation token, if any.
+    cancellation_key: Option<usize>,
+
+    /// Time by which the transfer must be complete, if any. This is enforced
+    /// by the agent.
+    pub(crate) deadline: Option<Instant>,
 }
 
 // Would be send implicitly except for the raw CURL pointer.
@@ -114,6 +201,93 @@ struct Shared {
     /// to communicate an error while reading the response body if the handler
     /// suddenly aborts.
     result: OnceCell<Result<(), Error>>,
+
+    /// Set if the response future is dropped before the response is received.
+    future_dropped: AtomicBool,
+
+    /// A waker that asks the agent to abort the transfer. Populated by an agent
+    /// when the request is initialized.
+    cancel_waker: OnceCell<Waker>,
+
+    /// Number of bytes of the response body that have been written to the
+    /// pipe but not yet read by the consumer.
+    response_body_buffered: AtomicUsize,
+
+    /// Set if the transfer is paused because too much of the response body is
+    /// buffered.
+    response_body_paused: AtomicBool,
+
+    /// A waker that asks the agent to resume writing the response body.
+    /// Populated by an agent when the request is initialized.
+    response_body_waker: OnceCell<Waker>,
+}
+
+impl Shared {
+    /// Ask the agent to abort the transfer, if it has begun.
+    fn wake_cancel(&self) {
+        if let Some(waker) = self.cancel_waker.get() {
+            waker.wake_by_ref();
+        }
+    }
+
+    /// Check whether writing `len` more bytes of the response body would
+    /// exceed the given buffer limit. If so, the transfer is marked as paused
+    /// until the consumer reads from the buffer.
+    ///
+    /// An empty buffer always accepts more data, so that the transfer cannot
+    /// stall if a single chunk is larger than the limit.
+    fn is_response_body_buffer_full(&self, limit: usize, len: usize) -> bool {
+        let is_full = || {
+            let buffered = self.response_body_buffered.load(Ordering::SeqCst);
+            buffered > 0 && buffered + len > limit
+        };
+
+        if !is_full() {
+            return false;
+        }
+
+        self.response_body_paused.store(true, Ordering::SeqCst);
+
+        // The consumer may have drained the buffer before seeing the pause
+        // flag, in which case nobody would resume the transfer.
+        if !is_full() {
+            self.response_body_paused.store(false, Ordering::SeqCst);
+            return false;
+        }
+
+        true
+    }
+
+    /// Record that the consumer has read bytes from the response body buffer,
+    /// and resume the transfer if it was paused.
+    fn response_body_consumed(&self, len: usize) {
+        self.response_body_buffered.fetch_sub(len, Ordering::SeqCst);
+
+        if self.response_body_paused.swap(false, Ordering::SeqCst) {
+            if let Some(waker) = self.response_body_waker.get() {
+                waker.wake_by_ref();
+            }
+        }
+    }
+}
+
+/// Guard that cancels the transfer when the response future is dropped before
+/// the response is received.
+struct CancelOnDrop(Option<Arc<Shared>>);
+
+impl CancelOnDrop {
+    fn disarm(&mut self) {
+        self.0 = None;
+    }
+}
+
+impl Drop for CancelOnDrop {
+    fn drop(&mut self) {
+        if let Some(shared) = self.0.take() {
+            shared.future_dropped.store(true, Ordering::SeqCst);
+            shared.wake_cancel();
+        }
+    }
 }
 
 impl RequestHandler {
@@ -127,9 +301,16 @@ impl RequestHandler {
         let (sender, receiver) = async_channel::bounded(1);
         let shared = Arc::new(Shared::default());
         let (response_body_reader, response_body_writer) = pipe::pipe();
+        let upload_total = request_body.len();
 
         let handler = Self {
-            span: tracing::debug_span!("handler", id = tracing::field::Empty),
+            span: tracing::debug_span!(
+                "handler",
+                id = tracing::field::Empty,
+                status = tracing::field::Empty,
+                bytes_uploaded = tracing::field::Empty,
+                bytes_downloaded = tracing::field::Empty,
+            ),
             sender: Some(sender),
             shared: shared.clone(),
             request_body,
@@ -140,18 +321,44 @@ impl RequestHandler {
             response_body_writer,
             response_body_waker: None,
             response_trailer_writer: TrailerWriter::new(),
+            enable_metrics: false,
             metrics: None,
+            submitted_at: None,
+            queue_time: None,
+            upload_progress: None,
+            upload_total,
+            upload_reported: 0,
+            download_progress: None,
+            download_reported: 0,
             handle: ptr::null_mut(),
             disable_connection_reuse_log: false,
+            max_response_body_size: None,
+            read_buffer_size: None,
+            response_body_received: 0,
+            header_timeout: None,
+            header_deadline: None,
+            response_headers_received: false,
+            on_complete: None,
+            request_method: http::Method::default(),
+            request_uri: http::Uri::default(),
+            tls_info: None,
+            request_headers: None,
+            cancellation_token: None,
+            cancellation_key: None,
+            deadline: None,
         };
 
+        // Dropping the future at any point before the response is received
+        // cancels the transfer, even if the future was never polled.
+        let mut guard = CancelOnDrop(Some(shared.clone()));
+
         // Create a future that resolves when the handler receives the response
         // headers.
         let future = async move {
-            let builder = receiver
-                .recv()
-                .await
-                .map_err(|e| Error::new(ErrorKind::Unknown, e))??;
+            let result = receiver.recv().await;
+            guard.disarm();
+
+            let builder = result.map_err(|e| Error::new(ErrorKind::Unknown, e))??;
 
             let reader = ResponseBodyReader {
                 inner: response_body_reader,
@@ -186,6 +393,24 @@ impl RequestHandler {
 
     fn is_future_canceled(&self) -> bool {
         self.sender.as_ref().map(Sender::is_closed).unwrap_or(false)
+            || self.shared.future_dropped.load(Ordering::SeqCst)
+    }
+
+    /// Check whether this transfer has been cancelled, either by dropping the
+    /// response future or using a cancellation token.
+    pub(crate) fn is_cancelled(&self) -> bool {
+        self.is_future_canceled()
+            || matches!(&self.cancellation_token, Some(token) if token.is_cancelled())
+    }
+
+    /// Get the error to fail the transfer with when its deadline is reached.
+    pub(crate) fn deadline_error(&self) -> Error {
+        // Curl does not track the time taken by a transfer that is still
+        // waiting for a connection to become available.
+        let queued = self.get_info_double(curl_sys::CURLINFO_TOTAL_TIME) <= 0.0
+            && self.get_info_double(curl_sys::CURLINFO_PRETRANSFER_TIME) <= 0.0;
+
+        deadline_error(queued)
     }
 
     /// Initialize the handler and prepare it for the request to begin.
@@ -199,6 +424,7 @@ impl RequestHandler {
         handle: *mut CURL,
         request_waker: Waker,
         response_waker: Waker,
+        cancel_waker: Waker,
     ) {
         let _enter = self.span.enter();
 
@@ -206,14 +432,98 @@ impl RequestHandler {
         debug_assert!(self.request_body_waker.is_none());
         debug_assert!(self.response_body_waker.is_none());
 
-        self.span.record("id", &id);
+        self.span.record("id", id);
         self.handle = handle;
         self.request_body_waker = Some(request_waker);
-        self.response_body_waker = Some(response_waker);
+        self.response_body_waker = Some(response_waker.clone());
+        self.header_deadline = self.header_timeout.map(|timeout| Instant::now() + timeout);
+
+        if let Some(token) = self.cancellation_token.as_ref() {
+            self.cancellation_key = token.register(cancel_waker.clone());
+        }
+
+        let _ = self.shared.cancel_waker.set(cancel_waker);
+        let _ = self.shared.response_body_waker.set(response_waker);
+
+        // The future may have been dropped before the waker was set.
+        if self.is_future_canceled() {
+            self.shared.wake_cancel();
+        }
     }
 
-    /// Set the final result for this transfer.
+    /// Set the final result for this transfer. This is called by the agent
+    /// once curl is done with the transfer.
     pub(crate) fn set_result(&mut self, result: Result<(), Error>) {
+        let result = match result {
+            // The transfer was aborted because it was cancelled, which is more
+            // useful to report than whatever error curl gave for the abort.
+            Err(_) if self.is_cancelled() => Err(ErrorKind::Cancelled.into()),
+            result => result.and_then(|()| self.check_response_body_length()),
+        };
+
+        self.store_result(result);
+
+        // Capture final timing and transfer values now that the transfer has
+        // ended, since the last progress callback may be slightly out of date.
+        self.scrape_metrics();
+
+        self.report_completion();
+
+        // Flush the trailer, if we haven't already.
+        self.response_trailer_writer.flush();
+
+        // Complete the response future, if we haven't already.
+        self.complete_response_future();
+    }
+
+    /// Check that the entire response body declared by the server in the
+    /// `Content-Length` header was received.
+    ///
+    /// Curl usually fails transfers that are cut short on its own, but this
+    /// makes sure that a transfer that ended cleanly with part of the body
+    /// missing is never mistaken for a successful one.
+    fn check_response_body_length(&self) -> Result<(), Error> {
+        // Responses to HEAD requests and responses with certain status codes
+        // never have a body, whatever the Content-Length header says.
+        let has_body = match self.response_status_code {
+            Some(status) => {
+                !status.is_informational()
+                    && status != http::StatusCode::NO_CONTENT
+                    && status != http::StatusCode::NOT_MODIFIED
+            }
+            None => false,
+        };
+
+        if !has_body || self.request_method == http::Method::HEAD {
+            return Ok(());
+        }
+
+        // Curl counts the body bytes as received, before any decompression,
+        // so they can be compared against the declared length directly.
+        let expected = self.get_info_double(curl_sys::CURLINFO_CONTENT_LENGTH_DOWNLOAD);
+        let received = self.get_info_double(curl_sys::CURLINFO_SIZE_DOWNLOAD);
+
+        if expected >= 0.0 && received < expected {
+            tracing::debug!(expected, received, "response body was truncated");
+
+            return Err(Error::new(
+                ErrorKind::ResponseBodyTruncated,
+                io::Error::new(
+                    io::ErrorKind::UnexpectedEof,
+                    format!(
+                        "expected {} bytes of response body, but only {} were received",
+                        expected as u64, received as u64,
+                    ),
+                ),
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Store the result of this transfer, unless a result has already been
+    /// stored.
+    fn store_result(&mut self, result: Result<(), Error>) {
         let result = result.map_err(|mut e| {
             if let Some(addr) = self.get_local_addr() {
                 e = e.with_local_addr(addr);
@@ -229,12 +539,35 @@ impl RequestHandler {
         if self.shared.result.set(result).is_err() {
             tracing::debug!("attempted to set error multiple times");
         }
+    }
 
-        // Flush the trailer, if we haven't already.
-        self.response_trailer_writer.flush();
-
-        // Complete the response future, if we haven't already.
-        self.complete_response_future();
+    /// Report the outcome of the completed transfer to the tracing span and
+    /// the completion callback, if any.
+    fn report_completion(&mut self) {
+        let summary = self.summarize();
+
+        self.span.record("bytes_uploaded", summary.bytes_uploaded);
+        self.span.record("bytes_downloaded", summary.bytes_downloaded);
+
+        let name_lookup = self.get_info_double(curl_sys::CURLINFO_NAMELOOKUP_TIME);
+        let connect = self.get_info_double(curl_sys::CURLINFO_CONNECT_TIME);
+        let app_connect = self.get_info_double(curl_sys::CURLINFO_APPCONNECT_TIME);
+        let start_transfer = self.get_info_double(curl_sys::CURLINFO_STARTTRANSFER_TIME);
+
+        tracing::debug!(
+            parent: &self.span,
+            name_lookup_time = ?Duration::from_secs_f64(name_lookup),
+            connect_time = ?Duration::from_secs_f64((connect - name_lookup).max(0.0)),
+            secure_connect_time = ?Duration::from_secs_f64((app_connect - connect).max(0.0)),
+            transfer_start_time = ?Duration::from_secs_f64(start_transfer),
+            total_time = ?summary.total_time,
+            error = summary.error.is_some(),
+            "transfer complete",
+        );
+
+        if let Some(callback) = self.on_complete.take() {
+            callback.call(&summary);
+        }
     }
 
     /// Mark the future as completed successfully with the response headers
@@ -280,6 +613,14 @@ impl RequestHandler {
             builder = builder.extension(RemoteAddr(addr));
         }
 
+        if let Some(connects) = self.get_num_connects() {
+            builder = builder.extension(ConnectionReused(connects == 0));
+        }
+
+        if let Some(info) = self.tls_info.take() {
+            builder = builder.extension(info);
+        }
+
         // Keep the request body around in case interceptors need access to
         // it. Otherwise we're just going to drop it later.
         builder = builder.extension(RequestBody(mem::take(&mut self.request_body)));
@@ -297,6 +638,123 @@ impl RequestHandler {
         builder
     }
 
+    /// Record that curl has started working on the transfer, if it has not been
+    /// recorded already.
+    fn record_transfer_start(&mut self) {
+        if self.queue_time.is_none() {
+            self.queue_time = self.submitted_at.map(|submitted_at| submitted_at.elapsed());
+        }
+    }
+
+    /// Read the latest transfer statistics from curl into our metrics object,
+    /// if metrics are being collected for this request.
+    fn scrape_metrics(&self) {
+        if self.handle.is_null() {
+            return;
+        }
+
+        if let Some(metrics) = self.metrics.as_ref() {
+            if let Some(queue_time) = self.queue_time {
+                metrics.inner.queue_time.store(queue_time.as_secs_f64());
+            }
+
+            unsafe {
+                curl_sys::curl_easy_getinfo(
+                    self.handle,
+                    curl_sys::CURLINFO_SPEED_UPLOAD,
+                    metrics.inner.upload_speed.as_ptr(),
+                );
+
+                curl_sys::curl_easy_getinfo(
+                    self.handle,
+                    curl_sys::CURLINFO_SPEED_DOWNLOAD,
+                    metrics.inner.download_speed.as_ptr(),
+                );
+
+                curl_sys::curl_easy_getinfo(
+                    self.handle,
+                    curl_sys::CURLINFO_NAMELOOKUP_TIME,
+                    metrics.inner.namelookup_time.as_ptr(),
+                );
+
+                curl_sys::curl_easy_getinfo(
+                    self.handle,
+                    curl_sys::CURLINFO_CONNECT_TIME,
+                    metrics.inner.connect_time.as_ptr(),
+                );
+
+                curl_sys::curl_easy_getinfo(
+                    self.handle,
+                    curl_sys::CURLINFO_APPCONNECT_TIME,
+                    metrics.inner.appconnect_time.as_ptr(),
+                );
+
+                curl_sys::curl_easy_getinfo(
+                    self.handle,
+                    curl_sys::CURLINFO_PRETRANSFER_TIME,
+                    metrics.inner.pretransfer_time.as_ptr(),
+                );
+
+                curl_sys::curl_easy_getinfo(
+                    self.handle,
+                    curl_sys::CURLINFO_STARTTRANSFER_TIME,
+                    metrics.inner.starttransfer_time.as_ptr(),
+                );
+
+                curl_sys::curl_easy_getinfo(
+                    self.handle,
+                    curl_sys::CURLINFO_TOTAL_TIME,
+                    metrics.inner.total_time.as_ptr(),
+                );
+
+                curl_sys::curl_easy_getinfo(
+                    self.handle,
+                    curl_sys::CURLINFO_REDIRECT_TIME,
+                    metrics.inner.redirect_time.as_ptr(),
+                );
+            }
+        }
+    }
+
+    /// Build a summary of the transfer. Should only be called once the final
+    /// result has been set.
+    fn summarize(&mut self) -> TransferSummary {
+        let bytes_uploaded = self.get_info_double(curl_sys::CURLINFO_SIZE_UPLOAD);
+        let bytes_downloaded = self.get_info_double(curl_sys::CURLINFO_SIZE_DOWNLOAD);
+        let total_time = self.get_info_double(curl_sys::CURLINFO_TOTAL_TIME);
+
+        // A transfer that failed before connecting reports zero connects,
+        // which does not mean that a connection was reused.
+        let connection_reused = match self.get_num_connects() {
+            Some(0) if matches!(self.get_primary_ip(), None | Some("")) => None,
+            connects => connects.map(|connects| connects == 0),
+        };
+
+        TransferSummary {
+            method: self.request_method.clone(),
+            uri: self.request_uri.clone(),
+            status: self.response_status_code,
+            bytes_uploaded: bytes_uploaded as u64,
+            bytes_downloaded: bytes_downloaded as u64,
+            total_time: Duration::from_secs_f64(total_time.max(0.0)),
+            connection_reused,
+            error: self.shared.result.get().and_then(|result| result.clone().err()),
+        }
+    }
+
+    /// Get a floating-point info value from curl, or zero if unavailable.
+    fn get_info_double(&self, info: curl_sys::CURLINFO) -> f64 {
+        let mut value = 0f64;
+
+        if !self.handle.is_null() {
+            unsafe {
+                curl_sys::curl_easy_getinfo(self.handle, info, &mut value);
+            }
+        }
+
+        value
+    }
+
     fn get_primary_addr(&mut self) -> Option<SocketAddr> {
         let ip = self.get_primary_ip()?.parse().ok()?;
         let port = self.get_primary_port()?;
@@ -344,6 +802,29 @@ impl RequestHandler {
         Some(port as u16)
     }
 
+    /// Get the number of new connections that had to be created in order to
+    /// perform the transfer.
+    fn get_num_connects(&mut self) -> Option<c_long> {
+        if self.handle.is_null() {
+            return None;
+        }
+
+        let mut connects: c_long = 0;
+
+        unsafe {
+            if curl_sys::curl_easy_getinfo(
+                self.handle,
+                curl_sys::CURLINFO_NUM_CONNECTS,
+                &mut connects,
+            ) != curl_sys::CURLE_OK
+            {
+                return None;
+            }
+        }
+
+        Some(connects)
+    }
+
     fn get_local_addr(&mut self) -> Option<SocketAddr> {
         let ip = self.get_local_ip()?.parse().ok()?;
         let port = self.get_local_port()?;
@@ -396,10 +877,12 @@ impl curl::easy::Handler for RequestHandler {
     /// Gets called by curl for each line of data in the HTTP response header.
     fn header(&mut self, data: &[u8]) -> bool {
         // Abort the request if it has been canceled.
-        if self.is_future_canceled() {
+        if self.is_cancelled() {
             return false;
         }
 
+        self.record_transfer_start();
+
         let span = tracing::trace_span!(parent: &self.span, "header");
         let _enter = span.enter();
 
@@ -440,6 +923,25 @@ impl curl::easy::Handler for RequestHandler {
 
         // Is this the end of the response header?
         if data == b"\r\n" {
+            // Informational responses are followed by another response, so
+            // they don't count towards the header timeout.
+            if let Some(status) = self.response_status_code {
+                if !status.is_informational() {
+                    self.response_headers_received = true;
+
+                    // The TLS session is only accessible while the connection
+                    // is in use, so capture its details now.
+                    self.tls_info = TlsInfo::from_handle(self.handle);
+
+                    self.span.record("status", status.as_u16());
+                    tracing::debug!(
+                        parent: &self.span,
+                        status = status.as_u16(),
+                        "received response headers"
+                    );
+                }
+            }
+
             // We will acknowledge the end of the header, but we can't complete
             // our response future yet. If curl decides to follow a redirect,
             // then this current response is not the final response and not the
@@ -457,10 +959,12 @@ impl curl::easy::Handler for RequestHandler {
     /// Gets called by curl when attempting to send bytes of the request body.
     fn read(&mut self, data: &mut [u8]) -> Result<usize, ReadError> {
         // Abort the request if it has been canceled.
-        if self.is_future_canceled() {
+        if self.is_cancelled() {
             return Err(ReadError::Abort);
         }
 
+        self.record_transfer_start();
+
         let span = tracing::trace_span!(parent: &self.span, "read");
         let _enter = span.enter();
 
@@ -481,7 +985,7 @@ impl curl::easy::Handler for RequestHandler {
                     // will ever have. That's why we set the error now, to
                     // improve the error message. Otherwise we'll return a
                     // rather generic-sounding I/O error to the caller.
-                    self.set_result(Err(e.into()));
+                    self.store_result(Err(e.into()));
 
                     Err(ReadError::Abort)
                 }
@@ -520,18 +1024,58 @@ impl curl::easy::Handler for RequestHandler {
         let _enter = span.enter();
         tracing::trace!("received {} bytes of data", data.len());
 
+        // Abort the transfer if the response body is larger than allowed. If
+        // this is the first chunk of the body, the response future will be
+        // completed with the error as well.
+        if let Some(max) = self.max_response_body_size {
+            if self.response_body_received + data.len() as u64 > max {
+                tracing::debug!(max, "response body exceeded maximum size, aborting");
+
+                let _ = self
+                    .shared
+                    .result
+                    .set(Err(Error::from(ErrorKind::ResponseBodyTooLarge)));
+                self.complete_response_future();
+
+                return Ok(0);
+            }
+        }
+
         // Now that we've started receiving the response body, we know no more
         // redirects can happen and we can complete the future safely.
         self.complete_response_future();
 
+        // Pause the transfer if the consumer has fallen too far behind. It is
+        // resumed once the consumer reads from the buffer.
+        if let Some(limit) = self.read_buffer_size {
+            if self.shared.is_response_body_buffer_full(limit, data.len()) {
+                tracing::trace!(limit, "response body buffer is full, pausing transfer");
+                return Err(WriteError::Pause);
+            }
+        }
+
         // Create a task context using a waker provided by the agent so we can
         // do an asynchronous write.
         if let Some(waker) = self.response_body_waker.as_ref() {
             let mut context = Context::from_waker(waker);
 
+            // Count the data as buffered before writing it, since the consumer
+            // may read it right away.
+            self.shared
+                .response_body_buffered
+                .fetch_add(data.len(), Ordering::SeqCst);
+
             match Pin::new(&mut self.response_body_writer).poll_write(&mut context, data) {
-                Poll::Pending => Err(WriteError::Pause),
-                Poll::Ready(Ok(len)) => Ok(len),
+                Poll::Pending => {
+                    self.shared
+                        .response_body_buffered
+                        .fetch_sub(data.len(), Ordering::SeqCst);
+                    Err(WriteError::Pause)
+                }
+                Poll::Ready(Ok(len)) => {
+                    self.response_body_received += len as u64;
+                    Ok(len)
+                }
                 Poll::Ready(Err(e)) => {
                     if e.kind() == io::ErrorKind::BrokenPipe {
                         // Only warn about connections closed for HTTP/1.x.
@@ -559,72 +1103,61 @@ impl curl::easy::Handler for RequestHandler {
 
     /// Capture transfer progress updates from curl.
     fn progress(&mut self, dltotal: f64, dlnow: f64, ultotal: f64, ulnow: f64) -> bool {
-        // Initialize metrics if required.
-        let metrics = self.metrics.get_or_insert_with(Metrics::new);
-
-        // Store the progress values given.
-        metrics.inner.upload_progress.store(ulnow);
-        metrics.inner.upload_total.store(ultotal);
-        metrics.inner.download_progress.store(dlnow);
-        metrics.inner.download_total.store(dltotal);
-
-        // Also scrape additional metrics.
-        if !self.handle.is_null() {
-            unsafe {
-                curl_sys::curl_easy_getinfo(
-                    self.handle,
-                    curl_sys::CURLINFO_SPEED_UPLOAD,
-                    metrics.inner.upload_speed.as_ptr(),
-                );
-
-                curl_sys::curl_easy_getinfo(
-                    self.handle,
-                    curl_sys::CURLINFO_SPEED_DOWNLOAD,
-                    metrics.inner.download_speed.as_ptr(),
-                );
+        self.record_transfer_start();
+
+        // Abort the transfer if the response headers did not arrive in time.
+        if !self.response_headers_received {
+            if let Some(deadline) = self.header_deadline {
+                if Instant::now() >= deadline {
+                    tracing::debug!("response headers not received in time, aborting");
+
+                    let _ = self.shared.result.set(Err(Error::new(
+                        ErrorKind::Timeout,
+                        io::Error::new(
+                            io::ErrorKind::TimedOut,
+                            "response headers were not received in time",
+                        ),
+                    )));
+                    self.complete_response_future();
+
+                    return false;
+                }
+            }
+        }
 
-                curl_sys::curl_easy_getinfo(
-                    self.handle,
-                    curl_sys::CURLINFO_NAMELOOKUP_TIME,
-                    metrics.inner.namelookup_time.as_ptr(),
-                );
+        // Report upload progress if it has changed since last time.
+        if let Some(callback) = self.upload_progress.as_ref() {
+            let sent = ulnow as u64;
 
-                curl_sys::curl_easy_getinfo(
-                    self.handle,
-                    curl_sys::CURLINFO_CONNECT_TIME,
-                    metrics.inner.connect_time.as_ptr(),
-                );
+            if sent != self.upload_reported {
+                self.upload_reported = sent;
+                callback.call(sent, self.upload_total);
+            }
+        }
 
-                curl_sys::curl_easy_getinfo(
-                    self.handle,
-                    curl_sys::CURLINFO_APPCONNECT_TIME,
-                    metrics.inner.appconnect_time.as_ptr(),
-                );
+        // Report download progress if it has changed since last time. Curl
+        // reports a total of zero if the size is not known.
+        if let Some(callback) = self.download_progress.as_ref() {
+            let received = dlnow as u64;
 
-                curl_sys::curl_easy_getinfo(
-                    self.handle,
-                    curl_sys::CURLINFO_PRETRANSFER_TIME,
-                    metrics.inner.pretransfer_time.as_ptr(),
-                );
+            if received != self.download_reported {
+                self.download_reported = received;
+                callback.call(received, Some(dltotal as u64).filter(|&total| total > 0));
+            }
+        }
 
-                curl_sys::curl_easy_getinfo(
-                    self.handle,
-                    curl_sys::CURLINFO_STARTTRANSFER_TIME,
-                    metrics.inner.starttransfer_time.as_ptr(),
-                );
+        if self.enable_metrics {
+            // Initialize metrics if required.
+            let metrics = self.metrics.get_or_insert_with(Metrics::new);
 
-                curl_sys::curl_easy_getinfo(
-                    self.handle,
-                    curl_sys::CURLINFO_TOTAL_TIME,
-                    metrics.inner.total_time.as_ptr(),
-                );
+            // Store the progress values given.
+            metrics.inner.upload_progress.store(ulnow);
+            metrics.inner.upload_total.store(ultotal);
+            metrics.inner.download_progress.store(dlnow);
+            metrics.inner.download_total.store(dltotal);
 
-                curl_sys::curl_easy_getinfo(
-                    self.handle,
-                    curl_sys::CURLINFO_REDIRECT_TIME,
-                    metrics.inner.redirect_time.as_ptr(),
-                );
-            }
+            // Also scrape additional metrics.
+            self.scrape_metrics();
         }
 
         true
@@ -663,12 +1196,95 @@ impl curl::easy::Handler for RequestHandler {
     }
 }
 
+impl Drop for RequestHandler {
+    fn drop(&mut self) {
+        // Stop listening for cancellation, since there is nothing to cancel
+        // anymore.
+        if let Some(key) = self.cancellation_key.take() {
+            if let Some(token) = self.cancellation_token.as_ref() {
+                token.unregister(key);
+            }
+        }
+    }
+}
+
 impl fmt::Debug for RequestHandler {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "RequestHandler")
     }
 }
 
+/// Get the error to fail a request with when its total timeout is reached,
+/// depending on whether it was still waiting to be sent.
+pub(crate) fn deadline_error(queued: bool) -> Error {
+    if queued {
+        Error::new(
+            ErrorKind::QueueTimeout,
+            io::Error::new(
+                io::ErrorKind::TimedOut,
+                "request was not sent before the total timeout was reached",
+            ),
+        )
+    } else {
+        Error::new(
+            ErrorKind::Timeout,
+            io::Error::new(io::ErrorKind::TimedOut, "total timeout was reached"),
+        )
+    }
+}
+
+/// A list of request headers to be sent by curl.
+///
+/// This is used instead of [`curl::easy::List`] since that only accepts UTF-8
+/// strings, while header values may contain arbitrary non-UTF-8 bytes.
+pub(crate) struct HeaderList {
+    raw: *mut curl_sys::curl_slist,
+}
+
+impl HeaderList {
+    pub(crate) fn new() -> Self {
+        Self {
+            raw: ptr::null_mut(),
+        }
+    }
+
+    /// Append a raw header line to the list.
+    pub(crate) fn append(&mut self, header: &[u8]) -> Result<(), curl::Error> {
+        let header = CString::new(header)?;
+        let raw = unsafe { curl_sys::curl_slist_append(self.raw, header.as_ptr()) };
+
+        if raw.is_null() {
+            return Err(curl::Error::new(curl_sys::CURLE_OUT_OF_MEMORY));
+        }
+
+        self.raw = raw;
+
+        Ok(())
+    }
+
+    /// Set the headers to send for the given easy handle. The list is moved
+    /// into the handle's handler so that it lives as long as the handle does.
+    pub(crate) fn set_opt(self, easy: &mut Easy2<RequestHandler>) -> Result<(), curl::Error> {
+        let raw = self.raw;
+        easy.get_mut().request_headers = Some(self);
+
+        unsafe {
+            match curl_sys::curl_easy_setopt(easy.raw(), curl_sys::CURLOPT_HTTPHEADER, raw) {
+                curl_sys::CURLE_OK => Ok(()),
+                code => Err(curl::Error::new(code)),
+            }
+        }
+    }
+}
+
+impl Drop for HeaderList {
+    fn drop(&mut self) {
+        unsafe {
+            curl_sys::curl_slist_free_all(self.raw);
+        }
+    }
+}
+
 /// Wrapper around a pipe reader that returns an error that tracks transfer
 /// cancellation.
 pub(crate) struct ResponseBodyReader {
@@ -697,6 +1313,10 @@ impl AsyncRead for ResponseBodyReader {
                 // The transfer did not finish properly at all, so return an error.
                 None => Poll::Ready(Err(io::ErrorKind::ConnectionAborted.into())),
             },
+            Poll::Ready(Ok(len)) => {
+                self.shared.response_body_consumed(len);
+                Poll::Ready(Ok(len))
+            }
             poll => poll,
         }
     }
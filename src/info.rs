@@ -1,5 +1,6 @@
 //! Runtime support for checking versions and feature availability.
 
+use crate::config::Encoding;
 use once_cell::sync::Lazy;
 
 // Query for curl version info just once since it is immutable.
@@ -51,6 +52,22 @@ pub fn is_http_version_supported(version: http::Version) -> bool {
     }
 }
 
+/// Check if runtime support is available for decoding response bodies using
+/// the given encoding.
+///
+/// Like HTTP versions, support for each encoding depends on how curl was built.
+/// Gzip and deflate are almost always available, while Brotli and Zstandard
+/// are usually only available when linking to a system curl library built with
+/// support for them. Encodings that are not available are never advertised to
+/// servers when automatic decompression is enabled.
+pub fn is_encoding_supported(encoding: Encoding) -> bool {
+    match encoding {
+        Encoding::Gzip | Encoding::Deflate => CURL_VERSION.feature_libz(),
+        Encoding::Brotli => CURL_VERSION.feature_brotli(),
+        Encoding::Zstd => CURL_VERSION.feature_zstd(),
+    }
+}
+
 fn curl_version() -> (u8, u8, u8) {
     let bits = CURL_VERSION.version_num();
 
@@ -86,4 +103,10 @@ mod tests {
             assert!(is_http_version_supported(http::Version::HTTP_2));
         }
     }
+
+    #[test]
+    fn gzip_and_deflate_always_supported() {
+        assert!(is_encoding_supported(Encoding::Gzip));
+        assert!(is_encoding_supported(Encoding::Deflate));
+    }
 }
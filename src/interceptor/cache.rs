@@ -0,0 +1,525 @@
+use super::{Context, Interceptor, InterceptorFuture};
+use crate::{body::AsyncBody, error::Error, response::AsyncReadResponseExt};
+use http::{header, HeaderMap, HeaderName, HeaderValue, Method, Request, Response, StatusCode};
+use std::{
+    collections::HashMap,
+    fmt,
+    sync::{Arc, Mutex},
+    time::{Duration, SystemTime},
+};
+
+/// A response stored in a [`CacheStore`].
+#[derive(Clone, Debug)]
+pub struct CachedResponse {
+    status: StatusCode,
+    headers: HeaderMap,
+    body: Vec<u8>,
+    stored_at: SystemTime,
+    vary: Vec<(HeaderName, Option<HeaderValue>)>,
+}
+
+impl CachedResponse {
+    /// Get the status code of the stored response.
+    pub fn status(&self) -> StatusCode {
+        self.status
+    }
+
+    /// Get the headers of the stored response.
+    pub fn headers(&self) -> &HeaderMap {
+        &self.headers
+    }
+
+    /// Get the body of the stored response.
+    pub fn body(&self) -> &[u8] {
+        &self.body
+    }
+
+    /// Get the time at which the response was received or last revalidated.
+    pub fn stored_at(&self) -> SystemTime {
+        self.stored_at
+    }
+
+    /// Check whether the request headers named by the response's `Vary`
+    /// header match the ones of the request that produced this response.
+    fn matches(&self, request_headers: &HeaderMap) -> bool {
+        self.vary
+            .iter()
+            .all(|(name, value)| request_headers.get(name) == value.as_ref())
+    }
+
+    /// Get how long this response has been stored for, including any age
+    /// reported by the server when it was received.
+    fn age(&self, now: SystemTime) -> Duration {
+        let initial_age = self
+            .headers
+            .get(header::AGE)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.trim().parse().ok())
+            .map(Duration::from_secs)
+            .unwrap_or_default();
+
+        now.duration_since(self.stored_at).unwrap_or_default() + initial_age
+    }
+
+    /// Get how long this response can be served without revalidation.
+    fn freshness_lifetime(&self) -> Duration {
+        let directives = CacheControl::from_headers(&self.headers);
+
+        if directives.no_cache {
+            return Duration::from_secs(0);
+        }
+
+        if let Some(max_age) = directives.max_age {
+            return max_age;
+        }
+
+        let expires = match parse_date(&self.headers, header::EXPIRES) {
+            Some(expires) => expires,
+            None => return Duration::from_secs(0),
+        };
+
+        let date = parse_date(&self.headers, header::DATE).unwrap_or(self.stored_at);
+
+        expires.duration_since(date).unwrap_or_default()
+    }
+
+    fn is_fresh(&self, now: SystemTime) -> bool {
+        self.age(now) < self.freshness_lifetime()
+    }
+
+    fn has_validators(&self) -> bool {
+        self.headers.contains_key(header::ETAG) || self.headers.contains_key(header::LAST_MODIFIED)
+    }
+
+    fn to_response(&self, now: SystemTime) -> Response<AsyncBody> {
+        let mut response = Response::new(AsyncBody::from(self.body.clone()));
+
+        *response.status_mut() = self.status;
+        *response.headers_mut() = self.headers.clone();
+        response
+            .headers_mut()
+            .insert(header::AGE, HeaderValue::from(self.age(now).as_secs()));
+
+        response
+    }
+}
+
+/// A storage backend for responses cached by [`Cache`].
+///
+/// Entries are keyed by the request URI. Implementations must be safe to use
+/// from multiple requests at once.
+pub trait CacheStore: Send + Sync {
+    /// Get the response stored for the given key, if any.
+    fn get(&self, key: &str) -> Option<CachedResponse>;
+
+    /// Store a response under the given key, replacing any existing entry.
+    fn put(&self, key: &str, response: CachedResponse);
+
+    /// Remove the response stored for the given key, if any.
+    fn remove(&self, key: &str);
+}
+
+/// A [`CacheStore`] that keeps responses in memory. This is the default store
+/// used by [`Cache`].
+///
+/// Entries are never evicted, so this store is best suited for talking to a
+/// bounded set of URIs.
+#[derive(Debug, Default)]
+pub struct MemoryCacheStore {
+    entries: Mutex<HashMap<String, CachedResponse>>,
+}
+
+impl MemoryCacheStore {
+    /// Create a new, empty store.
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl CacheStore for MemoryCacheStore {
+    fn get(&self, key: &str) -> Option<CachedResponse> {
+        self.entries.lock().unwrap().get(key).cloned()
+    }
+
+    fn put(&self, key: &str, response: CachedResponse) {
+        self.entries
+            .lock()
+            .unwrap()
+            .insert(key.to_owned(), response);
+    }
+
+    fn remove(&self, key: &str) {
+        self.entries.lock().unwrap().remove(key);
+    }
+}
+
+/// An interceptor that caches responses to `GET` requests as a private
+/// (single user) HTTP cache.
+///
+/// Successful responses are stored if they carry freshness information
+/// (`Cache-Control: max-age` or `Expires`) or a validator (`ETag` or
+/// `Last-Modified`), unless either the request or the response contains
+/// `Cache-Control: no-store`. Responses with `Vary: *` are never stored, and
+/// for other `Vary` headers a stored response is only used for requests with
+/// matching header values.
+///
+/// While a stored response is fresh it is returned without contacting the
+/// server. Once it becomes stale, or if the request contains
+/// `Cache-Control: no-cache`, the request is sent with `If-None-Match` and
+/// `If-Modified-Since` headers so that the server can confirm the stored
+/// response is still valid with a `304 Not Modified`, in which case the stored
+/// body is served.
+///
+/// Response bodies are buffered in memory in order to be stored, so this cache
+/// is not suitable for large downloads.
+///
+/// # Examples
+///
+/// ```no_run
+/// use isahc::{interceptor::Cache, prelude::*, HttpClient};
+///
+/// let client = HttpClient::builder()
+///     .interceptor(Cache::new())
+///     .build()?;
+///
+/// // The second request is answered from the cache, or revalidated if the
+/// // first response is already stale.
+/// client.get("https://example.org")?;
+/// client.get("https://example.org")?;
+/// # Ok::<(), isahc::Error>(())
+/// ```
+pub struct Cache {
+    store: Arc<dyn CacheStore>,
+}
+
+impl Cache {
+    /// Create a new cache that stores responses in memory.
+    pub fn new() -> Self {
+        Self::with_store(MemoryCacheStore::new())
+    }
+
+    /// Create a new cache using the given storage backend.
+    pub fn with_store(store: impl CacheStore + 'static) -> Self {
+        Self {
+            store: Arc::new(store),
+        }
+    }
+}
+
+impl Default for Cache {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl fmt::Debug for Cache {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Cache").finish()
+    }
+}
+
+impl Interceptor for Cache {
+    type Err = Error;
+
+    fn intercept<'a>(
+        &'a self,
+        mut request: Request<AsyncBody>,
+        ctx: Context<'a>,
+    ) -> InterceptorFuture<'a, Self::Err> {
+        Box::pin(async move {
+            if request.method() != Method::GET {
+                return ctx.send(request).await;
+            }
+
+            let request_directives = CacheControl::from_headers(request.headers());
+
+            if request_directives.no_store {
+                return ctx.send(request).await;
+            }
+
+            let key = request.uri().to_string();
+            let request_headers = request.headers().clone();
+            let cached = self
+                .store
+                .get(&key)
+                .filter(|cached| cached.matches(&request_headers));
+
+            if let Some(cached) = cached.as_ref() {
+                if !request_directives.no_cache && cached.is_fresh(SystemTime::now()) {
+                    tracing::debug!("serving fresh response from cache");
+                    return Ok(cached.to_response(SystemTime::now()));
+                }
+
+                // Ask the server to revalidate the stored response, unless the
+                // caller is already making a conditional request of their own.
+                if !request.headers().contains_key(header::IF_NONE_MATCH)
+                    && !request.headers().contains_key(header::IF_MODIFIED_SINCE)
+                {
+                    if let Some(etag) = cached.headers.get(header::ETAG) {
+                        request
+                            .headers_mut()
+                            .insert(header::IF_NONE_MATCH, etag.clone());
+                    }
+
+                    if let Some(last_modified) = cached.headers.get(header::LAST_MODIFIED) {
+                        request
+                            .headers_mut()
+                            .insert(header::IF_MODIFIED_SINCE, last_modified.clone());
+                    }
+                }
+            }
+
+            let mut response = ctx.send(request).await?;
+
+            if let Some(mut cached) = cached {
+                if response.status() == StatusCode::NOT_MODIFIED {
+                    tracing::debug!("stored response revalidated by server");
+
+                    // Read the rest of the response so that the connection
+                    // can be reused.
+                    let _ = response.consume().await;
+
+                    // Update the stored headers with any sent along with the
+                    // 304, as described in RFC 7234, section 4.3.4.
+                    for name in response.headers().keys() {
+                        if name == header::CONTENT_LENGTH {
+                            continue;
+                        }
+
+                        cached.headers.remove(name);
+
+                        for value in response.headers().get_all(name) {
+                            cached.headers.append(name, value.clone());
+                        }
+                    }
+
+                    cached.headers.remove(header::AGE);
+                    cached.stored_at = SystemTime::now();
+                    self.store.put(&key, cached.clone());
+
+                    return Ok(cached.to_response(SystemTime::now()));
+                }
+            }
+
+            if response.status() != StatusCode::OK {
+                return Ok(response);
+            }
+
+            let vary = match vary_headers(response.headers(), &request_headers) {
+                Some(vary) => vary,
+                None => return Ok(response),
+            };
+
+            if CacheControl::from_headers(response.headers()).no_store {
+                self.store.remove(&key);
+                return Ok(response);
+            }
+
+            let (parts, mut body) = response.into_parts();
+            let mut cached = CachedResponse {
+                status: parts.status,
+                headers: parts.headers.clone(),
+                body: Vec::new(),
+                stored_at: SystemTime::now(),
+                vary,
+            };
+
+            if !cached.has_validators() && cached.freshness_lifetime() == Duration::from_secs(0) {
+                return Ok(Response::from_parts(parts, body));
+            }
+
+            cached.body = {
+                let mut buf = Vec::new();
+                futures_lite::io::AsyncReadExt::read_to_end(&mut body, &mut buf).await?;
+                buf
+            };
+
+            self.store.put(&key, cached.clone());
+
+            Ok(Response::from_parts(parts, AsyncBody::from(cached.body)))
+        })
+    }
+}
+
+/// The subset of `Cache-Control` directives understood by [`Cache`].
+#[derive(Debug, Default, PartialEq)]
+struct CacheControl {
+    no_store: bool,
+    no_cache: bool,
+    max_age: Option<Duration>,
+}
+
+impl CacheControl {
+    fn from_headers(headers: &HeaderMap) -> Self {
+        let mut directives = Self::default();
+
+        for value in headers.get_all(header::CACHE_CONTROL) {
+            let value = match value.to_str() {
+                Ok(value) => value,
+                Err(_) => continue,
+            };
+
+            for directive in value.split(',') {
+                let directive = directive.trim();
+                let (name, argument) = match directive.find('=') {
+                    Some(index) => (&directive[..index], Some(&directive[index + 1..])),
+                    None => (directive, None),
+                };
+
+                if name.eq_ignore_ascii_case("no-store") {
+                    directives.no_store = true;
+                } else if name.eq_ignore_ascii_case("no-cache") {
+                    directives.no_cache = true;
+                } else if name.eq_ignore_ascii_case("max-age") {
+                    directives.max_age = argument
+                        .and_then(|seconds| seconds.trim_matches('"').parse().ok())
+                        .map(Duration::from_secs);
+                }
+            }
+        }
+
+        directives
+    }
+}
+
+/// Get the request headers named by the response's `Vary` header, along with
+/// their values in the request. Returns `None` if the response varies on
+/// something other than request headers and cannot be cached.
+fn vary_headers(
+    response_headers: &HeaderMap,
+    request_headers: &HeaderMap,
+) -> Option<Vec<(HeaderName, Option<HeaderValue>)>> {
+    let mut vary = Vec::new();
+
+    for value in response_headers.get_all(header::VARY) {
+        for name in value.to_str().ok()?.split(',') {
+            let name = name.trim();
+
+            if name.is_empty() {
+                continue;
+            }
+
+            if name == "*" {
+                return None;
+            }
+
+            let name = HeaderName::from_bytes(name.as_bytes()).ok()?;
+            let value = request_headers.get(&name).cloned();
+
+            vary.push((name, value));
+        }
+    }
+
+    Some(vary)
+}
+
+fn parse_date(headers: &HeaderMap, name: HeaderName) -> Option<SystemTime> {
+    headers
+        .get(name)?
+        .to_str()
+        .ok()
+        .and_then(|value| httpdate::parse_http_date(value).ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+
+    static_assertions::assert_impl_all!(Cache: Send, Sync);
+
+    fn cached(headers: &[(&'static str, &'static str)]) -> CachedResponse {
+        let mut map = HeaderMap::new();
+
+        for (name, value) in headers {
+            map.append(*name, HeaderValue::from_static(value));
+        }
+
+        CachedResponse {
+            status: StatusCode::OK,
+            headers: map,
+            body: Vec::new(),
+            stored_at: SystemTime::now(),
+            vary: Vec::new(),
+        }
+    }
+
+    #[test_case("max-age=60", false, false, Some(60))]
+    #[test_case("no-store", true, false, None)]
+    #[test_case("No-Cache, max-age=\"5\"", false, true, Some(5))]
+    #[test_case("private, max-age=bogus", false, false, None)]
+    fn parse_cache_control(value: &str, no_store: bool, no_cache: bool, max_age: Option<u64>) {
+        let mut headers = HeaderMap::new();
+        headers.insert(header::CACHE_CONTROL, value.parse().unwrap());
+
+        assert_eq!(
+            CacheControl::from_headers(&headers),
+            CacheControl {
+                no_store,
+                no_cache,
+                max_age: max_age.map(Duration::from_secs),
+            }
+        );
+    }
+
+    #[test]
+    fn max_age_freshness() {
+        let response = cached(&[("cache-control", "max-age=60")]);
+
+        assert!(response.is_fresh(SystemTime::now()));
+        assert!(!response.is_fresh(SystemTime::now() + Duration::from_secs(61)));
+    }
+
+    #[test]
+    fn age_header_counts_towards_age() {
+        let response = cached(&[("cache-control", "max-age=60"), ("age", "59")]);
+
+        assert!(!response.is_fresh(SystemTime::now() + Duration::from_secs(2)));
+    }
+
+    #[test]
+    fn expires_relative_to_date() {
+        let response = cached(&[
+            ("date", "Sun, 06 Nov 1994 08:49:37 GMT"),
+            ("expires", "Sun, 06 Nov 1994 08:50:37 GMT"),
+        ]);
+
+        assert_eq!(response.freshness_lifetime(), Duration::from_secs(60));
+    }
+
+    #[test]
+    fn no_cache_is_never_fresh() {
+        let response = cached(&[("cache-control", "no-cache, max-age=60")]);
+
+        assert!(!response.is_fresh(SystemTime::now()));
+    }
+
+    #[test]
+    fn vary_star_is_not_cacheable() {
+        let mut response_headers = HeaderMap::new();
+        response_headers.insert(header::VARY, HeaderValue::from_static("*"));
+
+        assert!(vary_headers(&response_headers, &HeaderMap::new()).is_none());
+    }
+
+    #[test]
+    fn vary_matches_request_headers() {
+        let mut response_headers = HeaderMap::new();
+        response_headers.insert(
+            header::VARY,
+            HeaderValue::from_static("Accept, Accept-Language"),
+        );
+
+        let mut request_headers = HeaderMap::new();
+        request_headers.insert(header::ACCEPT, HeaderValue::from_static("text/plain"));
+
+        let mut response = cached(&[]);
+        response.vary = vary_headers(&response_headers, &request_headers).unwrap();
+
+        assert!(response.matches(&request_headers));
+
+        request_headers.insert(header::ACCEPT, HeaderValue::from_static("text/html"));
+
+        assert!(!response.matches(&request_headers));
+    }
+}
@@ -4,6 +4,59 @@
 //! with interceptors. Interceptors are handlers that augment HTTP client
 //! functionality by decorating HTTP calls with custom logic.
 //!
+//! Interceptors are asynchronous and fallible. An interceptor may await other
+//! futures both before and after passing the request along (to refresh an
+//! access token or sign the request, for example), and may reject a request by
+//! returning an error instead of calling [`Context::send`]. Errors of a custom
+//! type are returned to the caller with
+//! [`ErrorKind::Unknown`](crate::error::ErrorKind::Unknown) as their kind, and
+//! can be retrieved from [`Error::source`].
+//!
+//! ```no_run
+//! use isahc::{
+//!     error::ErrorKind,
+//!     interceptor::{Context, Interceptor, InterceptorFuture},
+//!     AsyncBody,
+//!     HttpClient,
+//! };
+//! use isahc::http::{HeaderValue, Request};
+//!
+//! /// Refuses to send requests over plain HTTP, and adds a signature header
+//! /// to all other requests.
+//! struct SignRequests;
+//!
+//! async fn sign(request: &Request<AsyncBody>) -> HeaderValue {
+//!     // Talk to a remote signing service...
+//!     # HeaderValue::from_static("")
+//! }
+//!
+//! impl Interceptor for SignRequests {
+//!     type Err = isahc::Error;
+//!
+//!     fn intercept<'a>(
+//!         &'a self,
+//!         mut request: Request<AsyncBody>,
+//!         ctx: Context<'a>,
+//!     ) -> InterceptorFuture<'a, Self::Err> {
+//!         Box::pin(async move {
+//!             if request.uri().scheme_str() != Some("https") {
+//!                 return Err(ErrorKind::InvalidRequest.into());
+//!             }
+//!
+//!             let signature = sign(&request).await;
+//!             request.headers_mut().insert("x-signature", signature);
+//!
+//!             ctx.send(request).await
+//!         })
+//!     }
+//! }
+//!
+//! let client = HttpClient::builder()
+//!     .interceptor(SignRequests)
+//!     .build()?;
+//! # Ok::<(), isahc::Error>(())
+//! ```
+//!
 //! Known issues:
 //!
 //! - [`from_fn`] doesn't work as desired. The trait bounds are too ambiguous
@@ -11,11 +64,8 @@
 //!   generic over a lifetime, there's no way to give the return type the
 //!   correct name using current Rust syntax.
 //! - [`InterceptorObj`] wraps the returned future in an extra box.
-//! - If an interceptor returns a custom error, it is stringified and wrapped in
-//!   `Error::Curl`. We should introduce a new error variant that boxes the
-//!   error and also records the type of the interceptor that created the error
-//!   for visibility. But we can't add a new variant right now without a BC
-//!   break. See [#182](https://github.com/sagebind/isahc/issues/182).
+//! - Custom errors returned by an interceptor do not record which interceptor
+//!   produced them. See [#182](https://github.com/sagebind/isahc/issues/182).
 //! - Automatic redirect following currently bypasses interceptors for
 //!   subsequent requests. This will be fixed when redirect handling is
 //!   rewritten as an interceptor itself. See
@@ -30,10 +80,18 @@ use crate::body::AsyncBody;
 use http::{Request, Response};
 use std::{error::Error, fmt, future::Future, pin::Pin};
 
+#[cfg(feature = "unstable-interceptors")]
+mod cache;
 mod context;
+#[cfg(feature = "unstable-interceptors")]
+mod logger;
 mod obj;
 
+#[cfg(feature = "unstable-interceptors")]
+pub use self::cache::{Cache, CacheStore, CachedResponse, MemoryCacheStore};
 pub use self::context::Context;
+#[cfg(feature = "unstable-interceptors")]
+pub use self::logger::Logger;
 pub(crate) use self::{context::Invoke, obj::InterceptorObj};
 
 type InterceptorResult<E> = Result<Response<AsyncBody>, E>;
@@ -61,6 +119,48 @@ macro_rules! interceptor {
 /// Since clients may be used to send requests concurrently, all interceptors
 /// must be synchronized and must be able to account for multiple requests being
 /// made in parallel.
+///
+/// An interceptor is not required to call [`Context::send`]. Returning a
+/// response of its own instead short-circuits the request: no connection is
+/// made, and the response is returned to the caller as if it had been received
+/// from the server. This is useful for serving responses from a cache or for
+/// substituting test doubles.
+///
+/// ```
+/// use isahc::{
+///     interceptor::{Context, Interceptor, InterceptorFuture},
+///     prelude::*,
+///     AsyncBody,
+///     HttpClient,
+/// };
+/// use isahc::http::{Request, Response};
+///
+/// /// Answers every request without touching the network.
+/// struct Offline;
+///
+/// impl Interceptor for Offline {
+///     type Err = isahc::Error;
+///
+///     fn intercept<'a>(
+///         &'a self,
+///         _request: Request<AsyncBody>,
+///         _ctx: Context<'a>,
+///     ) -> InterceptorFuture<'a, Self::Err> {
+///         Box::pin(async move {
+///             Ok(Response::builder()
+///                 .status(503)
+///                 .body(AsyncBody::from("offline"))?)
+///         })
+///     }
+/// }
+///
+/// let client = HttpClient::builder().interceptor(Offline).build()?;
+/// let mut response = client.get("http://example.org")?;
+///
+/// assert_eq!(response.status(), 503);
+/// assert_eq!(response.text()?, "offline");
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
 pub trait Interceptor: Send + Sync {
     /// The type of error returned by this interceptor.
     type Err: Error + Send + Sync + 'static;
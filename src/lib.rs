@@ -10,7 +10,8 @@
 //! - Fully asynchronous core, with incremental reading and writing of request
 //!   and response bodies and connection multiplexing.
 //! - Sessions and cookie persistence.
-//! - Automatic request cancellation on drop.
+//! - Automatic request cancellation on drop, or explicitly using a
+//!   cancellation token.
 //!
 //! # Getting started
 //!
@@ -94,6 +95,7 @@
 //!
 //! - [`Configurable`](config::Configurable): Configure request parameters.
 //! - [`RequestExt`]: Manipulate and send requests.
+//! - [`RequestBuilderExt`]: Build requests with specially encoded bodies.
 //! - [`ResponseExt`]: Get information about the corresponding request or
 //!   response statistics.
 //! - [`ReadResponseExt`]: Consume a response body in a variety of ways.
@@ -150,10 +152,21 @@
 //!
 //! Below is a list of all available feature flags and their meanings.
 //!
+//! ## `bytes`
+//!
+//! Enable reading bodies into `Bytes` buffers from the
+//! [bytes](https://docs.rs/bytes) crate using `Body::into_bytes` and
+//! `AsyncBody::into_bytes`. Disabled by default.
+//!
 //! ## `cookies`
 //!
 //! Enable persistent HTTP cookie support. Disabled by default.
 //!
+//! ## `form`
+//!
+//! Additional serialization of `application/x-www-form-urlencoded` request
+//! bodies via [serde](https://serde.rs). Disabled by default.
+//!
 //! ## `http2`
 //!
 //! Enable compile-time support for HTTP/2 in libcurl via libnghttp2. This does
@@ -169,13 +182,20 @@
 //! ## `json`
 //!
 //! Additional serialization and deserialization of JSON bodies via
-//! [serde](https://serde.rs). Disabled by default.
+//! [serde](https://serde.rs), including streaming newline-delimited JSON
+//! response bodies via the `ndjson` module. Disabled by default.
 //!
 //! ## `psl`
 //!
 //! Enable use of the Public Suffix List to filter out potentially malicious
 //! cross-domain cookies. Implies `cookies`, disabled by default.
 //!
+//! ## `request-compression`
+//!
+//! Enable support for compressing request bodies before sending them, using
+//! [`Configurable::request_body_compression`](config::Configurable::request_body_compression).
+//! Disabled by default.
+//!
 //! ## `spnego`
 //!
 //! Enable support for [SPNEGO-based HTTP
@@ -185,10 +205,25 @@
 //! Kerberos](https://web.mit.edu/kerberos/) headers must be pre-installed at
 //! compile time.
 //!
+//! ## `sse`
+//!
+//! Enable decoding of [server-sent
+//! events](https://html.spec.whatwg.org/multipage/server-sent-events.html)
+//! from `text/event-stream` response bodies via the `sse` module. Disabled
+//! by default.
+//!
 //! ## `static-curl`
 //!
 //! Use a bundled libcurl version and statically link to it. Enabled by default.
 //!
+//! On Unix, this also makes details about the TLS session used for a response
+//! available using [`ResponseExt::tls_info`].
+//!
+//! ## `testing`
+//!
+//! Enable the `testing` module, which provides utilities for testing code that
+//! uses Isahc without sending requests over the network. Disabled by default.
+//!
 //! ## `text-decoding`
 //!
 //! Enable support for decoding text-based responses in various charsets into
@@ -213,10 +248,28 @@
 //! track log events grouped by individual requests. This can be especially
 //! useful if you are sending multiple requests concurrently.
 //!
+//! Each request is wrapped in a span with the request method and URI, which
+//! stays entered while the response future is polled so that any spans of
+//! your own nest beneath it. Within it, a `handler` span covers the transfer
+//! performed by curl, and records the response status and the number of body
+//! bytes sent and received. Once a transfer completes, an event is logged with
+//! the time spent on name resolution, connecting, the TLS handshake, and the
+//! transfer as a whole.
+//!
+//! There is no separate `tracing` feature to enable any of this. Isahc always
+//! instruments requests with the tracing crate, which also forwards every
+//! event to the log crate, so the same diagnostics reach whichever of the two
+//! your application uses. When no subscriber or logger is listening, spans
+//! and events are disabled with a cheap check and cost next to nothing.
+//!
 //! If you set the log level to `Trace` for the `isahc::wire` target, Isahc will
 //! also log all incoming and outgoing data while in flight. This may come in
 //! handy if you are debugging code and need to see the exact data being sent to
 //! the server and being received.
+//!
+//! With the `unstable-interceptors` feature enabled, the `Logger` interceptor
+//! can be added to a client to log a one-line summary of each request and
+//! response under the `isahc::logger` target, with credentials redacted.
 
 #![doc(
     html_logo_url = "https://raw.githubusercontent.com/sagebind/isahc/master/media/isahc.svg.png",
@@ -245,9 +298,21 @@ mod macros;
 #[cfg(feature = "cookies")]
 pub mod cookies;
 
+#[cfg(feature = "json")]
+pub mod ndjson;
+
+#[cfg(feature = "sse")]
+pub mod sse;
+
+#[cfg(feature = "testing")]
+pub mod testing;
+
 mod agent;
 mod body;
+mod cancel;
 mod client;
+#[cfg(feature = "request-compression")]
+mod compression;
 mod default_headers;
 mod handler;
 mod headers;
@@ -257,13 +322,17 @@ mod parsing;
 mod redirect;
 mod request;
 mod response;
+mod retry;
+mod summary;
 mod task;
 mod text;
+mod tls;
 mod trailer;
 
 pub mod auth;
 pub mod config;
 pub mod error;
+pub mod multipart;
 
 #[cfg(feature = "unstable-interceptors")]
 pub mod interceptor;
@@ -273,13 +342,17 @@ pub(crate) mod interceptor;
 
 pub use crate::{
     body::{AsyncBody, Body},
-    client::{HttpClient, HttpClientBuilder, ResponseFuture},
+    cancel::CancellationToken,
+    client::{HttpClient, HttpClientBuilder, PreconnectFuture, ResponseFuture},
     error::Error,
+    handler::RequestHandler,
     http::{request::Request, response::Response},
     info::*,
     metrics::Metrics,
-    request::RequestExt,
+    request::{RequestBuilderExt, RequestExt},
     response::{AsyncReadResponseExt, ReadResponseExt, ResponseExt},
+    summary::TransferSummary,
+    tls::TlsInfo,
     trailer::Trailer,
 };
 
@@ -303,6 +376,7 @@ pub mod prelude {
         config::Configurable,
         AsyncReadResponseExt,
         ReadResponseExt,
+        RequestBuilderExt,
         RequestExt,
         ResponseExt,
     };
@@ -24,6 +24,8 @@ pub(crate) struct Inner {
     pub(crate) upload_speed: AtomicCell<f64>,
     pub(crate) download_speed: AtomicCell<f64>,
 
+    pub(crate) queue_time: AtomicCell<f64>,
+
     // An overview of the six time values (taken from the curl documentation):
     //
     // curl_easy_perform()
@@ -80,6 +82,18 @@ impl Metrics {
         self.inner.download_speed.load()
     }
 
+    /// Get the amount of time the request spent waiting to be started after
+    /// being submitted, such as while waiting for a connection to become
+    /// available when the connection limits of the client have been reached.
+    ///
+    /// Time spent waiting for a connection is also counted towards the other
+    /// times, such as [`Metrics::transfer_start_time`] and
+    /// [`Metrics::total_time`]. Comparing it against them shows whether latency
+    /// is caused by the network or by the connection limits of the client.
+    pub fn queue_time(&self) -> Duration {
+        Duration::from_secs_f64(self.inner.queue_time.load())
+    }
+
     /// Get the total time from the start of the request until DNS name
     /// resolving was completed.
     ///
@@ -158,6 +172,7 @@ impl fmt::Debug for Metrics {
             .field("upload_speed", &self.upload_speed())
             .field("download_progress", &self.download_progress())
             .field("download_speed", &self.download_speed())
+            .field("queue_time", &self.queue_time())
             .field("name_lookup_time", &self.name_lookup_time())
             .field("connect_time", &self.connect_time())
             .field("secure_connect_time", &self.secure_connect_time())
@@ -0,0 +1,441 @@
+//! Types for building `multipart/form-data` request bodies.
+//!
+//! Multipart forms are commonly used to upload files along with other form
+//! fields in a single request, as described in [RFC
+//! 7578](https://tools.ietf.org/html/rfc7578). A [`Form`] is assembled from a
+//! list of named [`Part`]s, and can then be turned into a [`Body`] that streams
+//! each part in turn without buffering the contents of any readers or files in
+//! memory.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use isahc::{multipart::Form, prelude::*, Request};
+//!
+//! let form = Form::new()
+//!     .text("title", "My holiday photos")
+//!     .file("photo", "beach.jpg")?;
+//!
+//! let response = Request::post("https://httpbin.org/post")
+//!     .multipart(form)?
+//!     .send()?;
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+
+use crate::body::Body;
+use std::{
+    borrow::Cow,
+    collections::{hash_map::RandomState, VecDeque},
+    fmt,
+    fs::File,
+    hash::{BuildHasher, Hasher},
+    io::{self, Cursor, Read},
+    path::Path,
+};
+
+/// A `multipart/form-data` form, made up of a list of named parts.
+///
+/// Forms are built up using a builder-style API, and can be converted into a
+/// [`Body`] using [`Form::into_body`] or the [`From`] trait. Since the boundary
+/// used to separate parts must also appear in the `Content-Type` header of the
+/// request, the body remembers the value returned by [`Form::content_type`]
+/// and adds that header when the request is sent, unless the request already
+/// has a `Content-Type`. Use
+/// [`RequestBuilderExt::multipart`](crate::RequestBuilderExt::multipart) to
+/// replace any content type already set on a request builder.
+#[must_use = "builders have no effect if unused"]
+pub struct Form {
+    boundary: String,
+    parts: Vec<(String, Part)>,
+}
+
+impl Form {
+    /// Create a new, empty form with a randomly generated boundary.
+    pub fn new() -> Self {
+        Self {
+            boundary: generate_boundary(),
+            parts: Vec::new(),
+        }
+    }
+
+    /// Get the boundary string used to separate parts of this form.
+    pub fn boundary(&self) -> &str {
+        &self.boundary
+    }
+
+    /// Get the value of the `Content-Type` header that should be sent along
+    /// with this form.
+    pub fn content_type(&self) -> String {
+        format!("multipart/form-data; boundary={}", self.boundary)
+    }
+
+    /// Add a plain text field to the form.
+    pub fn text<N, V>(self, name: N, value: V) -> Self
+    where
+        N: Into<String>,
+        V: Into<String>,
+    {
+        self.part(name, Part::text(value))
+    }
+
+    /// Add a file field to the form, reading the file contents from the given
+    /// path.
+    ///
+    /// The file is opened immediately so that any errors are reported up
+    /// front, but its contents are not read until the request body is sent.
+    /// The file name reported to the server is taken from the last component
+    /// of the path.
+    pub fn file<N, P>(self, name: N, path: P) -> io::Result<Self>
+    where
+        N: Into<String>,
+        P: AsRef<Path>,
+    {
+        Ok(self.part(name, Part::file(path)?))
+    }
+
+    /// Add an arbitrary part to the form.
+    pub fn part<N>(mut self, name: N, part: Part) -> Self
+    where
+        N: Into<String>,
+    {
+        self.parts.push((name.into(), part));
+        self
+    }
+
+    /// Convert this form into a request body.
+    ///
+    /// The body will have a known length if the length of every part in the
+    /// form is known, otherwise the body will have an unknown length. When
+    /// sent, the `Content-Type` header returned by [`Form::content_type`] is
+    /// added to the request unless it already has one.
+    pub fn into_body(self) -> Body {
+        let content_type = self.content_type();
+        let boundary = self.boundary;
+        let mut len = Some(0);
+        let mut segments: Vec<Box<dyn Read + Send + Sync>> = Vec::new();
+
+        for (name, part) in self.parts {
+            let header = part.header(&boundary, &name);
+
+            len = len.and_then(|len: u64| {
+                part.len
+                    .map(|part_len| len + header.len() as u64 + part_len + 2)
+            });
+
+            segments.push(Box::new(Cursor::new(header)));
+            segments.push(part.reader);
+            segments.push(Box::new(&b"\r\n"[..]));
+        }
+
+        let footer = format!("--{}--\r\n", boundary).into_bytes();
+        len = len.map(|len| len + footer.len() as u64);
+        segments.push(Box::new(Cursor::new(footer)));
+
+        let reader = Segments {
+            segments: segments.into_iter().collect(),
+        };
+
+        let body = match len {
+            Some(len) => Body::from_reader_sized(reader, len),
+            None => Body::from_reader(reader),
+        };
+
+        body.with_content_type(Some(content_type.into()))
+    }
+}
+
+impl Default for Form {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl From<Form> for Body {
+    fn from(form: Form) -> Self {
+        form.into_body()
+    }
+}
+
+impl fmt::Debug for Form {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Form")
+            .field("boundary", &self.boundary)
+            .field("parts", &self.parts)
+            .finish()
+    }
+}
+
+/// A single field in a multipart [`Form`].
+#[must_use = "builders have no effect if unused"]
+pub struct Part {
+    reader: Box<dyn Read + Send + Sync>,
+    len: Option<u64>,
+    file_name: Option<String>,
+    content_type: Option<String>,
+}
+
+impl Part {
+    /// Create a part containing plain text.
+    pub fn text<V>(value: V) -> Self
+    where
+        V: Into<String>,
+    {
+        Self::bytes(value.into().into_bytes())
+    }
+
+    /// Create a part containing the given bytes.
+    pub fn bytes<B>(bytes: B) -> Self
+    where
+        B: Into<Cow<'static, [u8]>>,
+    {
+        let bytes = bytes.into();
+        let len = bytes.len() as u64;
+
+        Self::new(Box::new(Cursor::new(bytes)), Some(len))
+    }
+
+    /// Create a part that streams its contents from the given reader.
+    ///
+    /// The part will have an unknown length, which means that the entire form
+    /// will also have an unknown length.
+    pub fn stream<R>(reader: R) -> Self
+    where
+        R: Read + Send + Sync + 'static,
+    {
+        Self::new(Box::new(reader), None)
+    }
+
+    /// Create a part that streams its contents from the given reader, with a
+    /// known length.
+    ///
+    /// Giving a value for `length` that doesn't actually match how much data
+    /// the reader will produce may result in errors when sending the form in a
+    /// request.
+    pub fn stream_sized<R>(reader: R, length: u64) -> Self
+    where
+        R: Read + Send + Sync + 'static,
+    {
+        Self::new(Box::new(reader), Some(length))
+    }
+
+    /// Create a part that streams its contents from the file at the given
+    /// path.
+    ///
+    /// The file name of the part is set to the last component of the path, and
+    /// the content type is set to `application/octet-stream`.
+    pub fn file<P>(path: P) -> io::Result<Self>
+    where
+        P: AsRef<Path>,
+    {
+        let path = path.as_ref();
+        let file = File::open(path)?;
+        let len = file.metadata()?.len();
+        let mut part = Self::stream_sized(file, len).content_type("application/octet-stream");
+
+        if let Some(file_name) = path.file_name() {
+            part = part.file_name(file_name.to_string_lossy());
+        }
+
+        Ok(part)
+    }
+
+    /// Set the file name of this part.
+    pub fn file_name<S>(mut self, file_name: S) -> Self
+    where
+        S: Into<String>,
+    {
+        self.file_name = Some(file_name.into());
+        self
+    }
+
+    /// Set the content type of this part.
+    pub fn content_type<S>(mut self, content_type: S) -> Self
+    where
+        S: Into<String>,
+    {
+        self.content_type = Some(content_type.into());
+        self
+    }
+
+    fn new(reader: Box<dyn Read + Send + Sync>, len: Option<u64>) -> Self {
+        Self {
+            reader,
+            len,
+            file_name: None,
+            content_type: None,
+        }
+    }
+
+    /// Render the boundary delimiter and headers that precede the contents of
+    /// this part.
+    fn header(&self, boundary: &str, name: &str) -> Vec<u8> {
+        let mut header = format!(
+            "--{}\r\nContent-Disposition: form-data; name=\"{}\"",
+            boundary,
+            escape_quoted(name)
+        );
+
+        if let Some(file_name) = self.file_name.as_ref() {
+            header.push_str("; filename=\"");
+            header.push_str(&escape_quoted(file_name));
+            header.push('"');
+        }
+
+        if let Some(content_type) = self.content_type.as_ref() {
+            header.push_str("\r\nContent-Type: ");
+            header.push_str(content_type);
+        }
+
+        header.push_str("\r\n\r\n");
+        header.into_bytes()
+    }
+}
+
+impl fmt::Debug for Part {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Part")
+            .field("len", &self.len)
+            .field("file_name", &self.file_name)
+            .field("content_type", &self.content_type)
+            .finish()
+    }
+}
+
+/// Reader which reads from a list of readers one after the other.
+struct Segments {
+    segments: VecDeque<Box<dyn Read + Send + Sync>>,
+}
+
+impl Read for Segments {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+
+        while let Some(segment) = self.segments.front_mut() {
+            match segment.read(buf)? {
+                0 => {
+                    self.segments.pop_front();
+                }
+                n => return Ok(n),
+            }
+        }
+
+        Ok(0)
+    }
+}
+
+/// Escape a value for use inside a quoted header parameter, as browsers do
+/// for form field names and file names.
+fn escape_quoted(value: &str) -> Cow<'_, str> {
+    if value.contains(&['"', '\r', '\n'][..]) {
+        Cow::Owned(
+            value
+                .replace('"', "%22")
+                .replace('\r', "%0D")
+                .replace('\n', "%0A"),
+        )
+    } else {
+        Cow::Borrowed(value)
+    }
+}
+
+/// Generate a boundary that is extremely unlikely to appear in any of the form
+/// contents.
+fn generate_boundary() -> String {
+    // Each new `RandomState` is seeded differently, which gives us a cheap
+    // source of randomness without an additional dependency.
+    let a = RandomState::new().build_hasher().finish();
+    let b = RandomState::new().build_hasher().finish();
+
+    format!("------------------------{:016x}{:016x}", a, b)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn read_to_string(body: Body) -> String {
+        let mut body = body;
+        let mut string = String::new();
+        body.read_to_string(&mut string).unwrap();
+        string
+    }
+
+    static_assertions::assert_impl_all!(Form: Send, Sync);
+
+    #[test]
+    fn boundaries_are_unique() {
+        assert_ne!(Form::new().boundary(), Form::new().boundary());
+    }
+
+    #[test]
+    fn empty_form() {
+        let form = Form::new();
+        let boundary = form.boundary().to_owned();
+        let body = form.into_body();
+
+        assert_eq!(body.len(), Some(boundary.len() as u64 + 6));
+        assert_eq!(read_to_string(body), format!("--{}--\r\n", boundary));
+    }
+
+    #[test]
+    fn text_and_file_parts() {
+        let form = Form::new().text("foo", "bar").part(
+            "upload",
+            Part::bytes(&b"hello"[..])
+                .file_name("hello.txt")
+                .content_type("text/plain"),
+        );
+        let boundary = form.boundary().to_owned();
+        let body = form.into_body();
+        let len = body.len();
+        let string = read_to_string(body);
+
+        assert_eq!(
+            string,
+            format!(
+                "--{0}\r\n\
+                Content-Disposition: form-data; name=\"foo\"\r\n\
+                \r\n\
+                bar\r\n\
+                --{0}\r\n\
+                Content-Disposition: form-data; name=\"upload\"; filename=\"hello.txt\"\r\n\
+                Content-Type: text/plain\r\n\
+                \r\n\
+                hello\r\n\
+                --{0}--\r\n",
+                boundary
+            )
+        );
+        assert_eq!(len, Some(string.len() as u64));
+    }
+
+    #[test]
+    fn body_carries_content_type() {
+        let form = Form::new();
+        let content_type = form.content_type();
+
+        assert_eq!(
+            form.into_body().into_async().0.content_type(),
+            Some(content_type.as_str())
+        );
+    }
+
+    #[test]
+    fn stream_part_has_unknown_length() {
+        let body = Form::new()
+            .part("foo", Part::stream(io::empty()))
+            .into_body();
+
+        assert_eq!(body.len(), None);
+    }
+
+    #[test]
+    fn names_are_escaped() {
+        let form = Form::new().text("a\"b\r\n", "");
+        let string = read_to_string(form.into_body());
+
+        assert!(string.contains("name=\"a%22b%0D%0A\""));
+    }
+}
@@ -0,0 +1,266 @@
+//! Decoding of [newline-delimited JSON](https://github.com/ndjson/ndjson-spec).
+//!
+//! Some servers stream a long-lived response body made up of one JSON value
+//! per line, a format also known as JSON Lines. This is common for tailing
+//! logs and for streaming analytics APIs. A [`JsonStream`] wraps such a
+//! response body and deserializes each line into a [`Stream`] of values as
+//! they arrive.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use futures_lite::StreamExt;
+//! use isahc::ndjson::JsonStream;
+//! use serde_json::Value;
+//!
+//! # async fn run() -> Result<(), Box<dyn std::error::Error>> {
+//! let response = isahc::get_async("https://example.org/logs").await?;
+//!
+//! let mut values = JsonStream::<_, Value>::new(response.into_body());
+//!
+//! while let Some(value) = values.next().await {
+//!     println!("{}", value?);
+//! }
+//! # Ok(()) }
+//! ```
+//!
+//! # Availability
+//!
+//! This module is only available when the [`json`](../index.html#json)
+//! feature is enabled.
+
+use futures_lite::{io::AsyncRead, Stream};
+use serde::de::DeserializeOwned;
+use std::{
+    fmt,
+    io,
+    marker::PhantomData,
+    mem,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+/// Size of the buffer used to read from the underlying reader.
+const READ_BUFFER_SIZE: usize = 8192;
+
+/// A stream of values deserialized from a newline-delimited JSON body.
+///
+/// A JSON stream can be created from any asynchronous reader, but is usually
+/// created from a response body. Each line is deserialized into a `T` as soon
+/// as it has been received in full, even if it was split across multiple
+/// reads. Blank lines are skipped, and the last line does not need to end
+/// with a newline.
+///
+/// If a line cannot be deserialized, an error of kind
+/// [`InvalidData`](io::ErrorKind::InvalidData) is yielded for that line and
+/// the stream moves on to the next line, unless
+/// [`JsonStream::end_on_error`] is enabled. The stream always ends after
+/// yielding an error reading from the underlying reader.
+pub struct JsonStream<R, T> {
+    reader: R,
+    buffer: Vec<u8>,
+
+    /// Number of bytes at the start of the buffer known not to contain a line
+    /// feed, so that long lines are not searched over and over again.
+    scanned: usize,
+    eof: bool,
+    done: bool,
+    end_on_error: bool,
+    _phantom: PhantomData<fn() -> T>,
+}
+
+impl<R: AsyncRead + Unpin, T: DeserializeOwned> JsonStream<R, T> {
+    /// Create a new JSON stream that reads from the given reader.
+    pub fn new(reader: R) -> Self {
+        Self {
+            reader,
+            buffer: Vec::new(),
+            scanned: 0,
+            eof: false,
+            done: false,
+            end_on_error: false,
+            _phantom: PhantomData,
+        }
+    }
+
+    /// End the stream after yielding the first line that cannot be
+    /// deserialized, instead of moving on to the next line.
+    ///
+    /// This is disabled by default.
+    pub fn end_on_error(mut self, end: bool) -> Self {
+        self.end_on_error = end;
+        self
+    }
+
+    /// Get the underlying reader.
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+
+    /// Remove the next complete line from the buffer, if there is one. Once
+    /// the reader has ended, whatever is left in the buffer is the last line.
+    fn next_line(&mut self) -> Option<Vec<u8>> {
+        let line = match self.buffer[self.scanned..].iter().position(|&b| b == b'\n') {
+            Some(index) => {
+                let end = self.scanned + index;
+                let mut line: Vec<u8> = self.buffer.drain(..=end).collect();
+                line.pop();
+                line
+            }
+            None if self.eof && !self.buffer.is_empty() => mem::take(&mut self.buffer),
+            None => {
+                self.scanned = self.buffer.len();
+                return None;
+            }
+        };
+
+        self.scanned = 0;
+
+        Some(line)
+    }
+}
+
+impl<R: AsyncRead + Unpin, T: DeserializeOwned> Stream for JsonStream<R, T> {
+    type Item = io::Result<T>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = &mut *self;
+
+        if this.done {
+            return Poll::Ready(None);
+        }
+
+        loop {
+            while let Some(line) = this.next_line() {
+                // Skip blank lines, including ones ending with a carriage
+                // return.
+                if line.iter().all(u8::is_ascii_whitespace) {
+                    continue;
+                }
+
+                return Poll::Ready(Some(match serde_json::from_slice(&line) {
+                    Ok(value) => Ok(value),
+                    Err(e) => {
+                        this.done = this.end_on_error;
+                        Err(io::Error::new(io::ErrorKind::InvalidData, e))
+                    }
+                }));
+            }
+
+            if this.eof {
+                this.done = true;
+                return Poll::Ready(None);
+            }
+
+            let mut chunk = [0; READ_BUFFER_SIZE];
+
+            match Pin::new(&mut this.reader).poll_read(cx, &mut chunk) {
+                Poll::Ready(Ok(0)) => this.eof = true,
+                Poll::Ready(Ok(len)) => this.buffer.extend_from_slice(&chunk[..len]),
+                Poll::Ready(Err(e)) if e.kind() == io::ErrorKind::Interrupted => {}
+                Poll::Ready(Err(e)) => {
+                    this.done = true;
+                    return Poll::Ready(Some(Err(e)));
+                }
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+    }
+}
+
+impl<R: fmt::Debug, T> fmt::Debug for JsonStream<R, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("JsonStream")
+            .field("reader", &self.reader)
+            .field("end_on_error", &self.end_on_error)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures_lite::{future::block_on, StreamExt};
+    use serde_json::{json, Value};
+
+    fn parse(input: &[u8]) -> Vec<io::Result<Value>> {
+        block_on(JsonStream::new(input).collect::<Vec<_>>())
+    }
+
+    fn values(input: &[u8]) -> Vec<Value> {
+        parse(input).into_iter().map(Result::unwrap).collect()
+    }
+
+    #[test]
+    fn one_value_per_line() {
+        assert_eq!(
+            values(b"{\"a\":1}\n[2]\n\"three\"\n"),
+            vec![json!({"a": 1}), json!([2]), json!("three")]
+        );
+    }
+
+    #[test]
+    fn blank_lines_are_skipped() {
+        assert_eq!(values(b"\n1\r\n\r\n  \n2\n\n"), vec![json!(1), json!(2)]);
+    }
+
+    #[test]
+    fn last_line_without_newline() {
+        assert_eq!(values(b"1\n2"), vec![json!(1), json!(2)]);
+    }
+
+    #[test]
+    fn malformed_line_does_not_end_stream() {
+        let results = parse(b"1\n{oops\n3\n");
+
+        assert_eq!(results.len(), 3);
+        assert_eq!(results[0].as_ref().unwrap(), &json!(1));
+        assert_eq!(
+            results[1].as_ref().unwrap_err().kind(),
+            io::ErrorKind::InvalidData
+        );
+        assert_eq!(results[2].as_ref().unwrap(), &json!(3));
+    }
+
+    #[test]
+    fn end_on_error() {
+        let results = block_on(
+            JsonStream::<_, Value>::new(&b"1\n{oops\n3\n"[..])
+                .end_on_error(true)
+                .collect::<Vec<_>>(),
+        );
+
+        assert_eq!(results.len(), 2);
+        assert!(results[1].is_err());
+    }
+
+    #[test]
+    fn line_split_across_reads() {
+        // Feed the stream one byte at a time.
+        struct Trickle<'a>(&'a [u8]);
+
+        impl AsyncRead for Trickle<'_> {
+            fn poll_read(
+                mut self: Pin<&mut Self>,
+                _cx: &mut Context<'_>,
+                buf: &mut [u8],
+            ) -> Poll<io::Result<usize>> {
+                if self.0.is_empty() {
+                    return Poll::Ready(Ok(0));
+                }
+
+                buf[0] = self.0[0];
+                self.0 = &self.0[1..];
+                Poll::Ready(Ok(1))
+            }
+        }
+
+        let values = block_on(
+            JsonStream::<_, Value>::new(Trickle(b"{\"hello\": \"world\"}\r\n{\"n\": 2}\n"))
+                .map(Result::unwrap)
+                .collect::<Vec<_>>(),
+        );
+
+        assert_eq!(values, vec![json!({"hello": "world"}), json!({"n": 2})]);
+    }
+}
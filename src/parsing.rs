@@ -52,16 +52,16 @@ pub(crate) fn parse_header(line: &[u8]) -> Option<(HeaderName, HeaderValue)> {
     Some((name, value))
 }
 
-pub(crate) fn header_to_curl_string(
+pub(crate) fn header_to_curl_bytes(
     name: &HeaderName,
     value: &HeaderValue,
     title_case: bool,
-) -> String {
-    let header_value = value
-        .to_str()
-        .expect("request header value is not valid UTF-8!");
+) -> Vec<u8> {
+    // Header values are not required to be valid UTF-8 (servers may expect
+    // Latin-1 for example), so the raw bytes are passed through as-is.
+    let header_value = value.as_bytes();
 
-    let mut string = String::new();
+    let mut bytes = Vec::new();
 
     if title_case {
         let name_bytes: &[u8] = name.as_ref();
@@ -69,27 +69,27 @@ pub(crate) fn header_to_curl_string(
 
         for &byte in name_bytes {
             if at_start_of_word {
-                string.push(byte.to_ascii_uppercase().into());
+                bytes.push(byte.to_ascii_uppercase());
             } else {
-                string.push(byte.into());
+                bytes.push(byte);
             }
 
             at_start_of_word = !byte.is_ascii_alphanumeric();
         }
     } else {
-        string.push_str(name.as_str());
+        bytes.extend_from_slice(name.as_str().as_bytes());
     }
 
     // libcurl requires a special syntax to set a header with an explicit empty
     // value. See https://curl.haxx.se/libcurl/c/CURLOPT_HTTPHEADER.html.
-    if header_value.trim().is_empty() {
-        string.push(';');
+    if header_value.iter().all(u8::is_ascii_whitespace) {
+        bytes.push(b';');
     } else {
-        string.push_str(": ");
-        string.push_str(header_value);
+        bytes.extend_from_slice(b": ");
+        bytes.extend_from_slice(header_value);
     }
 
-    string
+    bytes
 }
 
 #[cfg(test)]
@@ -171,32 +171,42 @@ mod tests {
     }
 
     #[test]
-    fn normal_header_to_curl_string() {
+    fn normal_header_to_curl_bytes() {
         let name = "User-Agent".parse().unwrap();
         let value = "foo".parse().unwrap();
 
-        assert_eq!(
-            header_to_curl_string(&name, &value, false),
-            "user-agent: foo"
-        );
+        assert_eq!(header_to_curl_bytes(&name, &value, false), b"user-agent: foo");
     }
 
     #[test]
-    fn blank_header_to_curl_string() {
+    fn blank_header_to_curl_bytes() {
         let name = "User-Agent".parse().unwrap();
         let value = "".parse().unwrap();
 
-        assert_eq!(header_to_curl_string(&name, &value, false), "user-agent;");
+        assert_eq!(header_to_curl_bytes(&name, &value, false), b"user-agent;");
     }
 
     #[test]
-    fn normal_header_to_curl_string_title_case() {
+    fn normal_header_to_curl_bytes_title_case() {
         let name = "User-Agent".parse().unwrap();
         let value = "foo".parse().unwrap();
 
-        assert_eq!(
-            header_to_curl_string(&name, &value, true),
-            "User-Agent: foo"
-        );
+        assert_eq!(header_to_curl_bytes(&name, &value, true), b"User-Agent: foo");
+    }
+
+    #[test]
+    fn non_utf8_header_to_curl_bytes() {
+        let name = "X-Name".parse().unwrap();
+        let value = HeaderValue::from_bytes(b"caf\xe9").unwrap();
+
+        assert_eq!(header_to_curl_bytes(&name, &value, false), b"x-name: caf\xe9");
+    }
+
+    #[test]
+    fn parse_non_utf8_header() {
+        let (name, value) = parse_header(b"X-Name: caf\xe9\r\n").unwrap();
+
+        assert_eq!(name, "x-name");
+        assert_eq!(value.as_bytes(), b"caf\xe9");
     }
 }
@@ -1,13 +1,22 @@
 use crate::{
     auth::Authentication,
     body::AsyncBody,
-    config::{request::RequestConfig, RedirectPolicy},
+    config::{request::RequestConfig, RedirectAction, RedirectAttempt, RedirectPolicy},
     error::{Error, ErrorKind},
     handler::RequestBody,
     interceptor::{Context, Interceptor, InterceptorFuture},
     request::RequestExt,
 };
-use http::{header::ToStrError, uri::Scheme, HeaderMap, HeaderValue, Request, Response, Uri};
+use http::{
+    header::ToStrError,
+    uri::Scheme,
+    HeaderMap,
+    HeaderValue,
+    Request,
+    Response,
+    StatusCode,
+    Uri,
+};
 use std::{borrow::Cow, convert::TryFrom, fmt::Write, str};
 use url::Url;
 
@@ -20,6 +29,20 @@ const DEFAULT_REDIRECT_LIMIT: u32 = 1024;
 /// following any redirects.
 pub(crate) struct EffectiveUri(pub(crate) Uri);
 
+/// Extension containing the number of redirects that were followed before
+/// receiving a response.
+pub(crate) struct RedirectCount(pub(crate) u32);
+
+/// Extension containing the URI and status code of each redirect response that
+/// was followed before receiving a response, in order.
+pub(crate) struct RedirectHistory(pub(crate) Vec<(Uri, StatusCode)>);
+
+/// Extension attached to requests made while following a redirect, containing
+/// the URI of the original request that was redirected.
+#[cfg_attr(not(feature = "cookies"), allow(dead_code))]
+#[derive(Clone)]
+pub(crate) struct RedirectOrigin(pub(crate) Uri);
+
 /// Interceptor that implements automatic following of HTTP redirects.
 pub(crate) struct RedirectInterceptor;
 
@@ -44,7 +67,7 @@ impl Interceptor for RedirectInterceptor {
                 .unwrap_or_default();
 
             // No redirect handling, just proceed normally.
-            if policy == RedirectPolicy::None {
+            if let RedirectPolicy::None = policy {
                 let mut response = ctx.send(request).await?;
                 response
                     .extensions_mut()
@@ -59,26 +82,74 @@ impl Interceptor for RedirectInterceptor {
                 .and_then(|config| config.auto_referer)
                 .unwrap_or(false);
 
+            let trusted_hosts = request
+                .extensions()
+                .get::<RequestConfig>()
+                .and_then(|config| config.trusted_redirect_hosts.clone())
+                .unwrap_or_default();
+
+            let https_only = request
+                .extensions()
+                .get::<RequestConfig>()
+                .and_then(|config| config.redirect_https_only)
+                .unwrap_or(false);
+
+            let deny_downgrade = https_only
+                || request
+                    .extensions()
+                    .get::<RequestConfig>()
+                    .and_then(|config| config.deny_redirect_downgrade)
+                    .unwrap_or(false);
+
             let limit = match policy {
                 RedirectPolicy::Limit(limit) => limit,
                 _ => DEFAULT_REDIRECT_LIMIT,
             };
 
-            // Keep track of how many redirects we've done.
+            // Keep track of how many redirects we've done, and where from.
+            let origin = effective_uri.clone();
             let mut redirect_count: u32 = 0;
+            let mut redirect_history = Vec::new();
 
             loop {
                 // Preserve a clone of the request before sending it.
                 let mut request_builder = request.to_builder();
+                let is_head_request = request.method() == http::Method::HEAD;
 
                 // Send the request to get the ball rolling.
                 let mut response = ctx.send(request).await?;
 
-                // Check for a redirect.
-                if let Some(redirect_location) = get_redirect_location(&effective_uri, &response) {
+                // Check for a redirect, and whether the policy allows it.
+                let redirect_location = get_redirect_location(&effective_uri, &response)
+                    .and_then(|location| {
+                        apply_policy(&policy, &effective_uri, location, &response, redirect_count)
+                    });
+
+                if let Some(redirect_location) = redirect_location {
                     // If we've reached the limit, return an error as requested.
                     if redirect_count >= limit {
-                        return Err(Error::with_response(ErrorKind::TooManyRedirects, &response));
+                        return Err(Error::with_response_context(
+                            ErrorKind::TooManyRedirects,
+                            Some(format!(
+                                "limit of {} redirects reached at {}, which redirects to {}",
+                                limit, effective_uri, redirect_location
+                            )),
+                            &response,
+                        ));
+                    }
+
+                    // Refuse insecure redirects if asked to.
+                    if is_insecure_redirect(
+                        &effective_uri,
+                        &redirect_location,
+                        https_only,
+                        deny_downgrade,
+                    ) {
+                        return Err(Error::with_response_context(
+                            ErrorKind::InsecureRedirect,
+                            Some(format!("{} redirects to {}", effective_uri, redirect_location)),
+                            &response,
+                        ));
                     }
 
                     // Set referer header.
@@ -93,17 +164,29 @@ impl Interceptor for RedirectInterceptor {
                     // Check if we should change the request method into a GET. HTTP
                     // specs don't really say one way or another when this should
                     // happen for most status codes, so we just mimic curl's
-                    // behavior here since it is so common.
-                    if response.status() == 301
-                        || response.status() == 302
-                        || response.status() == 303
-                    {
+                    // behavior here since it is so common. A HEAD request stays a
+                    // HEAD request, since a GET would download the response body.
+                    let change_to_get = !is_head_request
+                        && (response.status() == 301
+                            || response.status() == 302
+                            || response.status() == 303);
+
+                    if change_to_get {
                         request_builder = request_builder.method(http::Method::GET);
+
+                        // The request body is not sent with the GET, so neither
+                        // should any headers describing it.
+                        if let Some(headers) = request_builder.headers_mut() {
+                            remove_content_headers(headers);
+                        }
                     }
 
                     // If we are redirecting to a different authority, scrub
-                    // sensitive headers from subsequent requests.
-                    if !is_same_authority(&effective_uri, &redirect_location) {
+                    // sensitive headers from subsequent requests, unless the
+                    // user has told us to trust the new host.
+                    if !is_same_authority(&effective_uri, &redirect_location)
+                        && !is_trusted_host(&trusted_hosts, &redirect_location)
+                    {
                         if let Some(headers) = request_builder.headers_mut() {
                             scrub_sensitive_headers(headers);
                         }
@@ -111,6 +194,11 @@ impl Interceptor for RedirectInterceptor {
                         // Remove auth configuration.
                         if let Some(extensions) = request_builder.extensions_mut() {
                             extensions.remove::<Authentication>();
+
+                            if let Some(config) = extensions.get_mut::<RequestConfig>() {
+                                config.credentials = None;
+                                config.bearer_token = None;
+                            }
                         }
                     }
 
@@ -122,6 +210,11 @@ impl Interceptor for RedirectInterceptor {
                         .map(|v| v.0)
                         .unwrap_or_default();
 
+                    // The body is dropped if we changed the request into a GET.
+                    if change_to_get {
+                        request_body = AsyncBody::empty();
+                    }
+
                     // Redirect handling is tricky when we are uploading something.
                     // If we can, reset the body stream to the beginning. This might
                     // work if the body to upload is an in-memory byte buffer, but
@@ -137,9 +230,11 @@ impl Interceptor for RedirectInterceptor {
                     }
 
                     // Update the request to point to the new URI.
+                    redirect_history.push((effective_uri, response.status()));
                     effective_uri = redirect_location.clone();
                     request = request_builder
                         .uri(redirect_location)
+                        .extension(RedirectOrigin(origin.clone()))
                         .body(request_body)
                         .map_err(|e| Error::new(ErrorKind::InvalidRequest, e))?;
                     redirect_count += 1;
@@ -149,6 +244,12 @@ impl Interceptor for RedirectInterceptor {
                     response
                         .extensions_mut()
                         .insert(EffectiveUri(effective_uri));
+                    response
+                        .extensions_mut()
+                        .insert(RedirectCount(redirect_count));
+                    response
+                        .extensions_mut()
+                        .insert(RedirectHistory(redirect_history));
 
                     return Ok(response);
                 }
@@ -157,6 +258,55 @@ impl Interceptor for RedirectInterceptor {
     }
 }
 
+/// Decide where to redirect to according to the given policy, if anywhere.
+fn apply_policy<T>(
+    policy: &RedirectPolicy,
+    request_uri: &Uri,
+    location: Uri,
+    response: &Response<T>,
+    redirects: u32,
+) -> Option<Uri> {
+    if let RedirectPolicy::Custom(f) = policy {
+        let attempt = RedirectAttempt {
+            status: response.status(),
+            uri: request_uri,
+            location: &location,
+            redirects,
+        };
+
+        match f(&attempt) {
+            RedirectAction::Follow => Some(location),
+            RedirectAction::FollowTo(uri) => match resolve(request_uri, &uri.to_string()) {
+                Ok(uri) => Some(uri),
+                Err(e) => {
+                    tracing::debug!("invalid redirect location from policy: {}", e);
+                    None
+                }
+            },
+            RedirectAction::Stop => None,
+        }
+    } else {
+        Some(location)
+    }
+}
+
+/// Check if a redirect is insecure according to the given settings.
+fn is_insecure_redirect(from: &Uri, to: &Uri, https_only: bool, deny_downgrade: bool) -> bool {
+    if to.scheme() == Some(&Scheme::HTTPS) {
+        return false;
+    }
+
+    https_only || (deny_downgrade && from.scheme() == Some(&Scheme::HTTPS))
+}
+
+/// Remove headers that describe the request body.
+fn remove_content_headers(headers: &mut HeaderMap) {
+    headers.remove(http::header::CONTENT_ENCODING);
+    headers.remove(http::header::CONTENT_LENGTH);
+    headers.remove(http::header::CONTENT_TYPE);
+    headers.remove(http::header::TRANSFER_ENCODING);
+}
+
 fn get_redirect_location<T>(request_uri: &Uri, response: &Response<T>) -> Option<Uri> {
     if response.status().is_redirection() {
         let location = response.headers().get(http::header::LOCATION)?;
@@ -280,6 +430,15 @@ fn is_same_authority(a: &Uri, b: &Uri) -> bool {
     a.scheme() == b.scheme() && a.host() == b.host() && a.port() == b.port()
 }
 
+fn is_trusted_host(trusted_hosts: &[String], uri: &Uri) -> bool {
+    match uri.host() {
+        Some(host) => trusted_hosts
+            .iter()
+            .any(|trusted| trusted.eq_ignore_ascii_case(host)),
+        None => false,
+    }
+}
+
 fn scrub_sensitive_headers(headers: &mut HeaderMap) {
     headers.remove(http::header::AUTHORIZATION);
     headers.remove(http::header::COOKIE);
@@ -293,6 +452,22 @@ mod tests {
     use http::Response;
     use test_case::test_case;
 
+    #[test_case("https://a.com", "https://b.com", false, true => false)]
+    #[test_case("https://a.com", "http://b.com", false, true => true)]
+    #[test_case("https://a.com", "http://b.com", false, false => false)]
+    #[test_case("http://a.com", "http://b.com", false, true => false)]
+    #[test_case("http://a.com", "https://b.com", true, true => false)]
+    #[test_case("http://a.com", "http://b.com", true, true => true)]
+    #[test_case("http://a.com", "ftp://b.com", true, true => true)]
+    fn insecure_redirect(from: &str, to: &str, https_only: bool, deny_downgrade: bool) -> bool {
+        super::is_insecure_redirect(
+            &from.parse().unwrap(),
+            &to.parse().unwrap(),
+            https_only,
+            deny_downgrade,
+        )
+    }
+
     #[test_case("http://foo.com", "http://foo.com", "http://foo.com/")]
     #[test_case("http://foo.com", "/two", "http://foo.com/two")]
     #[test_case("http://foo.com", "http://foo.com#foo", "http://foo.com/")]
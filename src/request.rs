@@ -6,6 +6,7 @@ use crate::{
         Configurable,
     },
     error::Error,
+    multipart::Form,
 };
 use http::{Request, Response};
 
@@ -87,6 +88,136 @@ impl<T> RequestExt<T> for Request<T> {
     }
 }
 
+/// Extension methods on an HTTP request builder.
+pub trait RequestBuilderExt {
+    /// Use the given `multipart/form-data` form as the request body, consuming
+    /// the builder and returning the finished request.
+    ///
+    /// The `Content-Type` header of the request is set to
+    /// `multipart/form-data` along with the boundary used by the form,
+    /// replacing any content type already set on the builder.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use isahc::{multipart::Form, prelude::*, Request};
+    ///
+    /// let response = Request::post("https://httpbin.org/post")
+    ///     .multipart(Form::new().text("name", "Ferris"))?
+    ///     .send()?;
+    /// # Ok::<(), isahc::Error>(())
+    /// ```
+    fn multipart(self, form: Form) -> Result<Request<Body>, Error>;
+
+    /// Serialize the given value as JSON and use it as the request body,
+    /// consuming the builder and returning the finished request.
+    ///
+    /// A `Content-Type: application/json` header is also added to the request,
+    /// unless a content type has already been set on the builder. The body is
+    /// serialized up front, so the request will have a known
+    /// `Content-Length`. If serialization fails, an error is returned before
+    /// the request is ever sent.
+    ///
+    /// # Availability
+    ///
+    /// This method is only available when the [`json`](index.html#json) feature
+    /// is enabled.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use isahc::{prelude::*, Request};
+    /// use serde_json::json;
+    ///
+    /// let response = Request::post("https://httpbin.org/post")
+    ///     .json(&json!({
+    ///         "speed": "fast",
+    ///         "cool_name": true,
+    ///     }))?
+    ///     .send()?;
+    /// # Ok::<(), isahc::Error>(())
+    /// ```
+    #[cfg(feature = "json")]
+    fn json<T>(self, value: &T) -> Result<Request<Vec<u8>>, Error>
+    where
+        T: serde::Serialize + ?Sized;
+
+    /// Serialize the given value as URL-encoded form data and use it as the
+    /// request body, consuming the builder and returning the finished request.
+    ///
+    /// A `Content-Type: application/x-www-form-urlencoded` header is also added
+    /// to the request, unless a content type has already been set on the
+    /// builder. Sequences and maps are encoded in the same way as
+    /// [`serde_urlencoded`](https://docs.rs/serde_urlencoded) does. If
+    /// serialization fails, an error is returned before the request is ever
+    /// sent.
+    ///
+    /// # Availability
+    ///
+    /// This method is only available when the [`form`](index.html#form) feature
+    /// is enabled.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use isahc::{prelude::*, Request};
+    ///
+    /// let response = Request::post("https://httpbin.org/post")
+    ///     .form(&[("speed", "fast"), ("cool_name", "true")])?
+    ///     .send()?;
+    /// # Ok::<(), isahc::Error>(())
+    /// ```
+    #[cfg(feature = "form")]
+    fn form<T>(self, value: &T) -> Result<Request<Vec<u8>>, Error>
+    where
+        T: serde::Serialize + ?Sized;
+}
+
+impl RequestBuilderExt for http::request::Builder {
+    fn multipart(mut self, form: Form) -> Result<Request<Body>, Error> {
+        if let Some(headers) = self.headers_mut() {
+            headers.insert(
+                http::header::CONTENT_TYPE,
+                http::HeaderValue::from_str(&form.content_type()).map_err(http::Error::from)?,
+            );
+        }
+
+        self.body(form.into_body()).map_err(Error::from)
+    }
+
+    #[cfg(feature = "json")]
+    fn json<T>(mut self, value: &T) -> Result<Request<Vec<u8>>, Error>
+    where
+        T: serde::Serialize + ?Sized,
+    {
+        let body = crate::body::serialize_json(value)?;
+
+        if let Some(headers) = self.headers_mut() {
+            headers
+                .entry(http::header::CONTENT_TYPE)
+                .or_insert(http::HeaderValue::from_static(crate::body::JSON));
+        }
+
+        self.body(body).map_err(Error::from)
+    }
+
+    #[cfg(feature = "form")]
+    fn form<T>(mut self, value: &T) -> Result<Request<Vec<u8>>, Error>
+    where
+        T: serde::Serialize + ?Sized,
+    {
+        let body = crate::body::serialize_form(value)?;
+
+        if let Some(headers) = self.headers_mut() {
+            headers
+                .entry(http::header::CONTENT_TYPE)
+                .or_insert(http::HeaderValue::from_static(crate::body::FORM));
+        }
+
+        self.body(body).map_err(Error::from)
+    }
+}
+
 impl Configurable for http::request::Builder {
     #[cfg(feature = "cookies")]
     fn cookie_jar(self, cookie_jar: crate::cookies::CookieJar) -> Self {
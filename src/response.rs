@@ -1,9 +1,16 @@
-use crate::{metrics::Metrics, redirect::EffectiveUri, trailer::Trailer};
+use crate::{
+    error::Error,
+    metrics::Metrics,
+    redirect::{EffectiveUri, RedirectCount, RedirectHistory},
+    retry::Retries,
+    tls::TlsInfo,
+    trailer::Trailer,
+};
 use futures_lite::io::{copy as copy_async, AsyncRead, AsyncWrite};
-use http::{Response, Uri};
+use http::{Response, StatusCode, Uri};
 use std::{
     fs::File,
-    io::{self, Read, Write},
+    io::{self, BufWriter, Read, Write},
     net::SocketAddr,
     path::Path,
 };
@@ -41,6 +48,22 @@ pub trait ResponseExt<T> {
     ///
     /// This information is only available if populated by the HTTP client that
     /// produced the response.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use isahc::{config::RedirectPolicy, prelude::*, Request};
+    ///
+    /// let response = Request::get("https://httpbin.org/redirect/1")
+    ///     .redirect_policy(RedirectPolicy::Follow)
+    ///     .body(())?
+    ///     .send()?;
+    ///
+    /// // Relative links in the response should be resolved against the URI we
+    /// // ended up at, not the one we originally requested.
+    /// println!("Landed at: {}", response.effective_uri().unwrap());
+    /// # Ok::<(), isahc::Error>(())
+    /// ```
     fn effective_uri(&self) -> Option<&Uri>;
 
     /// Get the local socket address of the last-used connection involved in
@@ -71,8 +94,95 @@ pub trait ResponseExt<T> {
     /// server. Forward and reverse proxies between the caller and the server
     /// can cause the address to be returned to reflect the address of the
     /// nearest proxy rather than the server.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use isahc::prelude::*;
+    ///
+    /// let response = isahc::get("https://example.org")?;
+    ///
+    /// // Record which backend served the request, and from which local port.
+    /// if let (Some(local), Some(remote)) = (response.local_addr(), response.remote_addr()) {
+    ///     println!("{} -> {}", local, remote);
+    /// }
+    /// # Ok::<(), isahc::Error>(())
+    /// ```
     fn remote_addr(&self) -> Option<SocketAddr>;
 
+    /// Check whether the last connection involved in this request was reused
+    /// from the connection cache, rather than newly established for it.
+    ///
+    /// Returns `None` if this is not known, such as for responses that were
+    /// not received from the network.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use isahc::{prelude::*, HttpClient};
+    ///
+    /// let client = HttpClient::new()?;
+    ///
+    /// client.get("https://example.org")?.consume()?;
+    /// let response = client.get("https://example.org")?;
+    ///
+    /// // The second request should not need a new connection.
+    /// assert_eq!(response.connection_reused(), Some(true));
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    fn connection_reused(&self) -> Option<bool>;
+
+    /// Get information about the TLS session the response was received over,
+    /// such as the negotiated protocol version and cipher suite.
+    ///
+    /// Returns `None` if the response was not received over a secure
+    /// connection, or if the TLS engine in use does not support providing this
+    /// information. See [`TlsInfo`] for details.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use isahc::{config::TlsVersion, prelude::*};
+    ///
+    /// let response = isahc::get("https://example.org")?;
+    ///
+    /// if let Some(info) = response.tls_info() {
+    ///     println!("connected using {:?} with {}", info.version(), info.cipher());
+    ///     assert!(info.version() >= TlsVersion::V1_2);
+    /// }
+    /// # Ok::<(), isahc::Error>(())
+    /// ```
+    fn tls_info(&self) -> Option<&TlsInfo>;
+
+    /// Get the number of redirects that were followed in order to receive this
+    /// response. This is zero if no redirects were followed.
+    fn redirect_count(&self) -> u32;
+
+    /// Get the URI and status code of each redirect that was followed in order
+    /// to receive this response, starting with the originally requested URI.
+    /// This is empty if no redirects were followed.
+    ///
+    /// This is useful for logging, or for understanding how a request ended up
+    /// at a surprising destination.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use isahc::{config::RedirectPolicy, prelude::*, Request};
+    ///
+    /// let response = Request::get("https://httpbin.org/redirect/3")
+    ///     .redirect_policy(RedirectPolicy::Follow)
+    ///     .body(())?
+    ///     .send()?;
+    ///
+    /// for (uri, status) in response.redirect_history() {
+    ///     println!("{} redirected with {}", uri, status);
+    /// }
+    /// println!("Landed at: {}", response.effective_uri().unwrap());
+    /// # Ok::<(), isahc::Error>(())
+    /// ```
+    fn redirect_history(&self) -> &[(Uri, StatusCode)];
+
     /// Get the configured cookie jar used for persisting cookies from this
     /// response, if any.
     ///
@@ -90,6 +200,22 @@ pub trait ResponseExt<T> {
     /// metrics you can use
     /// [`Configurable::metrics`](crate::config::Configurable::metrics).
     fn metrics(&self) -> Option<&Metrics>;
+
+    /// Get the number of times the request was retried before this response
+    /// was received.
+    ///
+    /// This will always be zero unless a retry policy is configured using
+    /// [`Configurable::retry_policy`](crate::config::Configurable::retry_policy).
+    /// If the final attempt fails with an error instead, the number of retries
+    /// is available from [`Error::retry_count`].
+    fn retry_count(&self) -> u32;
+
+    /// Get the error returned by the most recent failed attempt to send the
+    /// request, if the request was retried because of an error.
+    ///
+    /// Retries made because of an unsuccessful response status are not
+    /// considered errors, and are not reported here.
+    fn last_retry_error(&self) -> Option<&Error>;
 }
 
 impl<T> ResponseExt<T> for Response<T> {
@@ -113,6 +239,24 @@ impl<T> ResponseExt<T> for Response<T> {
         self.extensions().get::<RemoteAddr>().map(|v| v.0)
     }
 
+    fn connection_reused(&self) -> Option<bool> {
+        self.extensions().get::<ConnectionReused>().map(|v| v.0)
+    }
+
+    fn tls_info(&self) -> Option<&TlsInfo> {
+        self.extensions().get()
+    }
+
+    fn redirect_count(&self) -> u32 {
+        self.extensions().get::<RedirectCount>().map_or(0, |v| v.0)
+    }
+
+    fn redirect_history(&self) -> &[(Uri, StatusCode)] {
+        self.extensions()
+            .get::<RedirectHistory>()
+            .map_or(&[], |v| v.0.as_slice())
+    }
+
     #[cfg(feature = "cookies")]
     fn cookie_jar(&self) -> Option<&crate::cookies::CookieJar> {
         self.extensions().get()
@@ -121,6 +265,16 @@ impl<T> ResponseExt<T> for Response<T> {
     fn metrics(&self) -> Option<&Metrics> {
         self.extensions().get()
     }
+
+    fn retry_count(&self) -> u32 {
+        self.extensions().get::<Retries>().map_or(0, |v| v.count)
+    }
+
+    fn last_retry_error(&self) -> Option<&Error> {
+        self.extensions()
+            .get::<Retries>()
+            .and_then(|v| v.last_error.as_ref())
+    }
 }
 
 /// Provides extension methods for consuming HTTP response streams.
@@ -175,7 +329,13 @@ pub trait ReadResponseExt<R: Read> {
 
     /// Copy the response body into a writer.
     ///
-    /// Returns the number of bytes that were written.
+    /// The body is streamed into the writer in chunks as it is received, so
+    /// the entire body is never buffered in memory. This makes it suitable for
+    /// saving large downloads to a file or piping them elsewhere.
+    ///
+    /// Returns the number of bytes that were written. If either the transfer
+    /// fails or the writer returns an error, copying stops and the error is
+    /// returned.
     ///
     /// # Examples
     ///
@@ -194,10 +354,20 @@ pub trait ReadResponseExt<R: Read> {
     /// Write the response body to a file.
     ///
     /// This method makes it convenient to download a file using a GET request
-    /// and write it to a file synchronously in a single chain of calls.
+    /// and write it to a file synchronously in a single chain of calls. The
+    /// file is created if it does not exist, and truncated if it does. The
+    /// body is streamed into the file using buffered writes as it is received.
     ///
     /// Returns the number of bytes that were written.
     ///
+    /// # Errors
+    ///
+    /// If the transfer fails, the returned error can be converted back into
+    /// an [`Error`] using [`From`] to find out why. Errors creating or writing
+    /// to the file are returned as they are, and convert into an [`Error`] of
+    /// kind [`Io`](crate::error::ErrorKind::Io). In either case, the file may
+    /// be left with only part of the body written to it.
+    ///
     /// # Examples
     ///
     /// ```no_run
@@ -208,7 +378,12 @@ pub trait ReadResponseExt<R: Read> {
     /// # Ok::<(), isahc::Error>(())
     /// ```
     fn copy_to_file<P: AsRef<Path>>(&mut self, path: P) -> io::Result<u64> {
-        File::create(path).and_then(|f| self.copy_to(f))
+        let mut writer = BufWriter::new(File::create(path)?);
+        let len = self.copy_to(&mut writer)?;
+
+        writer.flush()?;
+
+        Ok(len)
     }
 
     /// Read the entire response body into memory.
@@ -257,6 +432,55 @@ pub trait ReadResponseExt<R: Read> {
     #[cfg(feature = "text-decoding")]
     fn text(&mut self) -> io::Result<String>;
 
+    /// Read the response body as a string, using the encoding with the given
+    /// label if the response does not specify one.
+    ///
+    /// This behaves just like [`ReadResponseExt::text`], except that the given
+    /// encoding is used instead of UTF-8 if the body does not begin with a BOM
+    /// and the `Content-Type` header has no `charset` parameter. This is useful
+    /// for servers that are known to send text in a legacy encoding without
+    /// saying so. The label is matched as described in the [Encoding
+    /// Standard](https://encoding.spec.whatwg.org/#names-and-labels), so
+    /// labels such as `latin1` or `shift_jis` are accepted.
+    ///
+    /// If the label is not a known encoding, an error of kind
+    /// [`InvalidInput`](std::io::ErrorKind::InvalidInput) is returned without
+    /// reading the response body.
+    ///
+    /// # Availability
+    ///
+    /// This method is only available when the
+    /// [`text-decoding`](index.html#text-decoding) feature is enabled, which it
+    /// is by default.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use isahc::prelude::*;
+    ///
+    /// let text = isahc::get("https://example.org")?.text_with_charset("windows-1252")?;
+    /// println!("{}", text);
+    /// # Ok::<(), isahc::Error>(())
+    /// ```
+    #[cfg(feature = "text-decoding")]
+    fn text_with_charset(&mut self, default: &str) -> io::Result<String>;
+
+    /// Read the response body as a string, failing if the response body
+    /// contains any malformed characters.
+    ///
+    /// This behaves just like [`ReadResponseExt::text`], except that instead of
+    /// replacing malformed characters, an error of kind
+    /// [`InvalidData`](std::io::ErrorKind::InvalidData) is returned. Use this
+    /// when the text must be decoded losslessly.
+    ///
+    /// # Availability
+    ///
+    /// This method is only available when the
+    /// [`text-decoding`](index.html#text-decoding) feature is enabled, which it
+    /// is by default.
+    #[cfg(feature = "text-decoding")]
+    fn text_strict(&mut self) -> io::Result<String>;
+
     /// Deserialize the response body as JSON into a given type.
     ///
     /// # Availability
@@ -298,11 +522,38 @@ impl<R: Read> ReadResponseExt<R> for Response<R> {
         crate::text::Decoder::for_response(self).decode_reader(self.body_mut())
     }
 
+    #[cfg(feature = "text-decoding")]
+    fn text_with_charset(&mut self, default: &str) -> io::Result<String> {
+        crate::text::Decoder::for_response_with_default(self, default)?
+            .decode_reader(self.body_mut())
+    }
+
+    #[cfg(feature = "text-decoding")]
+    fn text_strict(&mut self) -> io::Result<String> {
+        crate::text::Decoder::for_response(self)
+            .strict()
+            .decode_reader(self.body_mut())
+    }
+
     #[cfg(feature = "json")]
     fn json<D>(&mut self) -> Result<D, serde_json::Error>
     where
         D: serde::de::DeserializeOwned,
     {
+        // JSON is almost always UTF-8, but respect any other charset the
+        // server explicitly declares.
+        #[cfg(feature = "text-decoding")]
+        {
+            let decoder = crate::text::Decoder::for_response(self);
+
+            if !decoder.is_utf8() {
+                return match decoder.decode_reader(self.body_mut()) {
+                    Ok(text) => serde_json::from_str(&text),
+                    Err(e) => Err(json_io_error(e)),
+                };
+            }
+        }
+
         serde_json::from_reader(self.body_mut())
     }
 }
@@ -356,7 +607,17 @@ pub trait AsyncReadResponseExt<R: AsyncRead + Unpin> {
 
     /// Copy the response body into a writer asynchronously.
     ///
-    /// Returns the number of bytes that were written.
+    /// The body is streamed into the writer in chunks as it is received, so
+    /// the entire body is never buffered in memory.
+    ///
+    /// Returns the number of bytes that were written. If either the transfer
+    /// fails or the writer returns an error, copying stops and the error is
+    /// returned.
+    ///
+    /// To save the body to a file, pass in an asynchronous file handle from
+    /// the async runtime or file system library of your choice. Isahc does not
+    /// provide one itself, since writing to files asynchronously requires
+    /// some sort of thread pool.
     ///
     /// # Examples
     ///
@@ -417,6 +678,34 @@ pub trait AsyncReadResponseExt<R: AsyncRead + Unpin> {
     #[cfg(feature = "text-decoding")]
     fn text(&mut self) -> crate::text::TextFuture<'_, &mut R>;
 
+    /// Read the response body as a string asynchronously, using the encoding
+    /// with the given label if the response does not specify one.
+    ///
+    /// This is the asynchronous equivalent of
+    /// [`ReadResponseExt::text_with_charset`].
+    ///
+    /// # Availability
+    ///
+    /// This method is only available when the
+    /// [`text-decoding`](index.html#text-decoding) feature is enabled, which it
+    /// is by default.
+    #[cfg(feature = "text-decoding")]
+    fn text_with_charset(&mut self, default: &str) -> crate::text::TextFuture<'_, &mut R>;
+
+    /// Read the response body as a string asynchronously, failing if the
+    /// response body contains any malformed characters.
+    ///
+    /// This is the asynchronous equivalent of
+    /// [`ReadResponseExt::text_strict`].
+    ///
+    /// # Availability
+    ///
+    /// This method is only available when the
+    /// [`text-decoding`](index.html#text-decoding) feature is enabled, which it
+    /// is by default.
+    #[cfg(feature = "text-decoding")]
+    fn text_strict(&mut self) -> crate::text::TextFuture<'_, &mut R>;
+
     /// Deserialize the response body as JSON into a given type.
     ///
     /// # Caveats
@@ -480,30 +769,48 @@ impl<R: AsyncRead + Unpin> AsyncReadResponseExt<R> for Response<R> {
         crate::text::Decoder::for_response(self).decode_reader_async(self.body_mut())
     }
 
+    #[cfg(feature = "text-decoding")]
+    fn text_with_charset(&mut self, default: &str) -> crate::text::TextFuture<'_, &mut R> {
+        match crate::text::Decoder::for_response_with_default(self, default) {
+            Ok(decoder) => decoder.decode_reader_async(self.body_mut()),
+            Err(e) => crate::text::TextFuture::new(async move { Err(e) }),
+        }
+    }
+
+    #[cfg(feature = "text-decoding")]
+    fn text_strict(&mut self) -> crate::text::TextFuture<'_, &mut R> {
+        crate::text::Decoder::for_response(self)
+            .strict()
+            .decode_reader_async(self.body_mut())
+    }
+
     #[cfg(feature = "json")]
     fn json<T>(&mut self) -> JsonFuture<'_, R, T>
     where
         T: serde::de::DeserializeOwned,
     {
         JsonFuture::new(async move {
+            // JSON is almost always UTF-8, but respect any other charset the
+            // server explicitly declares.
+            #[cfg(feature = "text-decoding")]
+            {
+                let decoder = crate::text::Decoder::for_response(self);
+
+                if !decoder.is_utf8() {
+                    return match decoder.decode_reader_async(self.body_mut()).await {
+                        Ok(text) => serde_json::from_str(&text),
+                        Err(e) => Err(json_io_error(e)),
+                    };
+                }
+            }
+
             let mut buf = allocate_buffer(self);
 
             // Serde does not support incremental parsing, so we have to resort
             // to reading the entire response into memory first and then
             // deserializing.
             if let Err(e) = copy_async(self.body_mut(), &mut buf).await {
-                struct ErrorReader(Option<io::Error>);
-
-                impl Read for ErrorReader {
-                    fn read(&mut self, _buf: &mut [u8]) -> io::Result<usize> {
-                        Err(self.0.take().unwrap())
-                    }
-                }
-
-                // Serde offers no public way to directly create an error from
-                // an I/O error, but we can do so in a roundabout way by parsing
-                // a reader that always returns the desired error.
-                serde_json::from_reader(ErrorReader(Some(e)))
+                Err(json_io_error(e))
             } else {
                 serde_json::from_slice(&buf)
             }
@@ -511,6 +818,27 @@ impl<R: AsyncRead + Unpin> AsyncReadResponseExt<R> for Response<R> {
     }
 }
 
+/// Convert an I/O error into a JSON error, so that callers can distinguish
+/// between the two using [`serde_json::Error::is_io`].
+#[cfg(feature = "json")]
+fn json_io_error(error: io::Error) -> serde_json::Error {
+    struct ErrorReader(Option<io::Error>);
+
+    impl Read for ErrorReader {
+        fn read(&mut self, _buf: &mut [u8]) -> io::Result<usize> {
+            Err(self.0.take().unwrap())
+        }
+    }
+
+    // Serde offers no public way to directly create an error from an I/O
+    // error, but we can do so in a roundabout way by parsing a reader that
+    // always returns the desired error.
+    match serde_json::from_reader::<_, serde::de::IgnoredAny>(ErrorReader(Some(error))) {
+        Err(e) => e,
+        Ok(_) => unreachable!(),
+    }
+}
+
 fn allocate_buffer<T>(response: &Response<T>) -> Vec<u8> {
     if let Some(length) = get_content_length(response) {
         Vec::with_capacity(length as usize)
@@ -548,6 +876,8 @@ pub(crate) struct LocalAddr(pub(crate) SocketAddr);
 
 pub(crate) struct RemoteAddr(pub(crate) SocketAddr);
 
+pub(crate) struct ConnectionReused(pub(crate) bool);
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -0,0 +1,267 @@
+use crate::{
+    body::AsyncBody,
+    config::{request::RequestConfig, RetryContext, RetryPolicy},
+    error::Error,
+    interceptor::{Context, Interceptor, InterceptorFuture},
+    redirect::RedirectOrigin,
+    request::RequestExt,
+    response::AsyncReadResponseExt,
+};
+use http::{Request, Response, StatusCode};
+use std::time::Duration;
+
+/// Initial delay to wait before retrying a request when using a retry limit.
+const INITIAL_BACKOFF: Duration = Duration::from_millis(100);
+
+/// Maximum delay to wait between retries when using a retry limit, and the
+/// longest `Retry-After` delay accepted from a server.
+const MAX_BACKOFF: Duration = Duration::from_secs(30);
+
+/// Extension containing information about any retries that were made before
+/// receiving a response.
+pub(crate) struct Retries {
+    pub(crate) count: u32,
+    pub(crate) last_error: Option<Error>,
+}
+
+/// Interceptor that implements automatic retrying of failed requests.
+pub(crate) struct RetryInterceptor;
+
+impl Interceptor for RetryInterceptor {
+    type Err = Error;
+
+    fn intercept<'a>(
+        &'a self,
+        mut request: Request<AsyncBody>,
+        ctx: Context<'a>,
+    ) -> InterceptorFuture<'a, Self::Err> {
+        Box::pin(async move {
+            // Get the retry policy for this request.
+            let policy = request
+                .extensions()
+                .get::<RequestConfig>()
+                .and_then(|config| config.retry_policy.as_ref())
+                .cloned()
+                .unwrap_or_default();
+
+            // No retry handling, just proceed normally.
+            if let RetryPolicy::None = policy {
+                return ctx.send(request).await;
+            }
+
+            let mut retries: u32 = 0;
+            let mut last_error = None;
+
+            loop {
+                // We can only retry if we are able to send the request body
+                // again. Preserve a copy of the request before sending it.
+                let retry_request = copy_request(&request);
+
+                let result = ctx.send(request).await;
+
+                let retry_request = match retry_request {
+                    Some(retry_request) => retry_request,
+                    None => return with_retries(result, retries, last_error),
+                };
+
+                let delay = {
+                    let (status, retry_after, error) = match &result {
+                        Ok(response) => {
+                            let status = response.status();
+
+                            if !status.is_client_error() && !status.is_server_error() {
+                                return with_retries(result, retries, last_error);
+                            }
+
+                            (Some(status), get_retry_after(response), None)
+                        }
+                        Err(e) => (None, None, Some(e)),
+                    };
+
+                    let retry_ctx = RetryContext {
+                        retries,
+                        method: retry_request.method(),
+                        uri: retry_request.uri(),
+                        status,
+                        retry_after,
+                        error,
+                    };
+
+                    match &policy {
+                        RetryPolicy::None => None,
+                        RetryPolicy::Limit(limit) => limit_delay(*limit, &retry_ctx),
+                        RetryPolicy::Custom(f) => f(&retry_ctx),
+                    }
+                };
+
+                let delay = match delay {
+                    Some(delay) => delay,
+                    None => return with_retries(result, retries, last_error),
+                };
+
+                match result {
+                    // Read the rest of the response so that the connection can
+                    // be reused for the next attempt.
+                    Ok(mut response) => {
+                        let _ = response.consume().await;
+                    }
+                    Err(e) => {
+                        tracing::debug!("request failed, will retry: {}", e);
+                        last_error = Some(e);
+                    }
+                }
+
+                tracing::debug!(?delay, retries, "retrying request");
+
+                if delay > Duration::from_secs(0) {
+                    crate::task::sleep(delay).await;
+                }
+
+                request = retry_request;
+                retries += 1;
+            }
+        })
+    }
+}
+
+/// Create a copy of a request so that it can be sent again, if its body can be
+/// copied.
+///
+/// Every extension that Isahc attaches to requests is copied along with it.
+/// Extensions of other types cannot be copied, since they are not required to
+/// implement `Clone`, but no interceptor that runs after this one reads them.
+fn copy_request(request: &Request<AsyncBody>) -> Option<Request<AsyncBody>> {
+    let body = request.body().try_clone()?;
+    let mut builder = request.to_builder();
+
+    if let Some(origin) = request.extensions().get::<RedirectOrigin>() {
+        builder = builder.extension(origin.clone());
+    }
+
+    builder.body(body).ok()
+}
+
+/// Determine how long to wait before retrying a failed attempt when using
+/// [`RetryPolicy::Limit`], if at all.
+fn limit_delay(limit: u32, ctx: &RetryContext<'_>) -> Option<Duration> {
+    if ctx.retries() >= limit {
+        return None;
+    }
+
+    let should_retry = match (ctx.status(), ctx.error()) {
+        (Some(status), _) => is_retryable_status(status),
+        (None, Some(error)) => error.is_network(),
+        (None, None) => false,
+    };
+
+    if !should_retry {
+        return None;
+    }
+
+    Some(
+        ctx.retry_after()
+            .unwrap_or_else(|| INITIAL_BACKOFF * 2u32.saturating_pow(ctx.retries()))
+            .min(MAX_BACKOFF),
+    )
+}
+
+fn is_retryable_status(status: StatusCode) -> bool {
+    match status {
+        StatusCode::REQUEST_TIMEOUT
+        | StatusCode::TOO_MANY_REQUESTS
+        | StatusCode::BAD_GATEWAY
+        | StatusCode::SERVICE_UNAVAILABLE
+        | StatusCode::GATEWAY_TIMEOUT => true,
+        _ => false,
+    }
+}
+
+/// Parse a `Retry-After` header specified as a number of seconds, clamped to
+/// [`MAX_BACKOFF`].
+fn get_retry_after<T>(response: &Response<T>) -> Option<Duration> {
+    response
+        .headers()
+        .get(http::header::RETRY_AFTER)?
+        .to_str()
+        .ok()?
+        .trim()
+        .parse()
+        .ok()
+        .map(|seconds| Duration::from_secs(seconds).min(MAX_BACKOFF))
+}
+
+/// Attach retry information to a response or to the error returned by the
+/// final attempt, if any retries were made.
+fn with_retries(
+    result: Result<Response<AsyncBody>, Error>,
+    count: u32,
+    last_error: Option<Error>,
+) -> Result<Response<AsyncBody>, Error> {
+    if count == 0 {
+        return result;
+    }
+
+    match result {
+        Ok(mut response) => {
+            response
+                .extensions_mut()
+                .insert(Retries { count, last_error });
+
+            Ok(response)
+        }
+        Err(e) => Err(e.with_retry_count(count)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use http::{Method, Uri};
+    use test_case::test_case;
+
+    #[test_case(0, 503, Some(100))]
+    #[test_case(1, 503, Some(200))]
+    #[test_case(2, 429, Some(400))]
+    #[test_case(3, 503, None)]
+    #[test_case(0, 500, None)]
+    #[test_case(0, 404, None)]
+    fn limit_backoff(retries: u32, status: u16, expected_ms: Option<u64>) {
+        let ctx = RetryContext {
+            retries,
+            method: &Method::GET,
+            uri: &Uri::from_static("http://localhost"),
+            status: Some(StatusCode::from_u16(status).unwrap()),
+            retry_after: None,
+            error: None,
+        };
+
+        assert_eq!(limit_delay(3, &ctx), expected_ms.map(Duration::from_millis));
+    }
+
+    #[test]
+    fn limit_honors_retry_after() {
+        let ctx = RetryContext {
+            retries: 0,
+            method: &Method::GET,
+            uri: &Uri::from_static("http://localhost"),
+            status: Some(StatusCode::SERVICE_UNAVAILABLE),
+            retry_after: Some(Duration::from_secs(2)),
+            error: None,
+        };
+
+        assert_eq!(limit_delay(3, &ctx), Some(Duration::from_secs(2)));
+    }
+
+    #[test_case("2", Some(2))]
+    #[test_case(" 5 ", Some(5))]
+    #[test_case("18446744073709551615", Some(30))]
+    #[test_case("Wed, 21 Oct 2015 07:28:00 GMT", None)]
+    fn parse_retry_after(value: &str, expected_secs: Option<u64>) {
+        let response = Response::builder()
+            .header(http::header::RETRY_AFTER, value)
+            .body(())
+            .unwrap();
+
+        assert_eq!(get_retry_after(&response), expected_secs.map(Duration::from_secs));
+    }
+}
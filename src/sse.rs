@@ -0,0 +1,372 @@
+//! Decoding of [server-sent
+//! events](https://html.spec.whatwg.org/multipage/server-sent-events.html).
+//!
+//! Servers that stream events to clients using the `text/event-stream` format
+//! send a long-lived response body made up of one event after another. An
+//! [`EventStream`] wraps such a response body and parses it into a [`Stream`]
+//! of [`Event`]s as they arrive.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use futures_lite::StreamExt;
+//! use isahc::{prelude::*, sse::EventStream, Request};
+//!
+//! # async fn run() -> Result<(), Box<dyn std::error::Error>> {
+//! let response = Request::get("https://example.org/events")
+//!     .header("Accept", "text/event-stream")
+//!     .body(())?
+//!     .send_async()
+//!     .await?;
+//!
+//! let mut events = EventStream::new(response.into_body());
+//!
+//! while let Some(event) = events.next().await {
+//!     let event = event?;
+//!     println!("{}: {}", event.event(), event.data());
+//! }
+//! # Ok(()) }
+//! ```
+//!
+//! # Availability
+//!
+//! This module is only available when the [`sse`](../index.html#sse) feature
+//! is enabled.
+
+use futures_lite::{io::AsyncRead, Stream};
+use std::{
+    io,
+    mem,
+    pin::Pin,
+    task::{Context, Poll},
+    time::Duration,
+};
+
+/// Size of the buffer used to read from the underlying reader.
+const READ_BUFFER_SIZE: usize = 8192;
+
+/// A single event received from an event stream.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Event {
+    id: Option<String>,
+    event: Option<String>,
+    data: String,
+    retry: Option<Duration>,
+}
+
+impl Event {
+    /// Get the ID of the most recent event sent by the server, if any.
+    ///
+    /// Per the specification, event IDs persist across events, so this is the
+    /// last ID set by the server at or before this event. A client that
+    /// reconnects should send this value in the `Last-Event-ID` header.
+    pub fn id(&self) -> Option<&str> {
+        self.id.as_deref()
+    }
+
+    /// Get the type of this event. This is `message` unless the server
+    /// specified a different type.
+    pub fn event(&self) -> &str {
+        self.event.as_deref().unwrap_or("message")
+    }
+
+    /// Get the data of this event. Multiple `data` lines sent for a single
+    /// event are joined together with newlines.
+    pub fn data(&self) -> &str {
+        &self.data
+    }
+
+    /// Get the reconnection time requested by the server along with this
+    /// event, if any.
+    pub fn retry(&self) -> Option<Duration> {
+        self.retry
+    }
+}
+
+/// A stream of [`Event`]s parsed from a `text/event-stream` body.
+///
+/// An event stream can be created from any asynchronous reader, but is usually
+/// created from a response body. The stream ends when the underlying reader
+/// reaches the end of its data. An event that is incomplete when the reader
+/// ends is discarded, as the specification requires.
+#[derive(Debug)]
+pub struct EventStream<R> {
+    reader: R,
+    buffer: Vec<u8>,
+    eof: bool,
+    started: bool,
+    skip_line_feed: bool,
+    last_event_id: Option<String>,
+    retry: Option<Duration>,
+    pending: Event,
+}
+
+impl<R: AsyncRead + Unpin> EventStream<R> {
+    /// Create a new event stream that reads from the given reader.
+    pub fn new(reader: R) -> Self {
+        Self {
+            reader,
+            buffer: Vec::new(),
+            eof: false,
+            started: false,
+            skip_line_feed: false,
+            last_event_id: None,
+            retry: None,
+            pending: Event::default(),
+        }
+    }
+
+    /// Get the ID of the last event received, if any.
+    pub fn last_event_id(&self) -> Option<&str> {
+        self.last_event_id.as_deref()
+    }
+
+    /// Get the most recent reconnection time requested by the server, if any.
+    pub fn retry(&self) -> Option<Duration> {
+        self.retry
+    }
+
+    /// Get the underlying reader.
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+
+    /// Remove the next complete line from the buffer, if there is one.
+    fn next_line(&mut self) -> Option<String> {
+        if self.skip_line_feed && !self.buffer.is_empty() {
+            self.skip_line_feed = false;
+
+            if self.buffer[0] == b'\n' {
+                self.buffer.remove(0);
+            }
+        }
+
+        // Strip a leading byte order mark from the stream.
+        if !self.started {
+            if self.buffer.len() < 3 && b"\xEF\xBB\xBF".starts_with(&self.buffer) && !self.eof {
+                return None;
+            }
+
+            if self.buffer.starts_with(b"\xEF\xBB\xBF") {
+                self.buffer.drain(..3);
+            }
+
+            self.started = true;
+        }
+
+        let end = self.buffer.iter().position(|&b| b == b'\r' || b == b'\n')?;
+        let mut consumed = end + 1;
+
+        if self.buffer[end] == b'\r' {
+            match self.buffer.get(end + 1) {
+                Some(b'\n') => consumed += 1,
+                Some(_) => {}
+                // We can't tell yet if this carriage return is followed by a
+                // line feed, so skip it if it is the next byte we receive.
+                None => self.skip_line_feed = true,
+            }
+        }
+
+        let line = String::from_utf8_lossy(&self.buffer[..end]).into_owned();
+        self.buffer.drain(..consumed);
+
+        Some(line)
+    }
+
+    /// Process a single line, returning an event if the line completes one.
+    fn process_line(&mut self, line: &str) -> Option<Event> {
+        if line.is_empty() {
+            let event = mem::take(&mut self.pending);
+
+            // Events without any data are not dispatched.
+            if event.data.is_empty() {
+                return None;
+            }
+
+            return Some(Event {
+                id: self.last_event_id.clone(),
+                data: event.data[..event.data.len() - 1].to_owned(),
+                ..event
+            });
+        }
+
+        // Lines beginning with a colon are comments.
+        if line.starts_with(':') {
+            return None;
+        }
+
+        let (field, value) = match line.find(':') {
+            Some(index) => {
+                let value = &line[index + 1..];
+                let value = if value.starts_with(' ') {
+                    &value[1..]
+                } else {
+                    value
+                };
+
+                (&line[..index], value)
+            }
+            None => (line, ""),
+        };
+
+        match field {
+            "event" => self.pending.event = Some(value.to_owned()),
+            "data" => {
+                // Mark the event as having data, even if the data is empty.
+                self.pending.data.push_str(value);
+                self.pending.data.push('\n');
+            }
+            "id" if !value.contains('\0') => self.last_event_id = Some(value.to_owned()),
+            "retry" if !value.is_empty() && value.bytes().all(|b| b.is_ascii_digit()) => {
+                if let Ok(millis) = value.parse() {
+                    let retry = Duration::from_millis(millis);
+                    self.retry = Some(retry);
+                    self.pending.retry = Some(retry);
+                }
+            }
+            _ => {}
+        }
+
+        None
+    }
+}
+
+impl<R: AsyncRead + Unpin> Stream for EventStream<R> {
+    type Item = io::Result<Event>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = &mut *self;
+
+        loop {
+            while let Some(line) = this.next_line() {
+                if let Some(event) = this.process_line(&line) {
+                    return Poll::Ready(Some(Ok(event)));
+                }
+            }
+
+            if this.eof {
+                return Poll::Ready(None);
+            }
+
+            let mut chunk = [0; READ_BUFFER_SIZE];
+
+            match Pin::new(&mut this.reader).poll_read(cx, &mut chunk) {
+                Poll::Ready(Ok(0)) => this.eof = true,
+                Poll::Ready(Ok(len)) => this.buffer.extend_from_slice(&chunk[..len]),
+                Poll::Ready(Err(e)) if e.kind() == io::ErrorKind::Interrupted => {}
+                Poll::Ready(Err(e)) => return Poll::Ready(Some(Err(e))),
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures_lite::{future::block_on, StreamExt};
+
+    fn parse(input: &[u8]) -> Vec<Event> {
+        block_on(
+            EventStream::new(input)
+                .map(Result::unwrap)
+                .collect::<Vec<_>>(),
+        )
+    }
+
+    fn event(data: &str) -> Event {
+        Event {
+            data: data.to_owned(),
+            ..Event::default()
+        }
+    }
+
+    #[test]
+    fn single_event() {
+        let events = parse(b"data: hello\n\n");
+
+        assert_eq!(events, vec![event("hello")]);
+        assert_eq!(events[0].event(), "message");
+    }
+
+    #[test]
+    fn multi_line_data_is_joined() {
+        assert_eq!(parse(b"data: a\ndata:b\ndata\n\n"), vec![event("a\nb\n")]);
+    }
+
+    #[test]
+    fn comments_are_ignored() {
+        assert_eq!(
+            parse(b": keepalive\n\ndata: x\n: more\n\n"),
+            vec![event("x")]
+        );
+    }
+
+    #[test]
+    fn event_type_and_id() {
+        let events = parse(b"event: update\nid: 1\ndata: a\n\ndata: b\n\nid\ndata: c\n\n");
+
+        assert_eq!(events.len(), 3);
+        assert_eq!(events[0].event(), "update");
+        assert_eq!(events[0].id(), Some("1"));
+        assert_eq!(events[1].event(), "message");
+        assert_eq!(events[1].id(), Some("1"));
+        assert_eq!(events[2].id(), Some(""));
+    }
+
+    #[test]
+    fn retry_hint() {
+        let events = parse(b"retry: 1500\ndata: a\n\nretry: bogus\ndata: b\n\n");
+
+        assert_eq!(events[0].retry(), Some(Duration::from_millis(1500)));
+        assert_eq!(events[1].retry(), None);
+    }
+
+    #[test]
+    fn mixed_line_endings() {
+        assert_eq!(
+            parse(b"data: a\r\n\r\ndata: b\r\rdata: c\n\n"),
+            vec![event("a"), event("b"), event("c")]
+        );
+    }
+
+    #[test]
+    fn carriage_return_split_across_reads() {
+        // Feed the stream one byte at a time.
+        struct Trickle<'a>(&'a [u8]);
+
+        impl AsyncRead for Trickle<'_> {
+            fn poll_read(
+                mut self: Pin<&mut Self>,
+                _cx: &mut Context<'_>,
+                buf: &mut [u8],
+            ) -> Poll<io::Result<usize>> {
+                if self.0.is_empty() {
+                    return Poll::Ready(Ok(0));
+                }
+
+                buf[0] = self.0[0];
+                self.0 = &self.0[1..];
+                Poll::Ready(Ok(1))
+            }
+        }
+
+        let events = block_on(
+            EventStream::new(Trickle(b"\xEF\xBB\xBFdata: a\r\n\r\ndata: b\r\n\r\n"))
+                .map(Result::unwrap)
+                .collect::<Vec<_>>(),
+        );
+
+        assert_eq!(events, vec![event("a"), event("b")]);
+    }
+
+    #[test]
+    fn incomplete_event_is_discarded() {
+        assert_eq!(parse(b"data: a\n\ndata: b\n"), vec![event("a")]);
+    }
+
+    #[test]
+    fn empty_data_is_dispatched() {
+        assert_eq!(parse(b"data\n\nevent: x\n\n"), vec![event("")]);
+    }
+}
@@ -0,0 +1,98 @@
+//! Summaries of completed transfers.
+
+use crate::error::Error;
+use http::{Method, StatusCode, Uri};
+use std::{fmt, sync::Arc, time::Duration};
+
+/// A summary of a single completed transfer, passed to the callback registered
+/// with [`HttpClientBuilder::on_complete`](crate::HttpClientBuilder::on_complete).
+///
+/// A summary is produced for every transfer, whether it succeeded or failed.
+/// Note that redirects and retries each cause a separate transfer, and so
+/// produce a separate summary.
+#[derive(Clone, Debug)]
+pub struct TransferSummary {
+    pub(crate) method: Method,
+    pub(crate) uri: Uri,
+    pub(crate) status: Option<StatusCode>,
+    pub(crate) bytes_uploaded: u64,
+    pub(crate) bytes_downloaded: u64,
+    pub(crate) total_time: Duration,
+    pub(crate) connection_reused: Option<bool>,
+    pub(crate) error: Option<Error>,
+}
+
+impl TransferSummary {
+    /// Get the method of the request.
+    pub fn method(&self) -> &Method {
+        &self.method
+    }
+
+    /// Get the URI the request was sent to.
+    pub fn uri(&self) -> &Uri {
+        &self.uri
+    }
+
+    /// Get the status code of the response, if a response was received.
+    pub fn status(&self) -> Option<StatusCode> {
+        self.status
+    }
+
+    /// Get the number of request body bytes sent.
+    pub fn bytes_uploaded(&self) -> u64 {
+        self.bytes_uploaded
+    }
+
+    /// Get the number of response body bytes received.
+    pub fn bytes_downloaded(&self) -> u64 {
+        self.bytes_downloaded
+    }
+
+    /// Get the total time taken by the transfer, from the start of the request
+    /// until the end of the response body.
+    pub fn total_time(&self) -> Duration {
+        self.total_time
+    }
+
+    /// Check whether the transfer reused an existing connection, if known.
+    ///
+    /// This returns `None` if the transfer failed before a connection was
+    /// established.
+    pub fn connection_reused(&self) -> Option<bool> {
+        self.connection_reused
+    }
+
+    /// Get the error the transfer failed with, if it failed.
+    pub fn error(&self) -> Option<&Error> {
+        self.error.as_ref()
+    }
+
+    /// Check whether the transfer completed successfully. A transfer with an
+    /// error status code is still considered successful.
+    pub fn is_success(&self) -> bool {
+        self.error.is_none()
+    }
+}
+
+/// A callback that is invoked with a summary of every completed transfer.
+#[derive(Clone)]
+pub(crate) struct CompletionCallback(Arc<dyn Fn(&TransferSummary) + Send + Sync>);
+
+impl CompletionCallback {
+    pub(crate) fn new<F>(f: F) -> Self
+    where
+        F: Fn(&TransferSummary) + Send + Sync + 'static,
+    {
+        CompletionCallback(Arc::new(f))
+    }
+
+    pub(crate) fn call(&self, summary: &TransferSummary) {
+        (self.0)(summary)
+    }
+}
+
+impl fmt::Debug for CompletionCallback {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("CompletionCallback(..)")
+    }
+}
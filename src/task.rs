@@ -1,6 +1,13 @@
 //! Helpers for working with tasks and futures.
 
-use std::task::Waker;
+use async_channel::Sender;
+use once_cell::sync::Lazy;
+use std::{
+    sync::{Arc, Condvar, Mutex},
+    task::Waker,
+    thread,
+    time::{Duration, Instant},
+};
 
 /// Helper methods for working with wakers.
 pub(crate) trait WakerExt {
@@ -15,3 +22,86 @@ impl WakerExt for Waker {
         waker_fn::waker_fn(move || (f)(&inner))
     }
 }
+
+/// Asynchronously wait for the given duration to elapse.
+///
+/// Since we do not depend on any particular async runtime, the delay is
+/// implemented by a single background thread shared by all sleeping tasks,
+/// which is started the first time this is called. This is only suitable for
+/// infrequent waits such as backing off between retries.
+pub(crate) async fn sleep(duration: Duration) {
+    static SLEEPER: Lazy<Option<Arc<Sleeper>>> = Lazy::new(Sleeper::spawn);
+
+    let sleeper = match SLEEPER.as_ref() {
+        Some(sleeper) => sleeper,
+        None => return,
+    };
+
+    // A duration too long to represent as a deadline will never elapse.
+    let deadline = match Instant::now().checked_add(duration) {
+        Some(deadline) => deadline,
+        None => return futures_lite::future::pending().await,
+    };
+
+    let (sender, receiver) = async_channel::bounded::<()>(1);
+
+    sleeper.add(deadline, sender);
+
+    // The channel closes once the deadline passes and the sleeper thread drops
+    // the sender.
+    let _ = receiver.recv().await;
+}
+
+/// Background thread that wakes up sleeping tasks when their deadlines pass.
+struct Sleeper {
+    /// Deadline of each sleeping task, paired with a sender that is dropped to
+    /// wake the task up.
+    timers: Mutex<Vec<(Instant, Sender<()>)>>,
+    condvar: Condvar,
+}
+
+impl Sleeper {
+    fn spawn() -> Option<Arc<Self>> {
+        let sleeper = Arc::new(Self {
+            timers: Mutex::new(Vec::new()),
+            condvar: Condvar::new(),
+        });
+
+        let result = thread::Builder::new()
+            .name(String::from("isahc-sleep"))
+            .spawn({
+                let sleeper = sleeper.clone();
+                move || sleeper.run()
+            });
+
+        match result {
+            Ok(_) => Some(sleeper),
+            Err(e) => {
+                tracing::warn!("failed to spawn sleep thread: {}", e);
+                None
+            }
+        }
+    }
+
+    fn add(&self, deadline: Instant, sender: Sender<()>) {
+        self.timers.lock().unwrap().push((deadline, sender));
+        self.condvar.notify_one();
+    }
+
+    fn run(&self) {
+        let mut timers = self.timers.lock().unwrap();
+
+        loop {
+            let now = Instant::now();
+
+            // Wake up tasks whose deadline has passed, and forget about tasks
+            // that are no longer waiting.
+            timers.retain(|(deadline, sender)| *deadline > now && !sender.is_closed());
+
+            timers = match timers.iter().map(|(deadline, _)| *deadline).min() {
+                Some(deadline) => self.condvar.wait_timeout(timers, deadline - now).unwrap().0,
+                None => self.condvar.wait(timers).unwrap(),
+            };
+        }
+    }
+}
@@ -0,0 +1,190 @@
+use crate::{
+    body::AsyncBody,
+    error::{Error, ErrorKind},
+    interceptor::{Context, Interceptor, InterceptorFuture},
+};
+use futures_lite::io::AsyncReadExt;
+use http::{HeaderMap, Method, Request, Response, StatusCode, Uri, Version};
+use std::{
+    convert::TryFrom,
+    fmt,
+    io,
+    sync::{Arc, Mutex},
+};
+
+/// A transport for an [`HttpClient`](crate::HttpClient) that returns
+/// registered responses instead of sending requests over the network.
+///
+/// Responses are registered for a request method and URI using
+/// [`MockTransport::respond`]. Whenever the client sends a request matching
+/// one, a copy of the registered response is returned. Requests that do not
+/// match any registered response fail with a
+/// [`ConnectionFailed`](crate::error::ErrorKind::ConnectionFailed) error.
+///
+/// Every request received is recorded along with its body, and can be
+/// inspected afterwards using [`MockTransport::requests`].
+///
+/// Only the network is replaced; everything else the client does, such as
+/// following redirects, adding default headers, or handling cookies, still
+/// applies. Cloning a transport returns a new handle to the same transport,
+/// so keep a clone around to inspect the requests after giving it to a client.
+///
+/// # Examples
+///
+/// ```
+/// use isahc::{prelude::*, testing::MockTransport, HttpClient, Response};
+///
+/// let mock = MockTransport::new();
+/// mock.respond("GET", "https://example.org/hello", Response::new("hello world"));
+///
+/// let client = HttpClient::builder()
+///     .mock_transport(mock.clone())
+///     .build()?;
+///
+/// let mut response = client.get("https://example.org/hello")?;
+///
+/// assert_eq!(response.text()?, "hello world");
+/// assert_eq!(mock.requests()[0].uri(), "https://example.org/hello");
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+#[derive(Clone, Default)]
+pub struct MockTransport {
+    shared: Arc<Mutex<Shared>>,
+}
+
+#[derive(Default)]
+struct Shared {
+    responses: Vec<MockResponse>,
+    requests: Vec<Request<Vec<u8>>>,
+}
+
+struct MockResponse {
+    method: Method,
+    uri: Uri,
+    status: StatusCode,
+    version: Version,
+    headers: HeaderMap,
+    body: Vec<u8>,
+}
+
+impl MockTransport {
+    /// Create a new mock transport with no registered responses.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register a response to return for requests with the given method and
+    /// URI.
+    ///
+    /// The response is returned every time a matching request is sent. If
+    /// more than one response is registered for the same method and URI, the
+    /// one registered last is used.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the given method or URI is not valid.
+    pub fn respond<M, U, B>(&self, method: M, uri: U, response: Response<B>) -> &Self
+    where
+        Method: TryFrom<M>,
+        <Method as TryFrom<M>>::Error: fmt::Debug,
+        Uri: TryFrom<U>,
+        <Uri as TryFrom<U>>::Error: fmt::Debug,
+        B: Into<Vec<u8>>,
+    {
+        let (parts, body) = response.into_parts();
+
+        self.shared.lock().unwrap().responses.push(MockResponse {
+            method: Method::try_from(method).expect("invalid method"),
+            uri: Uri::try_from(uri).expect("invalid URI"),
+            status: parts.status,
+            version: parts.version,
+            headers: parts.headers,
+            body: body.into(),
+        });
+
+        self
+    }
+
+    /// Get a copy of all requests received so far, in the order they were
+    /// sent, including their bodies.
+    pub fn requests(&self) -> Vec<Request<Vec<u8>>> {
+        self.shared
+            .lock()
+            .unwrap()
+            .requests
+            .iter()
+            .map(super::clone_request)
+            .collect()
+    }
+}
+
+impl fmt::Debug for MockTransport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let shared = self.shared.lock().unwrap();
+
+        f.debug_struct("MockTransport")
+            .field("responses", &shared.responses.len())
+            .field("requests", &shared.requests.len())
+            .finish()
+    }
+}
+
+impl MockResponse {
+    fn matches(&self, method: &Method, uri: &Uri) -> bool {
+        self.method == method
+            && self.uri.scheme() == uri.scheme()
+            && self.uri.authority() == uri.authority()
+            && self.uri.path() == uri.path()
+            && self.uri.query() == uri.query()
+    }
+
+    fn to_response(&self) -> Response<AsyncBody> {
+        let mut response = Response::new(AsyncBody::from(self.body.clone()));
+        *response.status_mut() = self.status;
+        *response.version_mut() = self.version;
+        *response.headers_mut() = self.headers.clone();
+        response
+    }
+}
+
+/// Interceptor that answers requests using a mock transport. This must be the
+/// innermost interceptor, since it never passes requests on.
+pub(crate) struct MockInterceptor(pub(crate) MockTransport);
+
+impl Interceptor for MockInterceptor {
+    type Err = Error;
+
+    fn intercept<'a>(
+        &'a self,
+        request: Request<AsyncBody>,
+        _ctx: Context<'a>,
+    ) -> InterceptorFuture<'a, Self::Err> {
+        Box::pin(async move {
+            let (parts, mut body) = request.into_parts();
+            let mut bytes = Vec::new();
+            body.read_to_end(&mut bytes).await?;
+
+            let mut shared = self.0.shared.lock().unwrap();
+
+            let response = shared
+                .responses
+                .iter()
+                .rev()
+                .find(|response| response.matches(&parts.method, &parts.uri))
+                .map(MockResponse::to_response)
+                .ok_or_else(|| {
+                    Error::new(
+                        ErrorKind::ConnectionFailed,
+                        io::Error::new(
+                            io::ErrorKind::ConnectionRefused,
+                            format!("no mock response for {} {}", parts.method, parts.uri),
+                        ),
+                    )
+                });
+
+            shared.requests.push(Request::from_parts(parts, bytes));
+
+            response
+        })
+    }
+}
@@ -0,0 +1,36 @@
+//! Utilities for testing code that uses Isahc.
+//!
+//! Code that sends requests using an [`HttpClient`](crate::HttpClient) can be
+//! tested without a real server by building the client with a
+//! [`MockTransport`]. The client behaves as usual, except that requests are
+//! answered with responses registered ahead of time instead of being sent over
+//! the network.
+//!
+//! To test against a real server instead, start a [`TestServer`] on the
+//! loopback interface and send requests to its
+//! [`endpoint`](TestServer::endpoint). This exercises the whole client,
+//! including the network layer.
+//!
+//! # Availability
+//!
+//! This module is only available when the [`testing`](../index.html#testing)
+//! feature is enabled.
+
+use http::Request;
+
+mod mock;
+mod server;
+
+pub use self::{mock::MockTransport, server::TestServer};
+
+pub(crate) use self::mock::MockInterceptor;
+
+/// Make a copy of a recorded request.
+fn clone_request(request: &Request<Vec<u8>>) -> Request<Vec<u8>> {
+    let mut clone = Request::new(request.body().clone());
+    *clone.method_mut() = request.method().clone();
+    *clone.uri_mut() = request.uri().clone();
+    *clone.version_mut() = request.version();
+    *clone.headers_mut() = request.headers().clone();
+    clone
+}
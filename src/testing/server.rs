@@ -0,0 +1,227 @@
+use http::{HeaderMap, HeaderValue, Method, Request, Response, StatusCode, Version};
+use std::{
+    fmt,
+    io::Cursor,
+    net::SocketAddr,
+    panic::{self, AssertUnwindSafe},
+    sync::{Arc, Mutex},
+    thread,
+};
+
+type Handler = dyn Fn(&Request<Vec<u8>>) -> Response<Vec<u8>> + Send + Sync;
+
+/// A local HTTP server for testing code that sends requests using Isahc.
+///
+/// Unlike [`MockTransport`](super::MockTransport), a test server listens on a
+/// real TCP socket on the loopback interface, so requests go through the
+/// entire client including the network layer. Every request received is passed
+/// to a handler function that decides how to respond, and is recorded so that
+/// it can be inspected afterwards.
+///
+/// Each request is handled on its own thread. If the handler panics, the
+/// server responds with a `500 Internal Server Error` instead. The server shuts
+/// down when it is dropped.
+///
+/// Only HTTP/1.x is supported.
+///
+/// # Examples
+///
+/// ```
+/// use isahc::{prelude::*, testing::TestServer, Response};
+///
+/// let server = TestServer::new(|request| {
+///     Response::new(format!("you asked for {}", request.uri()))
+/// });
+///
+/// let mut response = isahc::get(format!("{}hello", server.endpoint()))?;
+///
+/// assert_eq!(response.text()?, "you asked for /hello");
+/// server.assert_requests_received(1);
+/// server.assert_received(|request| request.method() == "GET");
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+pub struct TestServer {
+    inner: Arc<Inner>,
+}
+
+struct Inner {
+    server: tiny_http::Server,
+    handler: Box<Handler>,
+    requests: Mutex<Vec<Request<Vec<u8>>>>,
+}
+
+impl TestServer {
+    /// Start a new test server that responds to requests using the given
+    /// handler function.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the server cannot bind to a local port.
+    pub fn new<F, B>(handler: F) -> Self
+    where
+        F: Fn(&Request<Vec<u8>>) -> Response<B> + Send + Sync + 'static,
+        B: Into<Vec<u8>>,
+    {
+        let server = tiny_http::Server::http("127.0.0.1:0").expect("failed to start test server");
+
+        let inner = Arc::new(Inner {
+            server,
+            handler: Box::new(move |request| handler(request).map(Into::into)),
+            requests: Mutex::new(Vec::new()),
+        });
+
+        thread::spawn({
+            let inner = inner.clone();
+
+            move || {
+                for request in inner.server.incoming_requests() {
+                    let inner = inner.clone();
+
+                    thread::spawn(move || inner.handle(request));
+                }
+            }
+        });
+
+        Self { inner }
+    }
+
+    /// Get the socket address the server is listening on.
+    pub fn addr(&self) -> SocketAddr {
+        self.inner.server.server_addr()
+    }
+
+    /// Get the base URL of the server, such as `http://127.0.0.1:8080/`.
+    pub fn endpoint(&self) -> String {
+        format!("http://{}/", self.addr())
+    }
+
+    /// Get the number of requests received so far.
+    pub fn requests_received(&self) -> usize {
+        self.inner.requests.lock().unwrap().len()
+    }
+
+    /// Get a copy of all requests received so far, in the order they were
+    /// received, including their bodies.
+    ///
+    /// The URI of each request is exactly as sent by the client, which is
+    /// usually only a path and query.
+    pub fn requests(&self) -> Vec<Request<Vec<u8>>> {
+        self.inner
+            .requests
+            .lock()
+            .unwrap()
+            .iter()
+            .map(super::clone_request)
+            .collect()
+    }
+
+    /// Assert that exactly the given number of requests have been received.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a different number of requests have been received.
+    #[track_caller]
+    pub fn assert_requests_received(&self, expected: usize) {
+        let received = self.requests_received();
+
+        assert_eq!(
+            received, expected,
+            "expected {} requests to be received, but {} were received",
+            expected, received
+        );
+    }
+
+    /// Assert that at least one request matching the given predicate has been
+    /// received.
+    ///
+    /// # Panics
+    ///
+    /// Panics if none of the requests received match the predicate.
+    #[track_caller]
+    pub fn assert_received<F>(&self, predicate: F)
+    where
+        F: Fn(&Request<Vec<u8>>) -> bool,
+    {
+        let requests = self.inner.requests.lock().unwrap();
+
+        if !requests.iter().any(predicate) {
+            panic!(
+                "none of the {} requests received matched the predicate",
+                requests.len()
+            );
+        }
+    }
+}
+
+impl Drop for TestServer {
+    fn drop(&mut self) {
+        // Stop the accept loop, which releases the last reference to the server
+        // and closes the socket.
+        self.inner.server.unblock();
+    }
+}
+
+impl fmt::Debug for TestServer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TestServer")
+            .field("addr", &self.addr())
+            .field("requests_received", &self.requests_received())
+            .finish()
+    }
+}
+
+impl Inner {
+    fn handle(&self, mut raw: tiny_http::Request) {
+        let mut body = Vec::new();
+
+        if raw.as_reader().read_to_end(&mut body).is_err() {
+            return;
+        }
+
+        let mut request = Request::new(body);
+        *request.method_mut() =
+            Method::from_bytes(raw.method().as_str().as_bytes()).unwrap_or_default();
+        *request.uri_mut() = raw.url().parse().unwrap_or_default();
+        *request.version_mut() = match raw.http_version() {
+            tiny_http::HTTPVersion(1, 0) => Version::HTTP_10,
+            _ => Version::HTTP_11,
+        };
+        *request.headers_mut() = raw
+            .headers()
+            .iter()
+            .filter_map(|header| {
+                Some((
+                    header.field.as_str().as_str().parse().ok()?,
+                    HeaderValue::from_bytes(header.value.as_bytes()).ok()?,
+                ))
+            })
+            .collect::<HeaderMap>();
+
+        let response = panic::catch_unwind(AssertUnwindSafe(|| (self.handler)(&request)))
+            .unwrap_or_else(|_| {
+                let mut response = Response::new(Vec::new());
+                *response.status_mut() = StatusCode::INTERNAL_SERVER_ERROR;
+                response
+            });
+
+        self.requests.lock().unwrap().push(request);
+
+        let (parts, body) = response.into_parts();
+        let headers = parts
+            .headers
+            .iter()
+            .filter_map(|(name, value)| {
+                tiny_http::Header::from_bytes(name.as_str().as_bytes(), value.as_bytes()).ok()
+            })
+            .collect();
+        let len = body.len();
+
+        let _ = raw.respond(tiny_http::Response::new(
+            parts.status.as_u16().into(),
+            headers,
+            Cursor::new(body),
+            Some(len),
+            None,
+        ));
+    }
+}
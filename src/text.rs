@@ -3,7 +3,7 @@
 #![cfg(feature = "text-decoding")]
 
 use crate::headers::HasHeaders;
-use encoding_rs::{CoderResult, Encoding};
+use encoding_rs::{CoderResult, DecoderResult, Encoding};
 use futures_lite::io::{AsyncRead, AsyncReadExt};
 use http::Response;
 use std::io;
@@ -28,7 +28,7 @@ macro_rules! decode_reader {
             unread = decoder.push(&buf[..unread + len]).len();
         }
 
-        Ok(decoder.finish(&buf[..unread]))
+        decoder.finish(&buf[..unread])
     }};
 }
 
@@ -44,6 +44,12 @@ pub(crate) struct Decoder {
 
     /// The output string that characters are accumulated to.
     output: String,
+
+    /// If true, fail on malformed input instead of replacing it.
+    strict: bool,
+
+    /// Set if malformed input was found while decoding in strict mode.
+    malformed: bool,
 }
 
 impl Decoder {
@@ -52,11 +58,34 @@ impl Decoder {
         Self {
             decoder: encoding.new_decoder(),
             output: String::new(),
+            strict: false,
+            malformed: false,
         }
     }
 
     /// Create a new encoder suitable for decoding the given response.
     pub(crate) fn for_response<T>(response: &Response<T>) -> Self {
+        Self::for_response_or(response, encoding_rs::UTF_8)
+    }
+
+    /// Create a new encoder suitable for decoding the given response, using the
+    /// encoding with the given label if the response does not specify one.
+    ///
+    /// Returns an error if the label is not a known encoding.
+    pub(crate) fn for_response_with_default<T>(
+        response: &Response<T>,
+        label: &str,
+    ) -> io::Result<Self> {
+        match Encoding::for_label(label.as_bytes()) {
+            Some(encoding) => Ok(Self::for_response_or(response, encoding)),
+            None => Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("unknown encoding '{}'", label),
+            )),
+        }
+    }
+
+    fn for_response_or<T>(response: &Response<T>, default: &'static Encoding) -> Self {
         if let Some(content_type) = response
             .content_type()
             .and_then(|header| header.parse::<mime::Mime>().ok())
@@ -67,12 +96,29 @@ impl Decoder {
                 {
                     return Self::new(encoding);
                 } else {
-                    tracing::warn!("unknown encoding '{}', falling back to UTF-8", charset);
+                    tracing::warn!(
+                        "unknown encoding '{}', falling back to {}",
+                        charset,
+                        default.name()
+                    );
                 }
             }
         }
 
-        Self::new(encoding_rs::UTF_8)
+        Self::new(default)
+    }
+
+    /// Fail with an error on malformed input instead of replacing it with
+    /// replacement characters.
+    pub(crate) fn strict(mut self) -> Self {
+        self.strict = true;
+        self
+    }
+
+    /// Check if this decoder is decoding UTF-8 text.
+    #[cfg(feature = "json")]
+    pub(crate) fn is_utf8(&self) -> bool {
+        self.decoder.encoding() == encoding_rs::UTF_8
     }
 
     /// Consume this decoder to decode text from a given synchronous reader.
@@ -96,12 +142,30 @@ impl Decoder {
 
     /// Mark the stream as complete and finish the decoding process, returning
     /// the resulting string.
-    pub(crate) fn finish(mut self, buf: &[u8]) -> String {
+    ///
+    /// In strict mode, an error is returned if any malformed input was found.
+    pub(crate) fn finish(mut self, buf: &[u8]) -> io::Result<String> {
         self.decode(buf, true);
-        self.output
+
+        if self.malformed {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("stream did not contain valid {}", self.decoder.encoding().name()),
+            ));
+        }
+
+        Ok(self.output)
+    }
+
+    fn decode<'b>(&mut self, buf: &'b [u8], last: bool) -> &'b [u8] {
+        if self.strict {
+            self.decode_without_replacement(buf, last)
+        } else {
+            self.decode_with_replacement(buf, last)
+        }
     }
 
-    fn decode<'b>(&mut self, mut buf: &'b [u8], last: bool) -> &'b [u8] {
+    fn decode_with_replacement<'b>(&mut self, mut buf: &'b [u8], last: bool) -> &'b [u8] {
         loop {
             let (result, consumed, _) = self.decoder.decode_to_string(buf, &mut self.output, last);
             buf = &buf[consumed..];
@@ -121,6 +185,36 @@ impl Decoder {
 
         buf
     }
+
+    fn decode_without_replacement<'b>(&mut self, mut buf: &'b [u8], last: bool) -> &'b [u8] {
+        // Once malformed input is found the result is an error, so there's no
+        // point in decoding the rest.
+        if self.malformed {
+            return &[];
+        }
+
+        loop {
+            let (result, consumed) = self
+                .decoder
+                .decode_to_string_without_replacement(buf, &mut self.output, last);
+            buf = &buf[consumed..];
+
+            match result {
+                DecoderResult::InputEmpty => break,
+                DecoderResult::OutputFull => self.output.reserve(
+                    self.decoder
+                        .max_utf8_buffer_length_without_replacement(buf.len())
+                        .unwrap(),
+                ),
+                DecoderResult::Malformed(..) => {
+                    self.malformed = true;
+                    return &[];
+                }
+            }
+        }
+
+        buf
+    }
 }
 
 #[cfg(test)]
@@ -136,7 +230,7 @@ mod tests {
 
         assert_eq!(decoder.push(b"hello"), b"");
         assert_eq!(decoder.push(b" "), b"");
-        assert_eq!(decoder.finish(b"world"), "hello world");
+        assert_eq!(decoder.finish(b"world").unwrap(), "hello world");
     }
 
     #[test]
@@ -148,6 +242,35 @@ mod tests {
             decoder.push(&[byte]);
         }
 
-        assert_eq!(decoder.finish(&[]), "hello world!");
+        assert_eq!(decoder.finish(&[]).unwrap(), "hello world!");
+    }
+
+    #[test]
+    fn malformed_input_is_replaced() {
+        let mut decoder = Decoder::new(encoding_rs::UTF_8);
+
+        decoder.push(b"hello \xFF");
+
+        assert_eq!(decoder.finish(b"world").unwrap(), "hello \u{FFFD}world");
+    }
+
+    #[test]
+    fn malformed_input_is_an_error_in_strict_mode() {
+        let mut decoder = Decoder::new(encoding_rs::UTF_8).strict();
+
+        decoder.push(b"hello \xFF");
+
+        let error = decoder.finish(b"world").unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn strict_mode_handles_characters_split_across_pushes() {
+        let mut decoder = Decoder::new(encoding_rs::UTF_8).strict();
+        let bytes = "caf\u{e9}".as_bytes();
+
+        let unread = decoder.push(&bytes[..4]).to_vec();
+
+        assert_eq!(decoder.finish(&[&unread[..], &bytes[4..]].concat()).unwrap(), "caf\u{e9}");
     }
 }
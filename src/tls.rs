@@ -0,0 +1,110 @@
+//! Information about the TLS sessions used by requests.
+
+use crate::config::TlsVersion;
+use curl_sys::CURL;
+
+/// Information about the TLS session a response was received over, such as
+/// the protocol version and cipher suite negotiated with the server.
+///
+/// This can be used to audit the security of connections after the fact, for
+/// example to log or reject responses received over a connection using an
+/// outdated protocol version. Use
+/// [`ResponseExt::tls_info`](crate::ResponseExt::tls_info) to get the
+/// information for a response.
+///
+/// This is only available if the TLS engine in use supports it, which is
+/// currently only the case for OpenSSL when libcurl is statically linked using
+/// the `static-curl` feature on Unix.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TlsInfo {
+    version: TlsVersion,
+    cipher: String,
+}
+
+impl TlsInfo {
+    /// Get the version of the TLS protocol that was negotiated.
+    pub fn version(&self) -> TlsVersion {
+        self.version
+    }
+
+    /// Get the name of the cipher suite that was negotiated, as named by the
+    /// TLS engine, such as `TLS_AES_256_GCM_SHA384` or
+    /// `ECDHE-RSA-AES128-GCM-SHA256`.
+    pub fn cipher(&self) -> &str {
+        &self.cipher
+    }
+
+    /// Get information about the TLS session of the connection currently used
+    /// by the given curl handle, if any.
+    ///
+    /// The session is only accessible while the connection is in use by the
+    /// handle, so this must be called during the transfer.
+    #[cfg(all(unix, feature = "static-curl"))]
+    #[allow(unsafe_code)]
+    pub(crate) fn from_handle(handle: *mut CURL) -> Option<Self> {
+        use std::{
+            ffi::CStr,
+            os::raw::{c_int, c_void},
+            ptr,
+        };
+
+        /// Get a pointer to the internal TLS session of the connection, which
+        /// is not yet exposed by the curl crate. Pointer values share their
+        /// info type with lists.
+        const CURLINFO_TLS_SSL_PTR: curl_sys::CURLINFO = curl_sys::CURLINFO_SLIST + 45;
+
+        const CURLSSLBACKEND_OPENSSL: c_int = 1;
+
+        #[repr(C)]
+        struct TlsSessionInfo {
+            backend: c_int,
+            internals: *mut c_void,
+        }
+
+        if handle.is_null() {
+            return None;
+        }
+
+        let mut info: *const TlsSessionInfo = ptr::null();
+
+        unsafe {
+            if curl_sys::curl_easy_getinfo(handle, CURLINFO_TLS_SSL_PTR, &mut info)
+                != curl_sys::CURLE_OK
+                || info.is_null()
+                || (*info).backend != CURLSSLBACKEND_OPENSSL
+                || (*info).internals.is_null()
+            {
+                return None;
+            }
+
+            // When using OpenSSL, the internals are a pointer to the `SSL`
+            // struct of the connection.
+            let ssl = (*info).internals as *const openssl_sys::SSL;
+
+            let version = match CStr::from_ptr(openssl_sys::SSL_get_version(ssl)).to_bytes() {
+                b"TLSv1" => TlsVersion::V1_0,
+                b"TLSv1.1" => TlsVersion::V1_1,
+                b"TLSv1.2" => TlsVersion::V1_2,
+                b"TLSv1.3" => TlsVersion::V1_3,
+                _ => return None,
+            };
+
+            let cipher = openssl_sys::SSL_get_current_cipher(ssl);
+
+            if cipher.is_null() {
+                return None;
+            }
+
+            let cipher = CStr::from_ptr(openssl_sys::SSL_CIPHER_get_name(cipher))
+                .to_string_lossy()
+                .into_owned();
+
+            Some(Self { version, cipher })
+        }
+    }
+
+    #[cfg(not(all(unix, feature = "static-curl")))]
+    pub(crate) fn from_handle(_handle: *mut CURL) -> Option<Self> {
+        None
+    }
+}
@@ -32,6 +32,105 @@ fn basic_auth_sends_authorization_header() {
         .expect_header("authorization", "Basic Y2xhcms6cXVlcnR5");
 }
 
+#[test]
+fn basic_auth_helper_sends_authorization_header() {
+    let m = mock!();
+
+    Request::get(m.url())
+        .basic_auth("clark", "querty")
+        .body(())
+        .unwrap()
+        .send()
+        .unwrap();
+
+    m.request()
+        .expect_header("authorization", "Basic Y2xhcms6cXVlcnR5");
+}
+
+#[test]
+fn basic_auth_with_empty_password() {
+    let m = mock!();
+
+    Request::get(m.url())
+        .basic_auth("clark", "")
+        .body(())
+        .unwrap()
+        .send()
+        .unwrap();
+
+    m.request().expect_header("authorization", "Basic Y2xhcms6");
+}
+
+#[test]
+fn basic_auth_from_client() {
+    let m = mock!();
+
+    let client = isahc::HttpClient::builder()
+        .basic_auth("clark", "querty")
+        .build()
+        .unwrap();
+
+    client.get(m.url()).unwrap();
+
+    m.request()
+        .expect_header("authorization", "Basic Y2xhcms6cXVlcnR5");
+}
+
+#[test]
+fn digest_auth_responds_to_challenge() {
+    let m = mock! {
+        #0 => {
+            status: 401,
+            headers {
+                "WWW-Authenticate": r#"Digest realm="isahc", nonce="dcd98b7102dd2f0e8b11d0f600bfb0c093", qop="auth""#,
+            }
+        },
+        _ => {
+            status: 200,
+        },
+    };
+
+    let response = Request::get(m.url())
+        .authentication(Authentication::digest())
+        .credentials(Credentials::new("clark", "querty"))
+        .body(())
+        .unwrap()
+        .send()
+        .unwrap();
+
+    assert_eq!(response.status(), 200);
+    assert_eq!(m.requests_received(), 2);
+
+    // Digest credentials are never sent up front.
+    assert_eq!(m.request().get_header("authorization").count(), 0);
+}
+
+#[test]
+fn ntlm_auth_responds_to_challenge() {
+    let m = mock! {
+        #0 => {
+            status: 401,
+            headers {
+                "WWW-Authenticate": "NTLM",
+            }
+        },
+        _ => {
+            status: 200,
+        },
+    };
+
+    let response = Request::get(m.url())
+        .authentication(Authentication::ntlm())
+        .credentials(Credentials::new("clark", "querty"))
+        .body(())
+        .unwrap()
+        .send()
+        .unwrap();
+
+    assert_eq!(response.status(), 200);
+    assert_eq!(m.requests_received(), 2);
+}
+
 #[cfg(feature = "spnego")]
 #[test]
 fn negotiate_auth_exists() {
@@ -73,3 +172,78 @@ fn negotiate_on_windows_provides_a_token() {
     m.request()
         .expect_header_regex("authorization", r"Negotiate \w+=*");
 }
+
+#[test]
+fn bearer_auth_sends_authorization_header() {
+    let m = mock!();
+
+    Request::get(m.url())
+        .bearer_auth("s3cr3t")
+        .body(())
+        .unwrap()
+        .send()
+        .unwrap();
+
+    m.request().expect_header("authorization", "Bearer s3cr3t");
+}
+
+#[test]
+fn bearer_auth_from_client() {
+    let m = mock!();
+
+    let client = isahc::HttpClient::builder()
+        .bearer_auth("s3cr3t")
+        .build()
+        .unwrap();
+
+    client.get(m.url()).unwrap();
+
+    m.request().expect_header("authorization", "Bearer s3cr3t");
+}
+
+#[test]
+fn bearer_auth_does_not_override_explicit_authorization_header() {
+    let m = mock!();
+
+    let client = isahc::HttpClient::builder()
+        .bearer_auth("s3cr3t")
+        .build()
+        .unwrap();
+
+    client
+        .send(
+            Request::get(m.url())
+                .header("Authorization", "Basic Y2xhcms6cXVlcnR5")
+                .body(())
+                .unwrap(),
+        )
+        .unwrap();
+
+    assert_eq!(m.request().get_header("authorization").count(), 1);
+    m.request()
+        .expect_header("authorization", "Basic Y2xhcms6cXVlcnR5");
+}
+
+#[test]
+fn bearer_auth_is_not_sent_when_redirecting_to_another_host() {
+    let m2 = mock!();
+    let location = m2.url();
+
+    let m1 = mock! {
+        status: 301,
+        headers {
+            "Location": location,
+        }
+    };
+
+    Request::get(m1.url())
+        .redirect_policy(isahc::config::RedirectPolicy::Follow)
+        .bearer_auth("s3cr3t")
+        .body(())
+        .unwrap()
+        .send()
+        .unwrap();
+
+    m1.request().expect_header("authorization", "Bearer s3cr3t");
+    assert_eq!(m2.request().get_header("authorization").count(), 0);
+}
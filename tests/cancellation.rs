@@ -0,0 +1,230 @@
+use futures_lite::{
+    future::{block_on, poll_once},
+    io::{copy, sink},
+};
+use isahc::{error::ErrorKind, prelude::*, CancellationToken, Request};
+use std::{
+    io::{Read, Write},
+    net::TcpListener,
+    sync::mpsc,
+    thread,
+    time::Duration,
+};
+use testserver::mock;
+
+#[macro_use]
+mod utils;
+
+/// Start a server that accepts a single connection, optionally writes a
+/// partial response, and then stalls. Returns the URL of the server and a
+/// receiver that is notified once the client closes the connection.
+fn serve_stalled_response(response: &'static [u8]) -> (String, mpsc::Receiver<()>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    let (tx, rx) = mpsc::channel();
+
+    thread::spawn(move || {
+        let mut stream = listener.accept().unwrap().0;
+        let mut request = Vec::new();
+        let mut buf = [0; 1024];
+
+        while !request.ends_with(b"\r\n\r\n") {
+            let len = stream.read(&mut buf).unwrap();
+            request.extend_from_slice(&buf[..len]);
+        }
+
+        stream.write_all(response).unwrap();
+
+        // Wait for the client to hang up.
+        while let Ok(len) = stream.read(&mut buf) {
+            if len == 0 {
+                break;
+            }
+        }
+
+        let _ = tx.send(());
+    });
+
+    (url, rx)
+}
+
+/// Start a server that responds to a single request with an endless response
+/// body. Returns the URL of the server and a receiver that is notified once the
+/// client stops receiving the body.
+fn serve_endless_response() -> (String, mpsc::Receiver<()>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    let (tx, rx) = mpsc::channel();
+
+    thread::spawn(move || {
+        let mut stream = listener.accept().unwrap().0;
+        let mut request = Vec::new();
+        let mut buf = [0; 1024];
+
+        while !request.ends_with(b"\r\n\r\n") {
+            let len = stream.read(&mut buf).unwrap();
+            request.extend_from_slice(&buf[..len]);
+        }
+
+        let chunk = [b'a'; 8192];
+
+        if stream
+            .write_all(b"HTTP/1.1 200 OK\r\ncontent-length: 1000000000000\r\n\r\n")
+            .is_ok()
+        {
+            while stream.write_all(&chunk).is_ok() {}
+        }
+
+        let _ = tx.send(());
+    });
+
+    (url, rx)
+}
+
+fn cancel_after(token: &CancellationToken, delay: Duration) {
+    let token = token.clone();
+
+    thread::spawn(move || {
+        thread::sleep(delay);
+        token.cancel();
+    });
+}
+
+#[test]
+fn dropping_response_future_aborts_transfer() {
+    let (url, closed) = serve_stalled_response(b"");
+
+    let mut future = isahc::get_async(url);
+
+    // Give the request a chance to be sent.
+    assert!(block_on(poll_once(&mut future)).is_none());
+    thread::sleep(Duration::from_millis(100));
+
+    drop(future);
+
+    closed
+        .recv_timeout(Duration::from_secs(5))
+        .expect("connection was not closed");
+}
+
+#[test]
+fn dropping_response_during_large_download_aborts_transfer() {
+    let (url, stopped) = serve_endless_response();
+
+    let mut response = isahc::get(url).unwrap();
+    let mut buf = [0; 65536];
+
+    response.body_mut().read_exact(&mut buf).unwrap();
+
+    drop(response);
+
+    stopped
+        .recv_timeout(Duration::from_secs(5))
+        .expect("server kept sending the response body");
+}
+
+#[test]
+fn dropping_response_future_during_large_download_aborts_transfer() {
+    let (url, stopped) = serve_endless_response();
+
+    // Download the body as part of the future, so that the future is dropped
+    // mid-transfer.
+    let mut future = Box::pin(async move {
+        let mut response = isahc::get_async(url).await.unwrap();
+        copy(response.body_mut(), &mut sink()).await
+    });
+
+    for _ in 0..10 {
+        assert!(block_on(poll_once(&mut future)).is_none());
+        thread::sleep(Duration::from_millis(20));
+    }
+
+    drop(future);
+
+    stopped
+        .recv_timeout(Duration::from_secs(5))
+        .expect("server kept sending the response body");
+}
+
+#[test]
+fn cancelling_token_aborts_request_in_flight() {
+    let (url, closed) = serve_stalled_response(b"");
+    let token = CancellationToken::new();
+
+    cancel_after(&token, Duration::from_millis(100));
+
+    let result = Request::get(url)
+        .cancellation_token(token.clone())
+        .body(())
+        .unwrap()
+        .send();
+
+    assert_matches!(result, Err(e) if e == ErrorKind::Cancelled);
+    assert!(token.is_cancelled());
+
+    closed
+        .recv_timeout(Duration::from_secs(5))
+        .expect("connection was not closed");
+}
+
+#[test]
+fn cancelling_token_aborts_response_body() {
+    let (url, closed) =
+        serve_stalled_response(b"HTTP/1.1 200 OK\r\ncontent-length: 100\r\n\r\nhello");
+    let token = CancellationToken::new();
+
+    let mut response = Request::get(url)
+        .cancellation_token(token.clone())
+        .body(())
+        .unwrap()
+        .send()
+        .unwrap();
+
+    cancel_after(&token, Duration::from_millis(100));
+
+    let mut body = Vec::new();
+    let error = response.body_mut().read_to_end(&mut body).unwrap_err();
+
+    assert_eq!(isahc::Error::from(error), ErrorKind::Cancelled);
+
+    closed
+        .recv_timeout(Duration::from_secs(5))
+        .expect("connection was not closed");
+}
+
+#[test]
+fn request_with_cancelled_token_is_not_sent() {
+    let m = mock!();
+    let token = CancellationToken::new();
+
+    token.cancel();
+
+    let result = Request::get(m.url())
+        .cancellation_token(token)
+        .body(())
+        .unwrap()
+        .send();
+
+    assert_matches!(result, Err(e) if e == ErrorKind::Cancelled);
+    assert_eq!(m.requests_received(), 0);
+}
+
+#[test]
+fn client_token_can_be_used_by_many_requests() {
+    let m = mock!();
+    let token = CancellationToken::new();
+
+    let client = isahc::HttpClient::builder()
+        .cancellation_token(token.clone())
+        .build()
+        .unwrap();
+
+    for _ in 0..5 {
+        client.get(m.url()).unwrap();
+    }
+
+    token.cancel();
+
+    assert_matches!(client.get(m.url()), Err(e) if e == ErrorKind::Cancelled);
+    assert_eq!(m.requests_received(), 5);
+}
@@ -0,0 +1,222 @@
+use futures_lite::future::block_on;
+use isahc::{error::ErrorKind, prelude::*, HttpClient};
+use std::{
+    net::TcpListener,
+    thread,
+    time::{Duration, Instant},
+};
+use testserver::mock;
+
+#[macro_use]
+mod utils;
+
+#[test]
+fn requests_after_close_return_error() {
+    let m = mock!();
+
+    let client = HttpClient::new().unwrap();
+    let clone = client.clone();
+
+    client.get(m.url()).unwrap();
+    client.close(Duration::from_secs(1));
+
+    assert_matches!(clone.get(m.url()), Err(e) if e == ErrorKind::ClientClosed);
+    assert_eq!(m.requests_received(), 1);
+}
+
+#[test]
+fn close_waits_for_in_flight_requests() {
+    let m = mock! {
+        delay: 500ms,
+        body: "hello world",
+    };
+
+    let client = HttpClient::new().unwrap();
+
+    let handle = thread::spawn({
+        let client = client.clone();
+        let url = m.url();
+
+        move || client.get(url).unwrap().text().unwrap()
+    });
+
+    // Give the request a chance to start.
+    thread::sleep(Duration::from_millis(100));
+
+    client.close(Duration::from_secs(5));
+
+    assert_eq!(handle.join().unwrap(), "hello world");
+}
+
+#[test]
+fn close_aborts_in_flight_requests_after_timeout() {
+    let m = mock! {
+        delay: 5s,
+    };
+
+    let client = HttpClient::new().unwrap();
+
+    let handle = thread::spawn({
+        let client = client.clone();
+        let url = m.url();
+
+        move || client.get(url)
+    });
+
+    // Give the request a chance to start.
+    thread::sleep(Duration::from_millis(100));
+
+    let start = Instant::now();
+    client.close(Duration::from_millis(200));
+
+    assert!(start.elapsed() < Duration::from_secs(2));
+    assert_matches!(handle.join().unwrap(), Err(e) if e == ErrorKind::ClientClosed);
+}
+
+#[test]
+fn preconnect_opens_reusable_connection() {
+    let m = mock! {
+        body: "hello world",
+    };
+
+    let client = HttpClient::new().unwrap();
+    client.preconnect(m.url()).unwrap();
+
+    assert_eq!(m.request().method(), "HEAD");
+
+    let mut response = client.get(m.url()).unwrap();
+    response.consume().unwrap();
+
+    assert_eq!(response.connection_reused(), Some(true));
+    assert_eq!(m.requests_received(), 2);
+}
+
+#[test]
+fn preconnect_ignores_response_status() {
+    let m = mock! {
+        status: 405,
+    };
+
+    HttpClient::new().unwrap().preconnect(m.url()).unwrap();
+
+    assert_eq!(m.requests_received(), 1);
+}
+
+#[test]
+fn preconnect_async_returns_error_if_connection_fails() {
+    // Find a port that nothing is listening on.
+    let port = TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap()
+        .port();
+
+    let client = HttpClient::new().unwrap();
+    let result = block_on(client.preconnect_async(format!("http://127.0.0.1:{}", port)));
+
+    assert_matches!(result, Err(e) if e == ErrorKind::ConnectionFailed);
+}
+
+#[test]
+fn requests_are_spread_across_worker_threads() {
+    let m = mock! {
+        delay: 100ms,
+        body: "hello world",
+    };
+
+    let client = HttpClient::builder().worker_threads(4).build().unwrap();
+
+    let handles = (0..8)
+        .map(|_| {
+            let client = client.clone();
+            let url = m.url();
+
+            thread::spawn(move || client.get(url).unwrap().text().unwrap())
+        })
+        .collect::<Vec<_>>();
+
+    for handle in handles {
+        assert_eq!(handle.join().unwrap(), "hello world");
+    }
+
+    assert_eq!(m.requests_received(), 8);
+}
+
+#[test]
+fn close_shuts_down_all_worker_threads() {
+    let m = mock!();
+
+    let client = HttpClient::builder().worker_threads(3).build().unwrap();
+    let clone = client.clone();
+
+    for _ in 0..3 {
+        client.get(m.url()).unwrap();
+    }
+
+    client.close(Duration::from_secs(1));
+
+    for _ in 0..3 {
+        assert_matches!(clone.get(m.url()), Err(e) if e == ErrorKind::ClientClosed);
+    }
+}
+
+#[test]
+#[cfg(target_os = "linux")]
+fn agent_thread_uses_configured_name() {
+    fn thread_names() -> Vec<String> {
+        std::fs::read_dir("/proc/self/task")
+            .unwrap()
+            .filter_map(|entry| std::fs::read_to_string(entry.ok()?.path().join("comm")).ok())
+            .map(|name| name.trim_end().to_owned())
+            .collect()
+    }
+
+    let client = HttpClient::builder()
+        .thread_name("named-agent")
+        .build()
+        .unwrap();
+
+    assert!(thread_names().iter().any(|name| name == "named-agent"));
+
+    client.close(Duration::from_secs(1));
+
+    assert!(!thread_names().iter().any(|name| name == "named-agent"));
+}
+
+#[test]
+fn thread_name_with_null_byte_is_rejected() {
+    let result = HttpClient::builder().thread_name("bad\0name").build();
+
+    assert_matches!(result, Err(e) if e == ErrorKind::ClientInitialization);
+}
+
+#[test]
+fn with_curl_is_applied_to_every_request() {
+    let m = mock!();
+
+    let client = HttpClient::builder()
+        .with_curl(|easy| easy.referer("https://example.org/"))
+        .build()
+        .unwrap();
+
+    for _ in 0..2 {
+        client.get(m.url()).unwrap();
+
+        m.request().expect_header("referer", "https://example.org/");
+    }
+
+    assert_eq!(m.requests_received(), 2);
+}
+
+#[test]
+fn with_curl_error_fails_request() {
+    let m = mock!();
+
+    let client = HttpClient::builder()
+        .with_curl(|_| Err(curl::Error::new(curl_sys::CURLE_BAD_FUNCTION_ARGUMENT)))
+        .build()
+        .unwrap();
+
+    assert_matches!(client.get(m.url()), Err(e) if e == ErrorKind::InvalidRequest);
+    assert_eq!(m.requests_received(), 0);
+}
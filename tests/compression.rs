@@ -0,0 +1,103 @@
+#![cfg(feature = "request-compression")]
+
+use flate2::{
+    write::{GzEncoder, ZlibEncoder},
+    Compression,
+};
+use isahc::{config::Encoding, error::ErrorKind, prelude::*, Body, Request};
+use std::io::Write;
+use testserver::mock;
+
+#[macro_use]
+mod utils;
+
+fn gzip(data: &[u8]) -> Vec<u8> {
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(data).unwrap();
+    encoder.finish().unwrap()
+}
+
+fn deflate(data: &[u8]) -> Vec<u8> {
+    let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(data).unwrap();
+    encoder.finish().unwrap()
+}
+
+#[test]
+fn request_body_is_compressed_with_gzip() {
+    let m = mock!();
+
+    Request::post(m.url())
+        .request_body_compression(Encoding::Gzip)
+        .body("hello world")
+        .unwrap()
+        .send()
+        .unwrap();
+
+    let compressed = gzip(b"hello world");
+
+    m.request().expect_header("content-encoding", "gzip");
+    m.request().expect_header("content-length", compressed.len().to_string());
+    m.request().expect_body(compressed);
+}
+
+#[test]
+fn streaming_request_body_is_compressed_with_deflate() {
+    let m = mock!();
+
+    Request::post(m.url())
+        .request_body_compression(Encoding::Deflate)
+        .body(Body::from_reader("hello world".as_bytes()))
+        .unwrap()
+        .send()
+        .unwrap();
+
+    m.request().expect_header("content-encoding", "deflate");
+    m.request().expect_header("transfer-encoding", "chunked");
+    m.request().expect_body(deflate(b"hello world"));
+}
+
+#[test]
+fn empty_request_body_is_not_compressed() {
+    let m = mock!();
+
+    Request::post(m.url())
+        .request_body_compression(Encoding::Gzip)
+        .body("")
+        .unwrap()
+        .send()
+        .unwrap();
+
+    assert_eq!(m.request().get_header("content-encoding").count(), 0);
+}
+
+#[test]
+fn already_encoded_request_body_is_not_compressed_again() {
+    let m = mock!();
+    let compressed = gzip(b"hello world");
+
+    Request::post(m.url())
+        .request_body_compression(Encoding::Gzip)
+        .header("content-encoding", "gzip")
+        .body(compressed.clone())
+        .unwrap()
+        .send()
+        .unwrap();
+
+    assert_eq!(m.request().get_header("content-encoding").count(), 1);
+    m.request().expect_body(compressed);
+}
+
+#[test]
+fn unsupported_request_body_compression_returns_error() {
+    let m = mock!();
+
+    let result = Request::post(m.url())
+        .request_body_compression(Encoding::Brotli)
+        .body("hello world")
+        .unwrap()
+        .send();
+
+    assert_matches!(result, Err(e) if e == ErrorKind::InvalidRequest);
+    assert_eq!(m.requests_received(), 0);
+}
@@ -1,6 +1,13 @@
 #![cfg(feature = "cookies")]
 
-use isahc::{cookies::CookieJar, prelude::*, HttpClient};
+use isahc::{
+    config::RedirectPolicy,
+    cookies::{Cookie, CookieJar, SameSite},
+    prelude::*,
+    HttpClient,
+    Request,
+};
+use std::time::Duration;
 use testserver::mock;
 
 #[test]
@@ -29,3 +36,137 @@ fn cookie_lifecycle() {
 
     dbg!(m2.request()).expect_header("cookie", "baz=123; foo=bar");
 }
+
+#[test]
+fn cookie_jar_can_be_shared_between_clients() {
+    let jar = CookieJar::default();
+
+    let client1 = HttpClient::builder()
+        .cookie_jar(jar.clone())
+        .build()
+        .unwrap();
+    let client2 = HttpClient::builder()
+        .cookie_jar(jar.clone())
+        .timeout(Duration::from_secs(5))
+        .build()
+        .unwrap();
+
+    let m1 = mock! {
+        headers {
+            "set-cookie": "session=abc",
+        }
+    };
+    let m2 = mock! {
+        headers {
+            "set-cookie": "other=123",
+        }
+    };
+    let m3 = mock!();
+
+    client1.get(m1.url()).unwrap();
+    client2.get(m2.url()).unwrap();
+    client1.get(m3.url()).unwrap();
+
+    // Both clients wrote to the same jar.
+    m2.request().expect_header("cookie", "session=abc");
+    m3.request().expect_header("cookie", "other=123; session=abc");
+    assert_eq!(jar.iter().count(), 2);
+}
+
+#[test]
+fn cookies_persist_across_clients_using_a_file() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("cookies.txt");
+
+    let m1 = mock! {
+        headers {
+            "set-cookie": "foo=bar; Max-Age=3600",
+            "set-cookie": "session=123",
+        }
+    };
+
+    // The file does not exist yet, so the client starts with an empty jar.
+    let client = HttpClient::builder()
+        .cookies_from_file(&path)
+        .build()
+        .unwrap();
+
+    client.get(m1.url()).unwrap();
+    client.cookie_jar().unwrap().save(&path).unwrap();
+
+    let m2 = mock!();
+
+    let client = HttpClient::builder()
+        .cookies_from_file(&path)
+        .build()
+        .unwrap();
+
+    client.get(m2.url()).unwrap();
+
+    m2.request().expect_header("cookie", "foo=bar; session=123");
+}
+
+#[test]
+fn same_site_strict_cookie_is_withheld_on_cross_site_redirect() {
+    let jar = same_site_jar();
+    let target = mock!();
+    let redirect = mock! {
+        status: 302,
+        headers {
+            "location": target.url(),
+        }
+    };
+
+    // Start from a different host name for the same server, which makes the
+    // redirect cross-site.
+    Request::get(format!("http://localhost:{}/", redirect.addr().port()))
+        .cookie_jar(jar)
+        .redirect_policy(RedirectPolicy::Follow)
+        .body(())
+        .unwrap()
+        .send()
+        .unwrap();
+
+    target.request().expect_header("cookie", "lax=2; plain=3");
+}
+
+#[test]
+fn same_site_cookies_are_sent_on_same_site_redirect() {
+    let jar = same_site_jar();
+    let target = mock!();
+    let redirect = mock! {
+        status: 302,
+        headers {
+            "location": target.url(),
+        }
+    };
+
+    Request::get(redirect.url())
+        .cookie_jar(jar)
+        .redirect_policy(RedirectPolicy::Follow)
+        .body(())
+        .unwrap()
+        .send()
+        .unwrap();
+
+    target.request().expect_header("cookie", "lax=2; plain=3; strict=1");
+}
+
+/// Create a cookie jar containing cookies for the local test server with each
+/// kind of `SameSite` restriction.
+fn same_site_jar() -> CookieJar {
+    let jar = CookieJar::default();
+    let uri = "http://127.0.0.1/".parse().unwrap();
+
+    let cookies = vec![
+        Cookie::builder("strict", "1").same_site(SameSite::Strict),
+        Cookie::builder("lax", "2").same_site(SameSite::Lax),
+        Cookie::builder("plain", "3"),
+    ];
+
+    for cookie in cookies {
+        jar.set(cookie.build().unwrap(), &uri).unwrap();
+    }
+
+    jar
+}
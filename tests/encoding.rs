@@ -2,7 +2,7 @@ use flate2::{
     read::{DeflateEncoder, GzEncoder},
     Compression,
 };
-use isahc::{prelude::*, Request};
+use isahc::{config::Encoding, prelude::*, HttpClient, Request};
 use std::io::Read;
 use testserver::mock;
 
@@ -69,6 +69,103 @@ fn request_gzip_without_automatic_decompression() {
     assert_eq!(response.body().len(), Some(body_encoded.len() as u64));
 }
 
+#[test]
+fn client_without_automatic_decompression_returns_raw_body() {
+    let body = "hello world";
+    let mut body_encoded = Vec::new();
+
+    GzEncoder::new(body.as_bytes(), Compression::default())
+        .read_to_end(&mut body_encoded)
+        .unwrap();
+
+    let m = {
+        let body_encoded = body_encoded.clone();
+        mock! {
+            headers {
+                "Content-Encoding": "gzip",
+            }
+            body: body_encoded.clone(),
+        }
+    };
+
+    let client = HttpClient::builder()
+        .automatic_decompression(false)
+        .build()
+        .unwrap();
+
+    let mut response = client.get(m.url()).unwrap();
+    let mut body_received = Vec::new();
+    response.body_mut().read_to_end(&mut body_received).unwrap();
+
+    assert_eq!(body_received, body_encoded);
+    assert_eq!(response.headers()["content-encoding"], "gzip");
+    assert_eq!(m.request().get_header("accept-encoding").count(), 0);
+}
+
+#[test]
+fn accept_encodings_limits_advertised_encodings() {
+    let body = "hello world";
+    let mut body_encoded = Vec::new();
+
+    GzEncoder::new(body.as_bytes(), Compression::default())
+        .read_to_end(&mut body_encoded)
+        .unwrap();
+
+    let m = mock! {
+        headers {
+            "Content-Encoding": "gzip",
+        }
+        body: body_encoded.clone(),
+    };
+
+    let mut response = Request::get(m.url())
+        .accept_encodings(vec![Encoding::Gzip])
+        .body(())
+        .unwrap()
+        .send()
+        .unwrap();
+
+    assert_eq!(response.text().unwrap(), body);
+    m.request().expect_header("Accept-Encoding", "gzip");
+}
+
+#[test]
+fn accept_encodings_skips_unsupported_encodings() {
+    let m = mock!();
+
+    Request::get(m.url())
+        .accept_encodings(vec![Encoding::Zstd, Encoding::Gzip])
+        .body(())
+        .unwrap()
+        .send()
+        .unwrap();
+
+    if isahc::is_encoding_supported(Encoding::Zstd) {
+        m.request().expect_header("Accept-Encoding", "zstd, gzip");
+    } else {
+        m.request().expect_header("Accept-Encoding", "gzip");
+    }
+}
+
+#[test]
+fn default_accept_encoding_matches_supported_encodings() {
+    let m = mock!();
+
+    isahc::get(m.url()).unwrap();
+
+    let header = m.request().get_header("accept-encoding").next().unwrap();
+
+    for encoding in &[Encoding::Gzip, Encoding::Deflate, Encoding::Brotli, Encoding::Zstd] {
+        assert_eq!(
+            header.contains(encoding.as_str()),
+            isahc::is_encoding_supported(*encoding),
+            "{:?} advertised incorrectly in {}",
+            encoding,
+            header
+        );
+    }
+}
+
 #[test]
 fn deflate_encoded_response_is_decoded_automatically() {
     let body = "hello world";
@@ -1,4 +1,5 @@
 use isahc::{Body, Request, prelude::*};
+use std::time::Duration;
 use testserver::mock;
 
 #[test]
@@ -26,4 +27,40 @@ fn expect_header_is_not_sent_when_disabled() {
         .unwrap();
 
     assert!(m.request().get_header("expect").next().is_none());
+    m.request().expect_body("hello world");
+}
+
+#[test]
+fn expect_header_is_sent_with_custom_timeout() {
+    let m = mock!();
+
+    let body = Body::from_reader("hello world".as_bytes());
+
+    Request::post(m.url())
+        .expect_continue(Duration::from_millis(100))
+        .body(body)
+        .unwrap()
+        .send()
+        .unwrap();
+
+    m.request().expect_header("expect", "100-continue");
+    m.request().expect_body("hello world");
+}
+
+#[test]
+fn disabling_expect_removes_user_provided_header() {
+    let m = mock!();
+
+    let body = Body::from_reader("hello world".as_bytes());
+
+    Request::post(m.url())
+        .header("Expect", "100-continue")
+        .expect_continue(false)
+        .body(body)
+        .unwrap()
+        .send()
+        .unwrap();
+
+    assert!(m.request().get_header("expect").next().is_none());
+    m.request().expect_body("hello world");
 }
@@ -0,0 +1,89 @@
+#![cfg(feature = "form")]
+
+use futures_lite::future::block_on;
+use isahc::{prelude::*, AsyncBody, Body, Request};
+use std::collections::BTreeMap;
+use testserver::mock;
+
+#[test]
+fn serialize_form_request_body() {
+    let m = mock!();
+
+    Request::post(m.url())
+        .form(&[("foo", "bar"), ("baz", "hello world")])
+        .unwrap()
+        .send()
+        .unwrap();
+
+    m.request().expect_header("content-type", "application/x-www-form-urlencoded");
+    m.request().expect_header("content-length", "23");
+    m.request().expect_body("foo=bar&baz=hello+world");
+}
+
+#[test]
+fn form_request_body_does_not_override_content_type() {
+    let m = mock!();
+
+    Request::post(m.url())
+        .header(
+            "content-type",
+            "application/x-www-form-urlencoded; charset=utf-8",
+        )
+        .form(&[("foo", "bar")])
+        .unwrap()
+        .send()
+        .unwrap();
+
+    m.request().expect_header(
+        "content-type",
+        "application/x-www-form-urlencoded; charset=utf-8",
+    );
+}
+
+#[test]
+fn form_body_from_map() {
+    let mut map = BTreeMap::new();
+    map.insert("a", 1);
+    map.insert("b", 2);
+
+    let body = Body::form(&map).unwrap();
+
+    assert_eq!(body.len(), Some(7));
+}
+
+#[test]
+fn form_body_sets_content_type() {
+    let m = mock!();
+
+    Request::post(m.url())
+        .body(Body::form(&[("foo", "bar")]).unwrap())
+        .unwrap()
+        .send()
+        .unwrap();
+
+    m.request().expect_header("content-type", "application/x-www-form-urlencoded");
+    m.request().expect_body("foo=bar");
+}
+
+#[test]
+fn async_form_body_sets_content_type() {
+    let m = mock!();
+
+    block_on(async {
+        Request::post(m.url())
+            .body(AsyncBody::form(&[("foo", "bar")]).unwrap())
+            .unwrap()
+            .send_async()
+            .await
+            .unwrap();
+    });
+
+    m.request().expect_header("content-type", "application/x-www-form-urlencoded");
+}
+
+#[test]
+fn unsupported_form_value_returns_error() {
+    let result = Request::post("http://localhost").form(&[("nested", [1, 2])]);
+
+    assert!(result.unwrap_err().is_client());
+}
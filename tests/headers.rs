@@ -1,8 +1,9 @@
 use futures_lite::future::block_on;
-use isahc::{prelude::*, HttpClient, Request};
+use isahc::{error::ErrorKind, prelude::*, HttpClient, Request};
 use std::{
-    io::{self, Write},
+    io::{self, Read, Write},
     net::{Shutdown, TcpListener, TcpStream},
+    sync::mpsc,
     thread,
     time::Duration,
 };
@@ -29,6 +30,97 @@ fn user_agent_contains_expected_format() {
         .expect_header_regex("user-agent", r"^curl/\S+ isahc/\S+$");
 }
 
+#[test]
+fn user_agent_can_be_replaced_in_client_builder() {
+    let m = mock!();
+
+    let client = HttpClient::builder()
+        .user_agent("my-app/1.0")
+        .build()
+        .unwrap();
+
+    client.get(m.url()).unwrap();
+
+    m.request().expect_header("user-agent", "my-app/1.0");
+}
+
+#[test]
+fn user_agent_can_be_appended_to_in_client_builder() {
+    let m = mock!();
+
+    let client = HttpClient::builder()
+        .append_user_agent("my-app/1.0")
+        .build()
+        .unwrap();
+
+    client.get(m.url()).unwrap();
+
+    m.request()
+        .expect_header_regex("user-agent", r"^curl/\S+ isahc/\S+ my-app/1\.0$");
+}
+
+#[test]
+fn user_agent_in_request_overrides_client_user_agent() {
+    let m = mock!();
+
+    let client = HttpClient::builder()
+        .user_agent("my-app/1.0")
+        .build()
+        .unwrap();
+
+    let request = Request::get(m.url())
+        .header("user-agent", "other/2.0")
+        .body(())
+        .unwrap();
+
+    client.send(request).unwrap();
+
+    m.request().expect_header("user-agent", "other/2.0");
+}
+
+#[test]
+fn no_default_user_agent_sends_no_user_agent() {
+    let m = mock!();
+
+    let client = HttpClient::builder()
+        .no_default_user_agent()
+        .build()
+        .unwrap();
+
+    client.get(m.url()).unwrap();
+
+    assert_eq!(m.request().get_header("user-agent").count(), 0);
+}
+
+#[test]
+fn no_default_user_agent_still_sends_user_agent_set_on_request() {
+    let m = mock!();
+
+    let client = HttpClient::builder()
+        .no_default_user_agent()
+        .build()
+        .unwrap();
+
+    let request = Request::get(m.url())
+        .header("user-agent", "my-app/1.0")
+        .body(())
+        .unwrap();
+
+    client.send(request).unwrap();
+
+    m.request().expect_header("user-agent", "my-app/1.0");
+}
+
+#[test]
+fn invalid_user_agent_returns_error_on_build() {
+    let error = HttpClient::builder()
+        .user_agent("bad\nagent")
+        .build()
+        .unwrap_err();
+
+    assert_eq!(error.kind(), &ErrorKind::ClientInitialization);
+}
+
 // Issue [#209](https://github.com/sagebind/isahc/issues/209)
 #[test]
 fn setting_an_empty_header_sends_a_header_with_no_value() {
@@ -189,6 +281,80 @@ fn headers_in_request_builder_must_override_multiple_headers_in_httpclient_build
     m.request().expect_header("X-header", "some-value3");
 }
 
+#[test]
+fn default_headers_from_header_map_are_merged_per_header_name() {
+    let m = mock!();
+
+    let mut headers = isahc::http::HeaderMap::new();
+    headers.insert("api-version", "2".parse().unwrap());
+    headers.insert("x-trace-id", "default".parse().unwrap());
+
+    let client = HttpClient::builder()
+        .default_headers(&headers)
+        .build()
+        .unwrap();
+
+    let request = Request::get(m.url())
+        .header("X-Trace-Id", "custom")
+        .body(())
+        .unwrap();
+
+    client.send(request).unwrap();
+
+    m.request().expect_header("api-version", "2");
+    m.request().expect_header("x-trace-id", "custom");
+    assert_eq!(m.request().get_header("x-trace-id").count(), 1);
+}
+
+#[test]
+fn default_headers_replaces_previous_default_headers() {
+    let m = mock!();
+
+    let client = HttpClient::builder()
+        .default_header("x-old", "1")
+        .default_headers(&[("x-new", "2")])
+        .build()
+        .unwrap();
+
+    client.get(m.url()).unwrap();
+
+    m.request().expect_header("x-new", "2");
+    assert_eq!(m.request().get_header("x-old").count(), 0);
+}
+
+#[test]
+fn duplicate_response_headers_are_preserved_in_order() {
+    let m = mock! {
+        headers {
+            "set-cookie": "a=1",
+            "x-custom": "first",
+            "set-cookie": "b=2",
+            "x-custom": "second",
+            "set-cookie": "c=3",
+        }
+    };
+
+    let response = isahc::get(m.url()).unwrap();
+
+    let cookies = response
+        .headers()
+        .get_all("set-cookie")
+        .iter()
+        .map(|v| v.to_str().unwrap())
+        .collect::<Vec<_>>();
+
+    assert_eq!(cookies, vec!["a=1", "b=2", "c=3"]);
+
+    let custom = response
+        .headers()
+        .get_all("x-custom")
+        .iter()
+        .map(|v| v.to_str().unwrap())
+        .collect::<Vec<_>>();
+
+    assert_eq!(custom, vec!["first", "second"]);
+}
+
 #[test]
 fn trailer_headers() {
     let listener = TcpListener::bind("127.0.0.1:0").unwrap();
@@ -230,6 +396,47 @@ fn trailer_headers() {
     assert_eq!(response.trailer().wait().get("foo").unwrap(), "bar");
 }
 
+#[test]
+fn trailer_headers_available_after_consuming_body() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+
+    thread::spawn(move || {
+        let mut stream = listener.accept().unwrap().0;
+
+        consume_request_in_background(&stream);
+
+        stream
+            .write_all(
+                b"\
+            HTTP/1.1 200 OK\r\n\
+            transfer-encoding: chunked\r\n\
+            trailer: grpc-status, grpc-message\r\n\
+            \r\n\
+            5\r\n\
+            hello\r\n\
+            0\r\n\
+            grpc-status: 5\r\n\
+            grpc-message: not found\r\n\
+            \r\n\
+            ",
+            )
+            .unwrap();
+
+        let _ = stream.shutdown(Shutdown::Write);
+    });
+
+    let mut response = isahc::get(url).unwrap();
+
+    assert_eq!(response.text().unwrap(), "hello");
+
+    let trailer = response.trailer().try_get().unwrap();
+
+    assert_eq!(trailer.get("grpc-status").unwrap(), "5");
+    assert_eq!(trailer.get("grpc-message").unwrap(), "not found");
+    assert!(response.headers().get("grpc-status").is_none());
+}
+
 #[test]
 fn trailer_headers_async() {
     let listener = TcpListener::bind("127.0.0.1:0").unwrap();
@@ -326,6 +533,52 @@ fn trailer_headers_timeout() {
     );
 }
 
+#[test]
+fn non_utf8_header_values_are_sent_and_received_as_is() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    let (tx, rx) = mpsc::channel();
+
+    thread::spawn(move || {
+        let mut stream = listener.accept().unwrap().0;
+        let mut request = Vec::new();
+        let mut buf = [0; 1024];
+
+        while !request.ends_with(b"\r\n\r\n") {
+            let len = stream.read(&mut buf).unwrap();
+            request.extend_from_slice(&buf[..len]);
+        }
+
+        tx.send(request).unwrap();
+
+        stream
+            .write_all(
+                b"\
+            HTTP/1.1 200 OK\r\n\
+            content-length: 0\r\n\
+            x-latin1: caf\xe9\r\n\
+            \r\n\
+            ",
+            )
+            .unwrap();
+
+        let _ = stream.shutdown(Shutdown::Write);
+    });
+
+    let response = Request::get(url)
+        .header("x-latin1", http::HeaderValue::from_bytes(b"na\xefve").unwrap())
+        .body(())
+        .unwrap()
+        .send()
+        .unwrap();
+
+    let request = rx.recv().unwrap();
+    let expected = b"x-latin1: na\xefve";
+
+    assert!(request.windows(expected.len()).any(|w| w == expected));
+    assert_eq!(response.headers()["x-latin1"].as_bytes(), b"caf\xe9");
+}
+
 fn consume_request_in_background(stream: &TcpStream) {
     let mut stream = stream.try_clone().unwrap();
 
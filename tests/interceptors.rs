@@ -1,6 +1,6 @@
 #![cfg(feature = "unstable-interceptors")]
 
-use isahc::HttpClient;
+use isahc::{prelude::*, HttpClient};
 use testserver::mock;
 
 #[test]
@@ -19,3 +19,176 @@ fn change_http_method_with_interceptor() {
 
     assert_eq!(m.request().method(), "HEAD");
 }
+
+#[test]
+fn logger_interceptor_passes_requests_through() {
+    let m = mock! {
+        status: 201,
+        body: "hello",
+    };
+
+    let client = HttpClient::builder()
+        .interceptor(isahc::interceptor::Logger::new().level(log::Level::Info))
+        .build()
+        .unwrap();
+
+    let mut response = client
+        .send(
+            http::Request::post(m.url())
+                .header("Authorization", "Bearer s3cr3t")
+                .body("hi")
+                .unwrap(),
+        )
+        .unwrap();
+
+    assert_eq!(response.status(), 201);
+    assert_eq!(response.text().unwrap(), "hello");
+    m.request().expect_header("Authorization", "Bearer s3cr3t");
+    m.request().expect_body("hi");
+}
+
+#[test]
+fn interceptor_can_reject_request_with_custom_error() {
+    #[derive(Debug)]
+    struct Rejected;
+
+    impl std::fmt::Display for Rejected {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            f.write_str("rejected")
+        }
+    }
+
+    impl std::error::Error for Rejected {}
+
+    struct RejectAll;
+
+    impl isahc::interceptor::Interceptor for RejectAll {
+        type Err = Rejected;
+
+        fn intercept<'a>(
+            &'a self,
+            _request: isahc::Request<isahc::AsyncBody>,
+            _ctx: isahc::interceptor::Context<'a>,
+        ) -> isahc::interceptor::InterceptorFuture<'a, Self::Err> {
+            Box::pin(async move { Err(Rejected) })
+        }
+    }
+
+    let m = mock!();
+
+    let client = HttpClient::builder()
+        .interceptor(RejectAll)
+        .build()
+        .unwrap();
+
+    let error = client.get(m.url()).unwrap_err();
+
+    assert_eq!(error.kind(), &isahc::error::ErrorKind::Unknown);
+    assert!(std::error::Error::source(&error)
+        .unwrap()
+        .downcast_ref::<Rejected>()
+        .is_some());
+    assert_eq!(m.requests_received(), 0);
+}
+
+#[test]
+fn interceptor_can_short_circuit_with_a_synthetic_response() {
+    struct Cached;
+
+    impl isahc::interceptor::Interceptor for Cached {
+        type Err = isahc::Error;
+
+        fn intercept<'a>(
+            &'a self,
+            _request: isahc::Request<isahc::AsyncBody>,
+            _ctx: isahc::interceptor::Context<'a>,
+        ) -> isahc::interceptor::InterceptorFuture<'a, Self::Err> {
+            Box::pin(async move {
+                Ok(http::Response::builder()
+                    .status(200)
+                    .header("x-cache", "hit")
+                    .body(isahc::AsyncBody::from("cached"))?)
+            })
+        }
+    }
+
+    let m = mock!();
+
+    let client = HttpClient::builder().interceptor(Cached).build().unwrap();
+
+    let mut response = client.get(m.url()).unwrap();
+
+    assert_eq!(response.status(), 200);
+    assert_eq!(response.headers()["x-cache"], "hit");
+    assert_eq!(response.text().unwrap(), "cached");
+    assert_eq!(m.requests_received(), 0);
+}
+
+#[test]
+fn cache_serves_fresh_responses_without_network() {
+    let m = mock! {
+        headers {
+            "Cache-Control": "max-age=60",
+        }
+        body: "hello",
+    };
+
+    let client = HttpClient::builder()
+        .interceptor(isahc::interceptor::Cache::new())
+        .build()
+        .unwrap();
+
+    assert_eq!(client.get(m.url()).unwrap().text().unwrap(), "hello");
+    assert_eq!(client.get(m.url()).unwrap().text().unwrap(), "hello");
+    assert_eq!(m.requests_received(), 1);
+}
+
+#[test]
+fn cache_revalidates_stale_responses() {
+    let m = mock! {
+        #0 => {
+            headers {
+                "Cache-Control": "no-cache",
+                "ETag": "\"abc\"",
+            }
+            body: "hello",
+        },
+        _ => {
+            status: 304,
+        },
+    };
+
+    let client = HttpClient::builder()
+        .interceptor(isahc::interceptor::Cache::new())
+        .build()
+        .unwrap();
+
+    assert_eq!(client.get(m.url()).unwrap().text().unwrap(), "hello");
+
+    let mut response = client.get(m.url()).unwrap();
+
+    assert_eq!(response.status(), 200);
+    assert_eq!(response.headers()["etag"], "\"abc\"");
+    assert_eq!(response.text().unwrap(), "hello");
+    assert_eq!(m.requests_received(), 2);
+}
+
+#[test]
+fn cache_does_not_store_no_store_responses() {
+    let m = mock! {
+        headers {
+            "Cache-Control": "no-store, max-age=60",
+        }
+        body: "hello",
+    };
+
+    let client = HttpClient::builder()
+        .interceptor(isahc::interceptor::Cache::new())
+        .build()
+        .unwrap();
+
+    client.get(m.url()).unwrap().text().unwrap();
+    client.get(m.url()).unwrap().text().unwrap();
+
+    assert_eq!(m.requests_received(), 2);
+}
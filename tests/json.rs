@@ -1,8 +1,8 @@
 #![cfg(feature = "json")]
 
-use futures_lite::{future::block_on, io::AsyncRead};
-use isahc::prelude::*;
-use serde_json::Value;
+use futures_lite::{future::block_on, io::AsyncRead, StreamExt};
+use isahc::{ndjson::JsonStream, prelude::*, AsyncBody, Body, Request};
+use serde_json::{json, Value};
 use std::{
     io,
     pin::Pin,
@@ -43,6 +43,22 @@ fn deserialize_json_async() {
     });
 }
 
+#[cfg(feature = "text-decoding")]
+#[test]
+fn deserialize_json_respects_charset() {
+    let m = mock! {
+        headers {
+            "content-type": "application/json; charset=iso-8859-1",
+        }
+        body: &b"{\"foo\": \"caf\xe9\"}"[..],
+    };
+
+    let mut response = isahc::get(m.url()).unwrap();
+    let data = response.json::<Value>().unwrap();
+
+    assert_eq!(data["foo"], "caf\u{e9}");
+}
+
 #[test]
 fn deserialize_json_async_io_error() {
     struct BadReader;
@@ -63,3 +79,109 @@ fn deserialize_json_async_io_error() {
         assert_matches!(response.json::<Value>().await, Err(e) if e.is_io());
     });
 }
+
+#[test]
+fn serialize_json_request_body() {
+    let m = mock!();
+
+    Request::post(m.url())
+        .json(&json!({
+            "foo": "bar",
+        }))
+        .unwrap()
+        .send()
+        .unwrap();
+
+    m.request().expect_header("content-type", "application/json");
+    m.request().expect_header("content-length", "13");
+    m.request().expect_body(r#"{"foo":"bar"}"#);
+}
+
+#[test]
+fn json_request_body_does_not_override_content_type() {
+    let m = mock!();
+
+    Request::post(m.url())
+        .header("content-type", "application/vnd.api+json")
+        .json(&json!({}))
+        .unwrap()
+        .send()
+        .unwrap();
+
+    m.request().expect_header("content-type", "application/vnd.api+json");
+}
+
+#[test]
+fn json_body_sets_content_type() {
+    let m = mock!();
+
+    Request::post(m.url())
+        .body(Body::json(&[1, 2, 3]).unwrap())
+        .unwrap()
+        .send()
+        .unwrap();
+
+    m.request().expect_header("content-type", "application/json");
+    m.request().expect_body("[1,2,3]");
+}
+
+#[test]
+fn async_json_body_sets_content_type() {
+    let m = mock!();
+
+    block_on(async {
+        Request::post(m.url())
+            .body(AsyncBody::json(&[1, 2, 3]).unwrap())
+            .unwrap()
+            .send_async()
+            .await
+            .unwrap();
+    });
+
+    m.request().expect_header("content-type", "application/json");
+}
+
+#[test]
+fn json_body_does_not_override_content_type() {
+    let m = mock!();
+
+    Request::post(m.url())
+        .header("content-type", "application/vnd.api+json")
+        .body(Body::json(&[1, 2, 3]).unwrap())
+        .unwrap()
+        .send()
+        .unwrap();
+
+    m.request().expect_header("content-type", "application/vnd.api+json");
+}
+
+#[test]
+fn json_body_has_known_length() {
+    let body = Body::json(&[1, 2, 3]).unwrap();
+
+    assert_eq!(body.len(), Some(7));
+}
+
+#[test]
+fn stream_newline_delimited_json() {
+    let m = mock! {
+        headers {
+            "content-type": "application/x-ndjson",
+        }
+        body: "{\"n\": 1}\n\n{\"n\": 2}\nnot json\n{\"n\": 3}",
+    };
+
+    let results = block_on(async {
+        let response = isahc::get_async(m.url()).await.unwrap();
+
+        JsonStream::<_, Value>::new(response.into_body())
+            .collect::<Vec<_>>()
+            .await
+    });
+
+    assert_eq!(results.len(), 4);
+    assert_eq!(results[0].as_ref().unwrap(), &json!({"n": 1}));
+    assert_eq!(results[1].as_ref().unwrap(), &json!({"n": 2}));
+    assert_eq!(results[2].as_ref().unwrap_err().kind(), io::ErrorKind::InvalidData);
+    assert_eq!(results[3].as_ref().unwrap(), &json!({"n": 3}));
+}
@@ -1,5 +1,11 @@
-use isahc::{prelude::*, HttpClient, Request};
-use std::{io, time::Duration};
+use isahc::{error::ErrorKind, prelude::*, HttpClient, Request};
+use std::{
+    io,
+    net::TcpListener,
+    sync::{mpsc, Arc, Mutex},
+    thread,
+    time::Duration,
+};
 use testserver::mock;
 
 #[test]
@@ -34,3 +40,170 @@ fn enabling_metrics_causes_metrics_to_be_collected() {
     assert_eq!(metrics.download_progress().0, 11);
     assert!(metrics.total_time() > Duration::default());
 }
+
+#[test]
+fn queue_time_includes_time_waiting_for_connection() {
+    let m = mock! {
+        delay: 500ms,
+    };
+
+    let client = HttpClient::builder()
+        .max_connections(1)
+        .metrics(true)
+        .build()
+        .unwrap();
+
+    // Occupy the only connection allowed.
+    let handle = thread::spawn({
+        let client = client.clone();
+        let url = m.url();
+
+        move || client.get(url).unwrap().consume().unwrap()
+    });
+
+    thread::sleep(Duration::from_millis(100));
+
+    let response = client.get(m.url()).unwrap();
+    let metrics = response.metrics().unwrap();
+
+    assert!(metrics.queue_time() >= Duration::from_millis(200));
+    assert!(metrics.total_time() >= metrics.queue_time());
+
+    handle.join().unwrap();
+
+    // Nothing to wait for this time.
+    let response = client.get(m.url()).unwrap();
+
+    assert!(response.metrics().unwrap().queue_time() < Duration::from_millis(200));
+}
+
+#[test]
+fn upload_progress_callback_reports_bytes_sent() {
+    let m = mock!();
+    let body = vec![0; 1024 * 1024];
+    let reports = Arc::new(Mutex::new(Vec::new()));
+
+    let response = {
+        let reports = reports.clone();
+
+        Request::post(m.url())
+            .on_upload_progress(move |sent, total| {
+                reports.lock().unwrap().push((sent, total));
+            })
+            .body(body.clone())
+            .unwrap()
+            .send()
+            .unwrap()
+    };
+
+    let reports = reports.lock().unwrap();
+    let total = body.len() as u64;
+
+    assert!(!reports.is_empty());
+    assert!(reports.windows(2).all(|w| w[0].0 < w[1].0));
+    assert_eq!(reports.last(), Some(&(total, Some(total))));
+
+    // Reporting upload progress does not enable metrics.
+    assert!(response.metrics().is_none());
+}
+
+#[test]
+fn download_progress_callback_reports_bytes_received() {
+    let body = "wow so large ".repeat(10000);
+
+    let m = {
+        let body = body.clone();
+        mock! {
+            body: body.clone(),
+        }
+    };
+
+    let reports = Arc::new(Mutex::new(Vec::new()));
+
+    let mut response = {
+        let reports = reports.clone();
+
+        Request::get(m.url())
+            .on_download_progress(move |received, total| {
+                reports.lock().unwrap().push((received, total));
+            })
+            .body(())
+            .unwrap()
+            .send()
+            .unwrap()
+    };
+
+    response.copy_to(io::sink()).unwrap();
+
+    let reports = reports.lock().unwrap();
+    let total = body.len() as u64;
+
+    assert!(!reports.is_empty());
+    assert!(reports.windows(2).all(|w| w[0].0 < w[1].0));
+    assert_eq!(reports.last(), Some(&(total, Some(total))));
+}
+
+#[test]
+fn on_complete_reports_transfer_summary() {
+    let m = mock! {
+        body: "hello world",
+    };
+
+    let (tx, rx) = mpsc::channel();
+    let tx = Mutex::new(tx);
+
+    let client = HttpClient::builder()
+        .on_complete(move |summary| tx.lock().unwrap().send(summary.clone()).unwrap())
+        .build()
+        .unwrap();
+
+    client
+        .send(Request::post(m.url()).body("hello server").unwrap())
+        .unwrap()
+        .consume()
+        .unwrap();
+    client.get(m.url()).unwrap().consume().unwrap();
+
+    let first = rx.recv_timeout(Duration::from_secs(1)).unwrap();
+
+    assert_eq!(first.method(), "POST");
+    assert_eq!(first.uri().to_string(), m.url());
+    assert_eq!(first.status().unwrap(), 200);
+    assert_eq!(first.bytes_uploaded(), 12);
+    assert_eq!(first.bytes_downloaded(), 11);
+    assert!(first.total_time() > Duration::default());
+    assert_eq!(first.connection_reused(), Some(false));
+    assert!(first.is_success());
+
+    let second = rx.recv_timeout(Duration::from_secs(1)).unwrap();
+
+    assert_eq!(second.method(), "GET");
+    assert_eq!(second.connection_reused(), Some(true));
+}
+
+#[test]
+fn on_complete_reports_failed_transfers() {
+    // Find a port that nothing is listening on.
+    let port = TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap()
+        .port();
+
+    let (tx, rx) = mpsc::channel();
+    let tx = Mutex::new(tx);
+
+    let client = HttpClient::builder()
+        .on_complete(move |summary| tx.lock().unwrap().send(summary.clone()).unwrap())
+        .build()
+        .unwrap();
+
+    assert!(client.get(format!("http://127.0.0.1:{}", port)).is_err());
+
+    let summary = rx.recv_timeout(Duration::from_secs(1)).unwrap();
+
+    assert_eq!(summary.status(), None);
+    assert_eq!(summary.connection_reused(), None);
+    assert_eq!(summary.error().unwrap().kind(), &ErrorKind::ConnectionFailed);
+    assert!(!summary.is_success());
+}
@@ -1,8 +1,16 @@
-use isahc::{config::IpVersion, error::ErrorKind, prelude::*, Request};
+use futures_lite::future::block_on;
+use isahc::{
+    config::{IpVersion, ResolveMap},
+    error::ErrorKind,
+    prelude::*,
+    HttpClient,
+    Request,
+};
 use std::{
     io::{self, Read, Write},
     net::{Ipv4Addr, Ipv6Addr, Shutdown, TcpListener, TcpStream, ToSocketAddrs},
     thread,
+    time::{Duration, Instant},
 };
 use testserver::mock;
 
@@ -43,6 +51,38 @@ fn remote_addr_returns_expected_address() {
     assert_eq!(response.remote_addr(), Some(m.addr()));
 }
 
+#[test]
+fn remote_addr_is_available_on_async_responses() {
+    let m = mock!();
+
+    let response = block_on(isahc::get_async(m.url())).unwrap();
+
+    assert_eq!(response.remote_addr(), Some(m.addr()));
+    assert_eq!(response.local_addr().unwrap().ip(), Ipv4Addr::LOCALHOST);
+}
+
+#[test]
+fn remote_addr_is_last_connection_after_redirect() {
+    let m2 = mock!();
+    let location = m2.url();
+
+    let m1 = mock! {
+        status: 302,
+        headers {
+            "Location": location,
+        }
+    };
+
+    let response = Request::get(m1.url())
+        .redirect_policy(isahc::config::RedirectPolicy::Follow)
+        .body(())
+        .unwrap()
+        .send()
+        .unwrap();
+
+    assert_eq!(response.remote_addr(), Some(m2.addr()));
+}
+
 #[test]
 fn local_and_remote_addr_returns_expected_addresses_on_error() {
     let server = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).unwrap();
@@ -101,6 +141,29 @@ fn ipv6_only_will_not_connect_to_ipv4() {
     assert_matches!(result, Err(e) if e == ErrorKind::ConnectionFailed);
 }
 
+#[test]
+fn request_ip_version_overrides_client() {
+    let m = mock!();
+
+    let client = HttpClient::builder()
+        .ip_version(IpVersion::V6)
+        .build()
+        .unwrap();
+
+    // The mock server only listens on IPv4.
+    let response = client
+        .send(
+            Request::get(format!("http://localhost:{}", m.addr().port()))
+                .ip_version(IpVersion::V4)
+                .body(())
+                .unwrap(),
+        )
+        .unwrap();
+
+    assert_eq!(response.status(), 200);
+    assert_eq!(m.requests_received(), 1);
+}
+
 #[test]
 fn any_ip_version_uses_ipv4_or_ipv6() {
     // Create an IPv4 listener.
@@ -155,3 +218,332 @@ fn any_ip_version_uses_ipv4_or_ipv6() {
         assert!(response.local_addr().unwrap().is_ipv4());
     }
 }
+
+#[test]
+fn requests_beyond_connection_limit_are_queued() {
+    let m = mock! {
+        delay: 100ms,
+    };
+
+    let client = HttpClient::builder()
+        .max_connections_per_host(1)
+        .build()
+        .unwrap();
+
+    let start = Instant::now();
+
+    block_on(async {
+        let (a, b) = futures_lite::future::zip(
+            client.get_async(m.url()),
+            client.get_async(m.url()),
+        )
+        .await;
+
+        assert_eq!(a.unwrap().status(), 200);
+        assert_eq!(b.unwrap().status(), 200);
+    });
+
+    // Only one connection can be open at a time, so the second request must
+    // wait for the first one to complete.
+    assert!(start.elapsed() >= Duration::from_millis(200));
+    assert_eq!(m.requests_received(), 2);
+}
+
+#[test]
+fn connections_older_than_max_age_are_not_reused() {
+    let m = mock!();
+
+    let client = HttpClient::builder()
+        .max_connection_age(Duration::from_secs(1))
+        .build()
+        .unwrap();
+
+    let first = client.get(m.url()).unwrap();
+    thread::sleep(Duration::from_millis(1500));
+    let second = client.get(m.url()).unwrap();
+
+    assert_eq!(m.requests_received(), 2);
+    assert_eq!(first.connection_reused(), Some(false));
+    assert_eq!(second.connection_reused(), Some(false));
+}
+
+#[test]
+fn sub_second_max_connection_age_is_rounded_up() {
+    let m = mock!();
+
+    let client = HttpClient::builder()
+        .max_connection_age(Duration::from_millis(500))
+        .build()
+        .unwrap();
+
+    client.get(m.url()).unwrap();
+    thread::sleep(Duration::from_millis(1500));
+    let response = client.get(m.url()).unwrap();
+
+    assert_eq!(response.connection_reused(), Some(false));
+}
+
+#[test]
+#[cfg(unix)]
+fn invalid_interface_returns_error() {
+    let m = mock!();
+
+    let result = Request::get(m.url())
+        .interface(isahc::config::NetworkInterface::name("isahc-does-not-exist0"))
+        .body(())
+        .unwrap()
+        .send();
+
+    assert_matches!(result, Err(e) if e == ErrorKind::ClientInitialization);
+    assert_eq!(m.requests_received(), 0);
+}
+
+#[test]
+fn cloned_clients_share_connections() {
+    let m = mock! {
+        body: "hello world",
+    };
+
+    let client = HttpClient::new().unwrap();
+    let cloned = client.clone();
+
+    let mut response = client.get(m.url()).unwrap();
+    response.consume().unwrap();
+    let first_addr = response.local_addr().unwrap();
+
+    let mut response = cloned.get(m.url()).unwrap();
+    response.consume().unwrap();
+
+    // The same connection, and hence the same local port, should be reused.
+    assert_eq!(response.local_addr().unwrap(), first_addr);
+}
+
+#[test]
+fn owned_response_future_outlives_client() {
+    let m = mock! {
+        body: "hello world",
+    };
+
+    let future = {
+        let client = HttpClient::new().unwrap();
+        client.send_async_owned(Request::get(m.url()).body(()).unwrap())
+    };
+
+    let mut response = thread::spawn(move || block_on(future))
+        .join()
+        .unwrap()
+        .unwrap();
+
+    assert_eq!(block_on(response.text()).unwrap(), "hello world");
+}
+
+#[test]
+fn dns_resolve_overrides_address_but_keeps_host() {
+    let m = mock!();
+
+    let client = HttpClient::builder()
+        .dns_resolve(
+            ResolveMap::new()
+                .add("canary.example.test", m.addr().port(), m.addr().ip())
+                .add("other.example.test", m.addr().port(), Ipv4Addr::LOCALHOST),
+        )
+        .build()
+        .unwrap();
+
+    let response = client
+        .get(format!(
+            "http://canary.example.test:{}/path",
+            m.addr().port()
+        ))
+        .unwrap();
+
+    assert_eq!(response.status(), 200);
+    assert_eq!(m.request().url(), "/path");
+    m.request().expect_header(
+        "host",
+        format!("canary.example.test:{}", m.addr().port()),
+    );
+}
+
+#[test]
+fn connection_reuse_is_reported() {
+    let m = mock! {
+        body: "hello world",
+    };
+
+    let client = HttpClient::new().unwrap();
+
+    let mut response = client.get(m.url()).unwrap();
+    response.consume().unwrap();
+    assert_eq!(response.connection_reused(), Some(false));
+
+    let mut response = client.get(m.url()).unwrap();
+    response.consume().unwrap();
+    assert_eq!(response.connection_reused(), Some(true));
+}
+
+#[test]
+fn tcp_keepalive_options_are_accepted() {
+    // Setting the probe count requires libcurl 8.9.0 or newer.
+    if curl::Version::get().version_num() < 0x08_09_00 {
+        return;
+    }
+
+    let m = mock!();
+
+    let client = HttpClient::builder()
+        .tcp_keepalive(Duration::from_secs(10))
+        .tcp_keepalive_idle(Duration::from_secs(30))
+        .tcp_keepalive_count(3)
+        .build()
+        .unwrap();
+
+    let response = client.get(m.url()).unwrap();
+
+    assert_eq!(response.status(), 200);
+    assert_eq!(m.requests_received(), 1);
+}
+
+#[test]
+fn alpn_protocols_http11_only_is_accepted() {
+    let m = mock!();
+
+    let client = HttpClient::builder()
+        .alpn_protocols(vec!["http/1.1"])
+        .build()
+        .unwrap();
+
+    let response = client.get(m.url()).unwrap();
+
+    assert_eq!(response.status(), 200);
+    assert_eq!(response.version(), isahc::http::Version::HTTP_11);
+}
+
+#[test]
+fn alpn_protocols_do_not_change_insecure_connections() {
+    let m = mock!();
+
+    let client = HttpClient::builder()
+        .alpn_protocols(vec!["h2", "http/1.1"])
+        .build()
+        .unwrap();
+
+    let response = client.get(m.url()).unwrap();
+
+    assert_eq!(response.status(), 200);
+    assert_eq!(response.version(), isahc::http::Version::HTTP_11);
+}
+
+#[test]
+fn h2_only_alpn_protocols_are_rejected_by_client_builder() {
+    let result = HttpClient::builder().alpn_protocols(vec!["h2"]).build();
+
+    assert_matches!(result, Err(e) if e == ErrorKind::ClientInitialization);
+}
+
+#[test]
+fn unsupported_alpn_protocol_returns_error() {
+    let m = mock!();
+
+    let result = Request::get(m.url())
+        .alpn_protocols(vec!["h2", "spdy/3"])
+        .body(())
+        .unwrap()
+        .send();
+
+    assert_matches!(result, Err(e) if e == ErrorKind::InvalidRequest);
+    assert_eq!(m.requests_received(), 0);
+}
+
+#[test]
+fn ssl_server_name_replaces_host_but_keeps_address() {
+    let m = mock!();
+
+    let response = Request::get(format!("{}path", m.url()))
+        .ssl_server_name("staging.example.test")
+        .body(())
+        .unwrap()
+        .send()
+        .unwrap();
+
+    assert_eq!(response.status(), 200);
+    assert_eq!(m.request().url(), "/path");
+    m.request().expect_header(
+        "host",
+        format!("staging.example.test:{}", m.addr().port()),
+    );
+}
+
+#[test]
+fn tls_info_is_not_available_for_insecure_connections() {
+    let m = mock!();
+
+    let response = isahc::get(m.url()).unwrap();
+
+    assert_eq!(response.status(), 200);
+    assert!(response.tls_info().is_none());
+}
+
+#[test]
+fn ssl_verify_status_does_not_affect_insecure_connections() {
+    let m = mock!();
+
+    let response = Request::get(m.url())
+        .ssl_verify_status(true)
+        .body(())
+        .unwrap()
+        .send()
+        .unwrap();
+
+    assert_eq!(response.status(), 200);
+}
+
+#[test]
+fn fresh_connect_does_not_reuse_cached_connection() {
+    let m = mock! {
+        body: "hello world",
+    };
+
+    let client = HttpClient::new().unwrap();
+
+    client.get(m.url()).unwrap().consume().unwrap();
+
+    let request = Request::get(m.url())
+        .fresh_connect(true)
+        .body(())
+        .unwrap();
+
+    let mut response = client.send(request).unwrap();
+    response.consume().unwrap();
+
+    assert_eq!(response.connection_reused(), Some(false));
+}
+
+#[test]
+fn forbid_reuse_closes_connection_after_request() {
+    let m = mock! {
+        body: "hello world",
+    };
+
+    let client = HttpClient::builder().forbid_reuse(true).build().unwrap();
+
+    client.get(m.url()).unwrap().consume().unwrap();
+
+    let mut response = client.get(m.url()).unwrap();
+    response.consume().unwrap();
+
+    assert_eq!(response.connection_reused(), Some(false));
+}
+
+#[test]
+fn requests_succeed_with_happy_eyeballs_timeout() {
+    let m = mock!();
+
+    let client = HttpClient::builder()
+        .happy_eyeballs_timeout(Duration::from_millis(0))
+        .build()
+        .unwrap();
+
+    assert_eq!(client.get(m.url()).unwrap().status(), 200);
+    assert_eq!(m.requests_received(), 1);
+}
@@ -1,4 +1,10 @@
-use isahc::{prelude::*, Request};
+use isahc::{
+    auth::{Authentication, Credentials},
+    config::ResolveMap,
+    prelude::*,
+    HttpClient,
+    Request,
+};
 use testserver::{mock, socks4::Socks4Server};
 
 #[test]
@@ -42,6 +48,24 @@ fn http_proxy() {
     m.request().expect_header("proxy-connection", "Keep-Alive");
 }
 
+#[test]
+fn http_proxy_with_basic_credentials() {
+    let m = mock!();
+    let proxy = m.url().parse::<http::Uri>().unwrap();
+
+    Request::get("http://127.0.0.2:1234/")
+        .proxy(proxy)
+        .proxy_authentication(Authentication::basic())
+        .proxy_credentials(Credentials::new("clark", "qwerty"))
+        .body(())
+        .unwrap()
+        .send()
+        .unwrap();
+
+    m.request()
+        .expect_header("proxy-authorization", "Basic Y2xhcms6cXdlcnR5");
+}
+
 #[test]
 #[cfg_attr(tarpaulin, ignore)]
 fn socks4_proxy() {
@@ -95,3 +119,22 @@ fn proxy_blacklist_works() {
 
     assert_eq!(m.requests_received(), 1);
 }
+
+#[test]
+fn proxy_blacklist_matches_subdomains() {
+    let proxy = "http://127.0.0.2:1234/".parse::<http::Uri>().unwrap();
+    let m = mock!();
+
+    let client = HttpClient::builder()
+        .dns_resolve(ResolveMap::new().add("api.internal.test", m.addr().port(), m.addr().ip()))
+        .proxy(proxy)
+        .proxy_blacklist(vec!["internal.test"])
+        .build()
+        .unwrap();
+
+    client
+        .get(format!("http://api.internal.test:{}/", m.addr().port()))
+        .unwrap();
+
+    assert_eq!(m.requests_received(), 1);
+}
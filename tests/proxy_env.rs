@@ -0,0 +1,41 @@
+//! Tests for proxies configured using environment variables. These live in
+//! their own test binary, since environment variables are shared by every test
+//! in a process.
+
+use isahc::{prelude::*, Request};
+use std::env;
+use testserver::mock;
+
+#[test]
+fn proxy_and_no_proxy_are_read_from_environment() {
+    let proxy = mock!();
+    let upstream = mock!();
+
+    // Use a different host name for the proxy than for the upstream server, so
+    // that only the latter matches the bypass list.
+    env::set_var("http_proxy", format!("http://localhost:{}", proxy.addr().port()));
+    env::set_var("no_proxy", "127.0.0.1");
+
+    // Requests go through the proxy by default...
+    Request::get("http://127.0.0.2:1234/")
+        .body(())
+        .unwrap()
+        .send()
+        .unwrap();
+
+    assert_eq!(proxy.requests_received(), 1);
+    assert_eq!(proxy.request().url(), "http://127.0.0.2:1234/");
+
+    // ...unless the host is listed in the bypass list.
+    Request::get(upstream.url())
+        .body(())
+        .unwrap()
+        .send()
+        .unwrap();
+
+    assert_eq!(proxy.requests_received(), 1);
+    assert_eq!(upstream.requests_received(), 1);
+
+    env::remove_var("http_proxy");
+    env::remove_var("no_proxy");
+}
@@ -1,4 +1,11 @@
-use isahc::{config::RedirectPolicy, prelude::*, Body, HttpClient, Request};
+use isahc::{
+    config::{RedirectAction, RedirectPolicy},
+    prelude::*,
+    Body,
+    HttpClient,
+    Request,
+};
+use std::sync::{Arc, Mutex};
 use test_case::test_case;
 use testserver::mock;
 
@@ -16,6 +23,7 @@ fn response_301_no_follow() {
     assert_eq!(response.status(), 301);
     assert_eq!(response.headers()["Location"], "/2");
     assert_eq!(response.effective_uri().unwrap().path(), "/");
+    assert_eq!(response.redirect_count(), 0);
 
     assert_eq!(m.requests_received(), 1);
 }
@@ -45,11 +53,38 @@ fn response_301_auto_follow() {
     assert_eq!(response.status(), 200);
     assert_eq!(response.text().unwrap(), "ok");
     assert_eq!(response.effective_uri().unwrap().to_string(), m2.url());
+    assert_eq!(response.redirect_count(), 1);
 
     assert_eq!(m1.requests_received(), 1);
     assert_eq!(m2.requests_received(), 1);
 }
 
+#[test]
+fn request_redirect_policy_overrides_client() {
+    let m = mock! {
+        status: 301,
+        headers {
+            "Location": "/2",
+        }
+    };
+
+    let client = HttpClient::builder()
+        .redirect_policy(RedirectPolicy::Follow)
+        .build()
+        .unwrap();
+
+    let request = Request::get(m.url())
+        .redirect_policy(RedirectPolicy::None)
+        .body(())
+        .unwrap();
+
+    let response = client.send(request).unwrap();
+
+    assert_eq!(response.status(), 301);
+    assert_eq!(response.redirect_count(), 0);
+    assert_eq!(m.requests_received(), 1);
+}
+
 #[test]
 fn headers_are_reset_every_redirect() {
     let m2 = mock! {
@@ -86,6 +121,60 @@ fn headers_are_reset_every_redirect() {
     assert_eq!(m2.requests_received(), 1);
 }
 
+#[test]
+fn sensitive_headers_are_removed_when_redirecting_to_another_host() {
+    let m2 = mock!();
+    let location = m2.url();
+
+    let m1 = mock! {
+        status: 301,
+        headers {
+            "Location": location,
+        }
+    };
+
+    let response = Request::get(m1.url())
+        .redirect_policy(RedirectPolicy::Follow)
+        .header("Authorization", "Bearer secret")
+        .header("Cookie", "session=secret")
+        .body(())
+        .unwrap()
+        .send()
+        .unwrap();
+
+    assert_eq!(response.status(), 200);
+
+    m1.request().expect_header("Authorization", "Bearer secret");
+    assert_eq!(m2.request().get_header("Authorization").count(), 0);
+    assert_eq!(m2.request().get_header("Cookie").count(), 0);
+}
+
+#[test]
+fn sensitive_headers_are_kept_when_redirecting_to_a_trusted_host() {
+    let m2 = mock!();
+    let location = m2.url();
+
+    let m1 = mock! {
+        status: 301,
+        headers {
+            "Location": location,
+        }
+    };
+
+    let response = Request::get(m1.url())
+        .redirect_policy(RedirectPolicy::Follow)
+        .trust_redirect_hosts(vec!["127.0.0.1"])
+        .header("Authorization", "Bearer secret")
+        .body(())
+        .unwrap()
+        .send()
+        .unwrap();
+
+    assert_eq!(response.status(), 200);
+
+    m2.request().expect_header("Authorization", "Bearer secret");
+}
+
 #[test_case(301)]
 #[test_case(302)]
 #[test_case(303)]
@@ -243,8 +332,14 @@ fn redirect_limit_is_respected() {
 
     // Request should error with too many redirects.
     assert_eq!(error, isahc::error::ErrorKind::TooManyRedirects);
+    assert!(error.is_too_many_redirects());
     assert_eq!(error.remote_addr(), Some(m.addr()));
 
+    // The message should say what the limit was and where we got stuck.
+    let message = error.to_string();
+    assert!(message.contains("limit of 5 redirects"));
+    assert!(message.contains(&format!("{}next", m.url())));
+
     // After request (limit + 1) that returns a redirect should error.
     assert_eq!(m.requests_received(), 6);
 }
@@ -318,3 +413,304 @@ fn redirect_with_unencoded_utf8_bytes_in_location() {
     assert_eq!(m1.requests_received(), 1);
     assert_eq!(m2.requests_received(), 1);
 }
+
+#[test]
+fn custom_policy_receives_redirect_attempt() {
+    let m2 = mock! {
+        body: "ok",
+    };
+    let location = m2.url();
+
+    let m1 = mock! {
+        status: 302,
+        headers {
+            "Location": location,
+        }
+    };
+
+    let attempts = Arc::new(Mutex::new(Vec::new()));
+
+    let mut response = Request::get(m1.url())
+        .redirect_policy(RedirectPolicy::custom({
+            let attempts = attempts.clone();
+            move |attempt| {
+                attempts.lock().unwrap().push((
+                    attempt.status(),
+                    attempt.uri().to_string(),
+                    attempt.location().to_string(),
+                    attempt.redirects(),
+                ));
+                RedirectAction::Follow
+            }
+        }))
+        .body(())
+        .unwrap()
+        .send()
+        .unwrap();
+
+    assert_eq!(response.status(), 200);
+    assert_eq!(response.text().unwrap(), "ok");
+    assert_eq!(response.redirect_count(), 1);
+    assert_eq!(
+        *attempts.lock().unwrap(),
+        vec![(http::StatusCode::FOUND, m1.url(), m2.url(), 0)]
+    );
+}
+
+#[test]
+fn custom_policy_can_stop_redirect() {
+    let m2 = mock!();
+    let location = m2.url();
+
+    let m1 = mock! {
+        status: 301,
+        headers {
+            "Location": location,
+        }
+    };
+
+    let response = Request::get(m1.url())
+        .redirect_policy(RedirectPolicy::custom(|_| RedirectAction::Stop))
+        .body(())
+        .unwrap()
+        .send()
+        .unwrap();
+
+    assert_eq!(response.status(), 301);
+    assert_eq!(response.effective_uri().unwrap().to_string(), m1.url());
+    assert_eq!(response.redirect_count(), 0);
+
+    assert_eq!(m1.requests_received(), 1);
+    assert_eq!(m2.requests_received(), 0);
+}
+
+#[test]
+fn custom_policy_can_rewrite_location() {
+    let m3 = mock! {
+        body: "rewritten",
+    };
+    let m2 = mock!();
+    let location = m2.url();
+
+    let m1 = mock! {
+        status: 302,
+        headers {
+            "Location": location,
+        }
+    };
+
+    let rewritten = m3.url().parse::<http::Uri>().unwrap();
+
+    let mut response = Request::get(m1.url())
+        .redirect_policy(RedirectPolicy::custom(move |_| {
+            RedirectAction::FollowTo(rewritten.clone())
+        }))
+        .body(())
+        .unwrap()
+        .send()
+        .unwrap();
+
+    assert_eq!(response.text().unwrap(), "rewritten");
+    assert_eq!(response.effective_uri().unwrap().to_string(), m3.url());
+
+    assert_eq!(m2.requests_received(), 0);
+    assert_eq!(m3.requests_received(), 1);
+}
+
+#[test]
+fn redirect_history_records_each_hop() {
+    let m3 = mock! {
+        body: "ok",
+    };
+    let location = m3.url();
+
+    let m2 = mock! {
+        status: 302,
+        headers {
+            "Location": location,
+        }
+    };
+    let location = m2.url();
+
+    let m1 = mock! {
+        status: 301,
+        headers {
+            "Location": location,
+        }
+    };
+
+    let response = Request::get(m1.url())
+        .redirect_policy(RedirectPolicy::Follow)
+        .body(())
+        .unwrap()
+        .send()
+        .unwrap();
+
+    let history = response
+        .redirect_history()
+        .iter()
+        .map(|(uri, status)| (uri.to_string(), status.as_u16()))
+        .collect::<Vec<_>>();
+
+    assert_eq!(history, vec![(m1.url(), 301), (m2.url(), 302)]);
+    assert_eq!(response.effective_uri().unwrap().to_string(), m3.url());
+}
+
+#[test]
+fn redirect_history_is_empty_if_not_followed() {
+    let m = mock! {
+        status: 301,
+        headers {
+            "Location": "/2",
+        }
+    };
+
+    let response = isahc::get(m.url()).unwrap();
+
+    assert!(response.redirect_history().is_empty());
+}
+
+#[test]
+fn redirect_https_only_refuses_redirect_to_http() {
+    let m2 = mock!();
+    let location = m2.url();
+
+    let m1 = mock! {
+        status: 302,
+        headers {
+            "Location": location,
+        }
+    };
+
+    let result = Request::get(m1.url())
+        .redirect_policy(RedirectPolicy::Follow)
+        .redirect_https_only()
+        .body(())
+        .unwrap()
+        .send();
+
+    assert!(matches!(result, Err(e) if e == isahc::error::ErrorKind::InsecureRedirect));
+    assert_eq!(m1.requests_received(), 1);
+    assert_eq!(m2.requests_received(), 0);
+}
+
+#[test]
+fn deny_redirect_downgrade_allows_http_to_http() {
+    let m2 = mock! {
+        body: "ok",
+    };
+    let location = m2.url();
+
+    let m1 = mock! {
+        status: 302,
+        headers {
+            "Location": location,
+        }
+    };
+
+    let mut response = Request::get(m1.url())
+        .redirect_policy(RedirectPolicy::Follow)
+        .deny_redirect_downgrade()
+        .body(())
+        .unwrap()
+        .send()
+        .unwrap();
+
+    assert_eq!(response.text().unwrap(), "ok");
+    assert_eq!(m2.requests_received(), 1);
+}
+
+#[test_case(307)]
+#[test_case(308)]
+fn redirect_resends_request_body(status: u16) {
+    let m2 = mock!();
+    let location = m2.url();
+
+    let m1 = mock! {
+        status: status,
+        headers {
+            "Location": location,
+        }
+    };
+
+    let response = Request::post(m1.url())
+        .redirect_policy(RedirectPolicy::Follow)
+        .header("Content-Type", "text/plain")
+        .body("hello world")
+        .unwrap()
+        .send()
+        .unwrap();
+
+    assert_eq!(response.status(), 200);
+
+    m1.request().expect_body("hello world");
+    assert_eq!(m2.request().method(), "POST");
+    m2.request().expect_header("Content-Type", "text/plain");
+    m2.request().expect_body("hello world");
+}
+
+#[test_case(301)]
+#[test_case(302)]
+#[test_case(303)]
+fn redirect_to_get_drops_request_body(status: u16) {
+    let m2 = mock!();
+    let location = m2.url();
+
+    let m1 = mock! {
+        status: status,
+        headers {
+            "Location": location,
+        }
+    };
+
+    // A streaming body can't be rewound, but doesn't need to be for a GET.
+    let upload_stream = Body::from_reader(Body::from_bytes_static(b"hello world"));
+
+    let response = Request::post(m1.url())
+        .redirect_policy(RedirectPolicy::Follow)
+        .header("Content-Type", "text/plain")
+        .body(upload_stream)
+        .unwrap()
+        .send()
+        .unwrap();
+
+    assert_eq!(response.status(), 200);
+
+    assert_eq!(m2.request().method(), "GET");
+    assert_eq!(m2.request().get_header("Content-Type").count(), 0);
+    m2.request().expect_body("");
+}
+
+#[test]
+fn redirect_keeps_head_request() {
+    let m2 = mock!();
+    let location = m2.url();
+
+    let m1 = mock! {
+        status: 301,
+        headers {
+            "Location": location,
+        }
+    };
+
+    let response = Request::head(m1.url())
+        .redirect_policy(RedirectPolicy::Follow)
+        .body(())
+        .unwrap()
+        .send()
+        .unwrap();
+
+    assert_eq!(response.status(), 200);
+    assert_eq!(m2.request().method(), "HEAD");
+}
+
+#[test]
+fn redirect_policies_can_be_compared() {
+    let custom = RedirectPolicy::custom(|_| RedirectAction::Stop);
+
+    assert_eq!(RedirectPolicy::default(), RedirectPolicy::None);
+    assert_eq!(RedirectPolicy::Limit(5), RedirectPolicy::Limit(5));
+    assert_ne!(RedirectPolicy::Limit(5), RedirectPolicy::Follow);
+    assert_eq!(custom.clone(), custom);
+    assert_ne!(custom, RedirectPolicy::custom(|_| RedirectAction::Stop));
+}
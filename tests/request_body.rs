@@ -68,6 +68,23 @@ fn request_with_body_of_unknown_size_uses_chunked_encoding(method: &str) {
     m.request().expect_body(body);
 }
 
+#[test]
+fn request_with_sized_reader_body_uses_content_length() {
+    let body = "hello world";
+
+    let m = mock!();
+
+    Request::post(m.url())
+        .body(Body::from_reader_sized(body.as_bytes(), body.len() as u64))
+        .unwrap()
+        .send()
+        .unwrap();
+
+    m.request().expect_header("content-length", "11");
+    assert_eq!(m.request().get_header("transfer-encoding").count(), 0);
+    m.request().expect_body(body);
+}
+
 #[test_case("GET")]
 #[test_case("HEAD")]
 #[test_case("POST")]
@@ -151,3 +168,144 @@ fn upload_from_bad_async_reader_returns_error_with_original_cause() {
         io::ErrorKind::UnexpectedEof
     );
 }
+
+#[test]
+fn upload_from_async_reader() {
+    let body = "hello world";
+
+    let m = mock!();
+
+    block_on(async {
+        Request::post(m.url())
+            .body(AsyncBody::from_reader(futures_lite::io::Cursor::new(body)))
+            .unwrap()
+            .send_async()
+            .await
+            .unwrap();
+    });
+
+    m.request().expect_header("transfer-encoding", "chunked");
+    m.request().expect_body(body);
+}
+
+#[test]
+fn upload_from_sized_async_reader_uses_content_length() {
+    let body = "hello world";
+
+    let m = mock!();
+
+    block_on(async {
+        Request::post(m.url())
+            .body(AsyncBody::from_reader_sized(
+                futures_lite::io::Cursor::new(body),
+                body.len() as u64,
+            ))
+            .unwrap()
+            .send_async()
+            .await
+            .unwrap();
+    });
+
+    m.request().expect_header("content-length", "11");
+    m.request().expect_body(body);
+}
+
+#[test]
+fn upload_from_stream() {
+    let m = mock!();
+
+    let chunks = futures_lite::stream::iter(vec![
+        Ok::<_, io::Error>("hello "),
+        Ok("world"),
+    ]);
+
+    block_on(async {
+        isahc::post_async(m.url(), AsyncBody::from_stream(chunks))
+            .await
+            .unwrap();
+    });
+
+    m.request().expect_header("transfer-encoding", "chunked");
+    m.request().expect_body("hello world");
+}
+
+#[test]
+fn upload_from_stream_with_error_returns_error() {
+    let m = mock!();
+
+    let chunks = futures_lite::stream::iter(vec![
+        Ok("hello "),
+        Err(io::Error::new(io::ErrorKind::UnexpectedEof, "stream ended")),
+    ]);
+
+    let result = block_on(async { isahc::post_async(m.url(), AsyncBody::from_stream(chunks)).await });
+
+    assert_matches!(&result, Err(e) if e.kind() == isahc::error::ErrorKind::Io);
+}
+
+#[test]
+fn multipart_form_request_body() {
+    let m = mock!();
+
+    let form = isahc::multipart::Form::new().text("foo", "bar");
+    let content_type = form.content_type();
+    let boundary = form.boundary().to_owned();
+
+    Request::post(m.url())
+        .multipart(form)
+        .unwrap()
+        .send()
+        .unwrap();
+
+    m.request().expect_header("content-type", content_type);
+    m.request().expect_body(format!(
+        "--{0}\r\nContent-Disposition: form-data; name=\"foo\"\r\n\r\nbar\r\n--{0}--\r\n",
+        boundary
+    ));
+}
+
+#[test]
+fn multipart_form_replaces_existing_content_type() {
+    let m = mock!();
+
+    let form = isahc::multipart::Form::new().text("foo", "bar");
+    let content_type = form.content_type();
+
+    Request::post(m.url())
+        .header("content-type", "text/plain")
+        .multipart(form)
+        .unwrap()
+        .send()
+        .unwrap();
+
+    m.request().expect_header("content-type", content_type);
+    assert_eq!(m.request().get_header("content-type").count(), 1);
+}
+
+#[test]
+fn multipart_form_body_sets_content_type() {
+    let m = mock!();
+
+    let form = isahc::multipart::Form::new().text("foo", "bar");
+    let content_type = form.content_type();
+
+    isahc::post(m.url(), form).unwrap();
+
+    m.request().expect_header("content-type", content_type);
+}
+
+#[test]
+fn upload_buffer_size_sends_entire_body() {
+    let body = "a".repeat(100_000);
+
+    let m = mock!();
+
+    Request::post(m.url())
+        .upload_buffer_size(16 * 1024)
+        .body(body.clone())
+        .unwrap()
+        .send()
+        .unwrap();
+
+    m.request().expect_body(body);
+}
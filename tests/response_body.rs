@@ -1,6 +1,12 @@
 use futures_lite::{future::block_on, io::AsyncReadExt};
-use isahc::prelude::*;
-use std::{io, io::Read};
+use isahc::{error::ErrorKind, prelude::*, Request};
+use std::{
+    io,
+    io::{BufRead, Read, Write},
+    net::{Shutdown, TcpListener},
+    thread,
+    time::Duration,
+};
 use testserver::mock;
 
 #[macro_use]
@@ -73,6 +79,231 @@ fn large_response_body() {
     assert_eq!(response_text, body);
 }
 
+#[test]
+fn response_body_can_be_read_line_by_line() {
+    let m = mock! {
+        body: "a,1\nb,2\nc,3\n",
+    };
+
+    let body = isahc::get(m.url()).unwrap().into_body();
+    let lines = io::BufReader::new(body)
+        .lines()
+        .collect::<io::Result<Vec<_>>>()
+        .unwrap();
+
+    assert_eq!(lines, vec!["a,1", "b,2", "c,3"]);
+}
+
+#[test]
+fn copy_response_body_with_std_io() {
+    let m = mock! {
+        body: "hello world",
+    };
+
+    let mut body = isahc::get(m.url()).unwrap().into_body();
+    let mut copied = Vec::new();
+
+    assert_eq!(io::copy(&mut body, &mut copied).unwrap(), 11);
+    assert_eq!(copied, b"hello world");
+}
+
+#[test]
+fn large_response_body_read_in_small_chunks_async() {
+    let body = "wow so large ".repeat(1000);
+
+    let m = {
+        let body = body.clone();
+        mock! {
+            body: body.clone(),
+        }
+    };
+
+    let received = block_on(async move {
+        let mut body = isahc::get_async(m.url()).await.unwrap().into_body();
+        let mut received = Vec::new();
+        let mut buf = [0; 7];
+
+        loop {
+            match body.read(&mut buf).await.unwrap() {
+                0 => break,
+                len => received.extend_from_slice(&buf[..len]),
+            }
+        }
+
+        received
+    });
+
+    assert_eq!(received, body.as_bytes());
+}
+
+#[test]
+fn copy_async_response_body_with_futures_io() {
+    let m = mock! {
+        body: "hello world",
+    };
+
+    let copied = block_on(async move {
+        let body = isahc::get_async(m.url()).await.unwrap().into_body();
+        let mut copied = Vec::new();
+
+        futures_lite::io::copy(body, &mut copied).await.unwrap();
+
+        copied
+    });
+
+    assert_eq!(copied, b"hello world");
+}
+
+#[test]
+fn response_body_larger_than_max_size_returns_error() {
+    let m = mock! {
+        body: "wow so large ".repeat(1000),
+        transfer_encoding: true,
+    };
+
+    let result = Request::get(m.url())
+        .max_response_body_size(100)
+        .body(())
+        .unwrap()
+        .send()
+        .and_then(|mut response| response.text().map_err(Into::into));
+
+    assert_matches!(result, Err(e) if e.kind() == ErrorKind::ResponseBodyTooLarge);
+}
+
+#[test]
+fn response_body_in_small_chunks_larger_than_max_size_returns_error() {
+    use std::{
+        io::Write,
+        net::TcpListener,
+        thread,
+        time::Duration,
+    };
+
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+
+    // Send the body in many chunks that are each well within the limit, but
+    // add up to more than it.
+    thread::spawn(move || {
+        let mut stream = listener.accept().unwrap().0;
+        let mut request = Vec::new();
+        let mut buf = [0; 1024];
+
+        while !request.ends_with(b"\r\n\r\n") {
+            let len = stream.read(&mut buf).unwrap();
+            request.extend_from_slice(&buf[..len]);
+        }
+
+        stream
+            .write_all(b"HTTP/1.1 200 OK\r\ntransfer-encoding: chunked\r\n\r\n")
+            .unwrap();
+
+        for _ in 0..10 {
+            if stream.write_all(b"32\r\n").is_err()
+                || stream.write_all(&[b'a'; 50]).is_err()
+                || stream.write_all(b"\r\n").is_err()
+            {
+                return;
+            }
+
+            thread::sleep(Duration::from_millis(20));
+        }
+
+        let _ = stream.write_all(b"0\r\n\r\n");
+    });
+
+    let result = Request::get(url)
+        .max_response_body_size(200)
+        .body(())
+        .unwrap()
+        .send()
+        .and_then(|mut response| response.text().map_err(Into::into));
+
+    assert_matches!(result, Err(e) if e.kind() == ErrorKind::ResponseBodyTooLarge);
+}
+
+#[test]
+fn request_body_does_not_count_towards_max_response_body_size() {
+    let m = mock! {
+        body: "hello world",
+    };
+
+    let mut response = Request::post(m.url())
+        .max_response_body_size(11)
+        .body("a much larger request body ".repeat(100))
+        .unwrap()
+        .send()
+        .unwrap();
+
+    assert_eq!(response.text().unwrap(), "hello world");
+}
+
+#[test]
+fn response_body_within_max_size_is_read_fully() {
+    let m = mock! {
+        body: "hello world",
+    };
+
+    let mut response = Request::get(m.url())
+        .max_response_body_size(11)
+        .body(())
+        .unwrap()
+        .send()
+        .unwrap();
+
+    assert_eq!(response.text().unwrap(), "hello world");
+}
+
+/// Start a server that responds to a single request with the given raw
+/// response and then closes the connection.
+fn serve_raw_response(response: Vec<u8>) -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+
+    thread::spawn(move || {
+        let mut stream = listener.accept().unwrap().0;
+        let mut request = Vec::new();
+        let mut buf = [0; 1024];
+
+        while !request.ends_with(b"\r\n\r\n") {
+            let len = stream.read(&mut buf).unwrap();
+            request.extend_from_slice(&buf[..len]);
+        }
+
+        stream.write_all(&response).unwrap();
+        let _ = stream.shutdown(Shutdown::Both);
+    });
+
+    url
+}
+
+#[test]
+fn response_body_shorter_than_content_length_returns_error() {
+    let mut response = b"HTTP/1.1 200 OK\r\ncontent-length: 1000\r\n\r\n".to_vec();
+    response.extend_from_slice(&[b'a'; 800]);
+
+    let url = serve_raw_response(response);
+
+    let result = isahc::get(url).and_then(|mut response| {
+        let mut body = Vec::new();
+        response.copy_to(&mut body)?;
+        Ok(body)
+    });
+
+    assert_matches!(result, Err(e) if e.kind() == ErrorKind::ResponseBodyTruncated);
+}
+
+#[test]
+fn head_response_with_content_length_and_no_body_is_not_truncated() {
+    let url = serve_raw_response(b"HTTP/1.1 200 OK\r\ncontent-length: 1000\r\n\r\n".to_vec());
+
+    let mut response = Request::head(url).body(()).unwrap().send().unwrap();
+
+    assert_eq!(response.status(), 200);
+    assert_eq!(response.text().unwrap(), "");
+}
+
 #[test]
 fn response_body_with_content_length_knows_its_size() {
     let m = mock! {
@@ -148,6 +379,50 @@ fn reading_from_response_body_after_eof_continues_to_return_eof() {
     }
 }
 
+#[test]
+fn copy_response_body_to_writer() {
+    let body = "wow so large ".repeat(1000);
+
+    let m = {
+        let body = body.clone();
+        mock! {
+            body: body.clone(),
+        }
+    };
+
+    let mut buf = Vec::new();
+    let len = isahc::get(m.url()).unwrap().copy_to(&mut buf).unwrap();
+
+    assert_eq!(len, body.len() as u64);
+    assert_eq!(buf, body.as_bytes());
+}
+
+#[test]
+fn copy_response_body_propagates_writer_errors() {
+    struct FailingWriter;
+
+    impl io::Write for FailingWriter {
+        fn write(&mut self, _: &[u8]) -> io::Result<usize> {
+            Err(io::Error::new(io::ErrorKind::Other, "disk full"))
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    let m = mock! {
+        body: "hello world",
+    };
+
+    let error = isahc::get(m.url())
+        .unwrap()
+        .copy_to(FailingWriter)
+        .unwrap_err();
+
+    assert_eq!(error.to_string(), "disk full");
+}
+
 #[test]
 fn consume_unread_response_body() {
     let body = "wow so large ".repeat(1000);
@@ -189,3 +464,199 @@ fn consume_unread_response_body_async() {
         assert_matches!(response.body_mut().read(&mut buf).await, Ok(0));
     });
 }
+
+#[test]
+fn small_buffer_size_receives_entire_body() {
+    let body = "a".repeat(100_000);
+
+    let m = {
+        let body = body.clone();
+        mock! {
+            body: body.clone(),
+        }
+    };
+
+    let mut response = Request::get(m.url())
+        .buffer_size(1024)
+        .body(())
+        .unwrap()
+        .send()
+        .unwrap();
+
+    assert_eq!(response.text().unwrap(), body);
+}
+
+#[test]
+fn text_uses_declared_charset() {
+    let m = mock! {
+        headers {
+            "content-type": "text/plain; charset=iso-8859-1",
+        }
+        body: &b"caf\xe9"[..],
+    };
+
+    assert_eq!(isahc::get(m.url()).unwrap().text().unwrap(), "caf\u{e9}");
+}
+
+#[test]
+fn text_with_charset_uses_default_if_not_declared() {
+    let m = mock! {
+        body: &b"caf\xe9"[..],
+    };
+
+    let text = isahc::get(m.url())
+        .unwrap()
+        .text_with_charset("latin1")
+        .unwrap();
+
+    assert_eq!(text, "caf\u{e9}");
+}
+
+#[test]
+fn text_with_charset_prefers_declared_charset() {
+    let m = mock! {
+        headers {
+            "content-type": "text/plain; charset=utf-8",
+        }
+        body: "caf\u{e9}",
+    };
+
+    let text = isahc::get(m.url())
+        .unwrap()
+        .text_with_charset("latin1")
+        .unwrap();
+
+    assert_eq!(text, "caf\u{e9}");
+}
+
+#[test]
+fn text_with_unknown_charset_returns_error() {
+    let m = mock! {
+        body: "hello world",
+    };
+
+    let error = isahc::get(m.url())
+        .unwrap()
+        .text_with_charset("not-a-charset")
+        .unwrap_err();
+
+    assert_eq!(error.kind(), io::ErrorKind::InvalidInput);
+}
+
+#[test]
+fn text_replaces_malformed_characters() {
+    let m = mock! {
+        body: &b"caf\xe9"[..],
+    };
+
+    assert_eq!(isahc::get(m.url()).unwrap().text().unwrap(), "caf\u{fffd}");
+}
+
+#[test]
+fn text_strict_returns_error_for_malformed_characters() {
+    let m = mock! {
+        body: &b"caf\xe9"[..],
+    };
+
+    let error = isahc::get(m.url()).unwrap().text_strict().unwrap_err();
+
+    assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+}
+
+#[test]
+fn text_strict_async() {
+    let m = mock! {
+        body: "caf\u{e9}",
+    };
+
+    let text = block_on(async {
+        isahc::get_async(m.url())
+            .await
+            .unwrap()
+            .text_strict()
+            .await
+    })
+    .unwrap();
+
+    assert_eq!(text, "caf\u{e9}");
+}
+
+#[test]
+fn read_buffer_size_pauses_transfer_until_body_is_read() {
+    let body_len = 10 * 1024 * 1024;
+    let mut response =
+        format!("HTTP/1.1 200 OK\r\ncontent-length: {}\r\n\r\n", body_len).into_bytes();
+    response.resize(response.len() + body_len, b'a');
+
+    let url = serve_raw_response(response);
+
+    let client = isahc::HttpClient::builder()
+        .metrics(true)
+        .read_buffer_size(1)
+        .build()
+        .unwrap();
+
+    let mut response = client.get(url).unwrap();
+
+    // Give the client a chance to buffer as much as it is allowed to.
+    thread::sleep(Duration::from_millis(200));
+
+    // Only a chunk or two may be received without being read.
+    let (downloaded, _) = response.metrics().unwrap().download_progress();
+    assert!(downloaded < 64 * 1024, "downloaded {} bytes", downloaded);
+
+    // Reading resumes the transfer.
+    let mut body = Vec::new();
+    response.body_mut().read_to_end(&mut body).unwrap();
+    assert_eq!(body.len(), body_len);
+}
+
+#[test]
+fn copy_to_file_writes_body_and_truncates_existing_file() {
+    let m = mock! {
+        body: "hello world",
+    };
+
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("body.txt");
+
+    std::fs::write(&path, "a much longer body that should be replaced").unwrap();
+
+    let len = isahc::get(m.url()).unwrap().copy_to_file(&path).unwrap();
+
+    assert_eq!(len, 11);
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), "hello world");
+}
+
+#[test]
+fn copy_to_file_reports_transfer_errors() {
+    let mut response = b"HTTP/1.1 200 OK\r\ncontent-length: 1000\r\n\r\n".to_vec();
+    response.extend_from_slice(&[b'a'; 800]);
+
+    let url = serve_raw_response(response);
+    let dir = tempfile::tempdir().unwrap();
+
+    let error = isahc::get(url)
+        .unwrap()
+        .copy_to_file(dir.path().join("body.txt"))
+        .unwrap_err();
+
+    assert_eq!(isahc::Error::from(error), ErrorKind::ResponseBodyTruncated);
+}
+
+#[test]
+fn copy_to_file_reports_file_errors() {
+    let m = mock! {
+        body: "hello world",
+    };
+
+    let dir = tempfile::tempdir().unwrap();
+
+    // The directory cannot be opened as a file.
+    let error = isahc::get(m.url())
+        .unwrap()
+        .copy_to_file(dir.path())
+        .unwrap_err();
+
+    assert_eq!(isahc::Error::from(error), ErrorKind::Io);
+}
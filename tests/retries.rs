@@ -0,0 +1,170 @@
+use isahc::{config::RetryPolicy, http::StatusCode, prelude::*, Body, HttpClient, Request};
+use std::{
+    sync::{
+        atomic::{AtomicU32, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+use testserver::mock;
+
+#[test]
+fn requests_are_not_retried_by_default() {
+    let m = mock! {
+        status: 503,
+    };
+
+    let response = isahc::get(m.url()).unwrap();
+
+    assert_eq!(response.status(), 503);
+    assert_eq!(response.retry_count(), 0);
+    assert_eq!(m.requests_received(), 1);
+}
+
+#[test]
+fn retry_limit_retries_until_success() {
+    let m = mock! {
+        #0 => {
+            status: 503,
+            headers {
+                "retry-after": "0",
+            }
+        },
+        #1 => {
+            status: 502,
+        },
+        _ => {
+            body: "success",
+        },
+    };
+
+    let mut response = Request::get(m.url())
+        .retry_policy(RetryPolicy::Limit(3))
+        .body(())
+        .unwrap()
+        .send()
+        .unwrap();
+
+    assert_eq!(response.status(), 200);
+    assert_eq!(response.text().unwrap(), "success");
+    assert_eq!(response.retry_count(), 2);
+    assert!(response.last_retry_error().is_none());
+    assert_eq!(m.requests_received(), 3);
+}
+
+#[test]
+fn retry_limit_returns_last_response_when_exhausted() {
+    let m = mock! {
+        status: 503,
+        headers {
+            "retry-after": "0",
+        }
+    };
+
+    let response = Request::get(m.url())
+        .retry_policy(RetryPolicy::Limit(2))
+        .body(())
+        .unwrap()
+        .send()
+        .unwrap();
+
+    assert_eq!(response.status(), 503);
+    assert_eq!(response.retry_count(), 2);
+    assert_eq!(m.requests_received(), 3);
+}
+
+#[test]
+fn retried_request_resends_body() {
+    let m = mock! {
+        #0 => {
+            status: 503,
+            headers {
+                "retry-after": "0",
+            }
+        },
+        _ => {},
+    };
+
+    let client = HttpClient::builder()
+        .retry_policy(RetryPolicy::Limit(1))
+        .build()
+        .unwrap();
+
+    let response = client.post(m.url(), "hello world").unwrap();
+
+    assert_eq!(response.status(), 200);
+    assert_eq!(m.requests_received(), 2);
+    m.request().expect_body("hello world");
+}
+
+#[test]
+fn streaming_body_is_not_retried() {
+    let m = mock! {
+        status: 503,
+    };
+
+    let response = Request::post(m.url())
+        .retry_policy(RetryPolicy::Limit(3))
+        .body(Body::from_reader("hello world".as_bytes()))
+        .unwrap()
+        .send()
+        .unwrap();
+
+    assert_eq!(response.status(), 503);
+    assert_eq!(response.retry_count(), 0);
+    assert_eq!(m.requests_received(), 1);
+}
+
+#[test]
+fn custom_retry_policy() {
+    let m = mock! {
+        status: 404,
+    };
+
+    let calls = Arc::new(AtomicU32::new(0));
+    let policy = {
+        let calls = calls.clone();
+
+        RetryPolicy::custom(move |ctx| {
+            calls.fetch_add(1, Ordering::SeqCst);
+
+            if ctx.status() == Some(StatusCode::NOT_FOUND) && ctx.retries() < 1 {
+                Some(Duration::from_millis(10))
+            } else {
+                None
+            }
+        })
+    };
+
+    let response = Request::get(m.url())
+        .retry_policy(policy)
+        .body(())
+        .unwrap()
+        .send()
+        .unwrap();
+
+    assert_eq!(response.status(), 404);
+    assert_eq!(response.retry_count(), 1);
+    assert_eq!(calls.load(Ordering::SeqCst), 2);
+    assert_eq!(m.requests_received(), 2);
+}
+
+#[test]
+fn retry_count_is_recorded_on_final_error() {
+    // Find a port that nothing is listening on.
+    let port = std::net::TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap()
+        .port();
+
+    let error = Request::get(format!("http://127.0.0.1:{}", port))
+        .retry_policy(RetryPolicy::Limit(2))
+        .body(())
+        .unwrap()
+        .send()
+        .unwrap_err();
+
+    assert!(error.is_network());
+    assert_eq!(error.retry_count(), 2);
+}
@@ -0,0 +1,32 @@
+#![cfg(feature = "sse")]
+
+use futures_lite::{future::block_on, StreamExt};
+use isahc::sse::EventStream;
+use testserver::mock;
+
+#[test]
+fn read_events_from_response_body() {
+    let m = mock! {
+        headers {
+            "Content-Type": "text/event-stream",
+        }
+        body: ": hello\n\nevent: greeting\nid: 1\ndata: hello\ndata: world\n\ndata: bye\n\n",
+    };
+
+    let events = block_on(async {
+        let response = isahc::get_async(m.url()).await.unwrap();
+
+        EventStream::new(response.into_body())
+            .map(Result::unwrap)
+            .collect::<Vec<_>>()
+            .await
+    });
+
+    assert_eq!(events.len(), 2);
+    assert_eq!(events[0].event(), "greeting");
+    assert_eq!(events[0].id(), Some("1"));
+    assert_eq!(events[0].data(), "hello\nworld");
+    assert_eq!(events[1].event(), "message");
+    assert_eq!(events[1].id(), Some("1"));
+    assert_eq!(events[1].data(), "bye");
+}
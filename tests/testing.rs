@@ -0,0 +1,175 @@
+#![cfg(feature = "testing")]
+
+use isahc::{
+    config::RedirectPolicy,
+    error::ErrorKind,
+    prelude::*,
+    testing::{MockTransport, TestServer},
+    HttpClient,
+    Request,
+    Response,
+};
+
+#[macro_use]
+mod utils;
+
+#[test]
+fn registered_response_is_returned() {
+    let mock = MockTransport::new();
+    mock.respond(
+        "GET",
+        "http://example.org/hello",
+        Response::builder()
+            .status(201)
+            .header("X-Mock", "yes")
+            .body("hello world")
+            .unwrap(),
+    );
+
+    let client = HttpClient::builder()
+        .mock_transport(mock.clone())
+        .build()
+        .unwrap();
+
+    let mut response = client.get("http://example.org/hello").unwrap();
+
+    assert_eq!(response.status(), 201);
+    assert_eq!(response.headers()["X-Mock"], "yes");
+    assert_eq!(response.text().unwrap(), "hello world");
+}
+
+#[test]
+fn requests_are_recorded_with_bodies() {
+    let mock = MockTransport::new();
+    mock.respond("POST", "http://example.org/echo", Response::new(""));
+
+    let client = HttpClient::builder()
+        .mock_transport(mock.clone())
+        .build()
+        .unwrap();
+
+    let request = Request::post("http://example.org/echo")
+        .header("X-Request", "1")
+        .body("request body")
+        .unwrap();
+
+    client.send(request).unwrap();
+
+    let requests = mock.requests();
+
+    assert_eq!(requests.len(), 1);
+    assert_eq!(requests[0].method(), "POST");
+    assert_eq!(requests[0].uri(), "http://example.org/echo");
+    assert_eq!(requests[0].headers()["X-Request"], "1");
+    assert_eq!(requests[0].body(), b"request body");
+}
+
+#[test]
+fn unmatched_request_fails_to_connect() {
+    let mock = MockTransport::new();
+    mock.respond("GET", "http://example.org/", Response::new(""));
+
+    let client = HttpClient::builder()
+        .mock_transport(mock.clone())
+        .build()
+        .unwrap();
+
+    assert_matches!(
+        client.post("http://example.org/", ()),
+        Err(e) if e == ErrorKind::ConnectionFailed
+    );
+    assert_matches!(
+        client.get("http://example.org/other"),
+        Err(e) if e == ErrorKind::ConnectionFailed
+    );
+    assert_eq!(mock.requests().len(), 2);
+}
+
+#[test]
+fn last_registered_response_wins() {
+    let mock = MockTransport::new();
+    mock.respond("GET", "http://example.org/", Response::new("first"))
+        .respond("GET", "http://example.org/", Response::new("second"));
+
+    let client = HttpClient::builder()
+        .mock_transport(mock.clone())
+        .build()
+        .unwrap();
+
+    let mut response = client.get("http://example.org/").unwrap();
+
+    assert_eq!(response.text().unwrap(), "second");
+}
+
+#[test]
+fn redirects_are_followed_across_mock_responses() {
+    let mock = MockTransport::new();
+    mock.respond(
+        "GET",
+        "http://example.org/old",
+        Response::builder()
+            .status(301)
+            .header("Location", "/new")
+            .body("")
+            .unwrap(),
+    );
+    mock.respond("GET", "http://example.org/new", Response::new("moved"));
+
+    let client = HttpClient::builder()
+        .mock_transport(mock.clone())
+        .redirect_policy(RedirectPolicy::Follow)
+        .build()
+        .unwrap();
+
+    let mut response = client.get("http://example.org/old").unwrap();
+
+    assert_eq!(response.text().unwrap(), "moved");
+    assert_eq!(response.effective_uri().unwrap(), "http://example.org/new");
+    assert_eq!(mock.requests().len(), 2);
+}
+
+#[test]
+fn test_server_responds_using_handler() {
+    let server = TestServer::new(|request| {
+        Response::builder()
+            .header("X-Path", request.uri().path())
+            .body(request.body().clone())
+            .unwrap()
+    });
+
+    let mut response = Request::post(format!("{}echo", server.endpoint()))
+        .body("hello")
+        .unwrap()
+        .send()
+        .unwrap();
+
+    assert_eq!(response.headers()["X-Path"], "/echo");
+    assert_eq!(response.text().unwrap(), "hello");
+
+    server.assert_requests_received(1);
+    server.assert_received(|request| request.method() == "POST" && request.body() == b"hello");
+}
+
+#[test]
+fn test_server_records_requests_in_order() {
+    let server = TestServer::new(|_| Response::new(""));
+
+    isahc::get(format!("{}one", server.endpoint())).unwrap();
+    isahc::get(format!("{}two?x=1", server.endpoint())).unwrap();
+
+    let requests = server.requests();
+
+    assert_eq!(requests.len(), 2);
+    assert_eq!(requests[0].uri(), "/one");
+    assert_eq!(requests[1].uri(), "/two?x=1");
+}
+
+#[test]
+fn test_server_returns_500_if_handler_panics() {
+    let server = TestServer::new(|_| -> Response<&str> { panic!("oops") });
+
+    let response = isahc::get(server.endpoint()).unwrap();
+
+    assert_eq!(response.status(), 500);
+    server.assert_requests_received(1);
+}
@@ -1,8 +1,8 @@
-use isahc::{prelude::*, Request};
+use isahc::{prelude::*, HttpClient, Request};
 use std::{
     io::{self, Cursor, Read},
     thread,
-    time::Duration,
+    time::{Duration, Instant},
 };
 use testserver::mock;
 
@@ -62,3 +62,183 @@ fn timeout_during_response_body_produces_error() {
         std::io::ErrorKind::TimedOut
     );
 }
+
+#[test]
+fn low_speed_timeout_aborts_stalled_response_body() {
+    struct SlowReader;
+
+    impl Read for SlowReader {
+        fn read(&mut self, _buf: &mut [u8]) -> io::Result<usize> {
+            thread::sleep(Duration::from_secs(3));
+            Ok(0)
+        }
+    }
+
+    let m = mock! {
+        _ => {
+            body_reader: Cursor::new(vec![0; 100_000]).chain(SlowReader),
+        },
+    };
+
+    let mut response = Request::get(m.url())
+        .low_speed_timeout(100, Duration::from_secs(1))
+        .body(())
+        .unwrap()
+        .send()
+        .unwrap();
+
+    // The transfer stalls after the first chunk of data, which should trip the
+    // low speed limit.
+    assert_eq!(
+        response.copy_to(std::io::sink()).unwrap_err().kind(),
+        std::io::ErrorKind::TimedOut
+    );
+}
+
+#[test]
+fn header_timeout_aborts_slow_response() {
+    let m = mock! {
+        delay: 3s,
+    };
+
+    let start = Instant::now();
+    let result = Request::get(m.url())
+        .header_timeout(Duration::from_millis(500))
+        .body(())
+        .unwrap()
+        .send();
+
+    assert_matches!(result, Err(e) if e == isahc::error::ErrorKind::Timeout);
+    assert!(start.elapsed() < Duration::from_secs(3));
+}
+
+#[test]
+fn header_timeout_does_not_apply_to_response_body() {
+    struct SlowReader;
+
+    impl Read for SlowReader {
+        fn read(&mut self, _buf: &mut [u8]) -> io::Result<usize> {
+            thread::sleep(Duration::from_secs(2));
+            Ok(0)
+        }
+    }
+
+    let m = mock! {
+        _ => {
+            body_reader: Cursor::new(vec![0; 100_000]).chain(SlowReader),
+        },
+    };
+
+    let mut response = Request::get(m.url())
+        .header_timeout(Duration::from_millis(500))
+        .body(())
+        .unwrap()
+        .send()
+        .unwrap();
+
+    // Reading the body takes longer than the header timeout, which is fine.
+    assert_eq!(response.copy_to(std::io::sink()).unwrap(), 100_000);
+}
+
+#[test]
+fn total_timeout_aborts_slow_response() {
+    let m = mock! {
+        delay: 3s,
+    };
+
+    let start = Instant::now();
+    let result = Request::get(m.url())
+        .total_timeout(Duration::from_millis(500))
+        .body(())
+        .unwrap()
+        .send();
+
+    assert_matches!(result, Err(e) if e == isahc::error::ErrorKind::Timeout);
+    assert!(start.elapsed() < Duration::from_secs(3));
+}
+
+#[test]
+fn total_timeout_includes_time_waiting_for_connection() {
+    let m = mock! {
+        delay: 2s,
+    };
+
+    let client = HttpClient::builder().max_connections(1).build().unwrap();
+
+    // Occupy the only connection allowed.
+    let handle = thread::spawn({
+        let client = client.clone();
+        let url = m.url();
+
+        move || client.get(url).unwrap().status()
+    });
+
+    thread::sleep(Duration::from_millis(100));
+
+    let start = Instant::now();
+    let result = client.send(
+        Request::get(m.url())
+            .total_timeout(Duration::from_millis(500))
+            .body(())
+            .unwrap(),
+    );
+
+    assert_matches!(result, Err(e) if e == isahc::error::ErrorKind::QueueTimeout);
+    assert!(start.elapsed() < Duration::from_secs(1));
+    assert_eq!(handle.join().unwrap(), 200);
+    assert_eq!(m.requests_received(), 1);
+}
+
+#[cfg(feature = "unstable-interceptors")]
+#[test]
+fn total_timeout_reached_before_request_is_sent() {
+    let m = mock!();
+
+    let client = HttpClient::builder()
+        .interceptor(isahc::interceptor!(request, cx, {
+            thread::sleep(Duration::from_millis(600));
+            cx.send(request).await
+        }))
+        .build()
+        .unwrap();
+
+    let result = client.send(
+        Request::get(m.url())
+            .total_timeout(Duration::from_millis(500))
+            .body(())
+            .unwrap(),
+    );
+
+    assert_matches!(result, Err(e) if e == isahc::error::ErrorKind::QueueTimeout);
+    assert_eq!(m.requests_received(), 0);
+}
+
+#[test]
+fn total_timeout_includes_response_body() {
+    struct SlowReader;
+
+    impl Read for SlowReader {
+        fn read(&mut self, _buf: &mut [u8]) -> io::Result<usize> {
+            thread::sleep(Duration::from_secs(2));
+            Ok(0)
+        }
+    }
+
+    let m = mock! {
+        _ => {
+            body_reader: Cursor::new(vec![0; 100_000]).chain(SlowReader),
+        },
+    };
+
+    let mut response = Request::get(m.url())
+        .total_timeout(Duration::from_millis(500))
+        .body(())
+        .unwrap()
+        .send()
+        .unwrap();
+
+    assert_eq!(
+        response.copy_to(std::io::sink()).unwrap_err().kind(),
+        std::io::ErrorKind::TimedOut
+    );
+}
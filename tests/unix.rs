@@ -2,7 +2,7 @@
 
 use isahc::{config::Dialer, prelude::*, Request};
 use std::{
-    io::{self, Write},
+    io::{self, Read, Write},
     os::unix::net::UnixListener,
     thread,
 };
@@ -40,3 +40,45 @@ fn send_request_to_unix_socket() {
 
     assert_eq!(response.text().unwrap(), "success\n");
 }
+
+#[test]
+#[rustfmt::skip]
+fn unix_socket_request_keeps_path_and_host() {
+    let temp_dir = TempDir::new().unwrap();
+    let socket_path = temp_dir.path().join("test.sock");
+    let listener = UnixListener::bind(&socket_path).unwrap();
+
+    let server = thread::spawn(move || {
+        let (mut stream, _) = listener.accept().unwrap();
+        let mut request = Vec::new();
+        let mut buf = [0; 1024];
+
+        while !request.ends_with(b"\r\n\r\n") {
+            match stream.read(&mut buf).unwrap() {
+                0 => break,
+                n => request.extend_from_slice(&buf[..n]),
+            }
+        }
+
+        stream.write_all(b"\
+            HTTP/1.1 204 No Content\r\n\
+            \r\n\
+        ").unwrap();
+
+        String::from_utf8(request).unwrap()
+    });
+
+    let response = Request::get("http://localhost/info?all=1")
+        .dial(Dialer::unix_socket(socket_path))
+        .body(())
+        .unwrap()
+        .send()
+        .unwrap();
+
+    assert_eq!(response.status(), 204);
+
+    let request = server.join().unwrap();
+
+    assert!(request.starts_with("GET /info?all=1 HTTP/1.1\r\n"));
+    assert!(request.to_lowercase().contains("\r\nhost: localhost\r\n"));
+}